@@ -4,119 +4,449 @@ const MODULE_NAME: &str = "audio";
 const MODULE_ID: u8 = 2;
 const MODULE_VERSION: &str = "1.0";
 
+/// Écart toléré entre le PTS déduit des échantillons et l'horloge de capture (µs)
+const PTS_RESYNC_US: u64 = 100_000;
+
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tokio::task::JoinHandle;
 use std::time::{Duration, Instant};
-use crossbeam::queue::SegQueue;
-use cpal::{SampleFormat, Stream};
+use cpal::SampleFormat;
 
-use crate::config::Config;
+use crate::capture::audio_backend::{
+    backend_from_config, AudioBackend, AudioCallback, AudioDevice, AudioFormat, AudioStream, DeviceSelector,
+};
+use crate::capture::dsp::{FilterChain, FilterKind};
+use crate::capture::clock::{frames_to_us, pts_now, CAPTURE_CLOCK};
+use crate::capture::meter::AudioMeter;
+use crate::capture::resample::{convert_channels, Resampler};
+use crate::config::{self, ConfigFile};
+use crate::error::ModuleError;
 use crate::metrics::{Metrics, ModuleType};
+use crate::utils::queue::{capacity_for_ram, BoundedQueue, DropPolicy};
+
+/// Frame audio au format de sortie configuré
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioFrame {
+    /// Échantillons entrelacés f32 [-1, 1]
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Horodatage du premier échantillon (ms depuis UNIX_EPOCH)
+    pub timestamp: u64,
+    /// PTS du premier échantillon (µs, horloge `CAPTURE_CLOCK`)
+    pub pts: u64,
+}
+
+impl AudioFrame {
+    /// Nombre d'échantillons par canal
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels.max(1) as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceEventKind {
+    /// Flux du micro ouvert au démarrage
+    Opened,
+    /// Le périphérique du micro a disparu
+    Lost,
+    /// Micro ouvert sur le périphérique par défaut faute du périphérique demandé
+    Fallback,
+    /// Retour sur le périphérique demandé (ou réouverture du périphérique par défaut
+    /// quand c'est lui qui est demandé)
+    Reattached,
+}
+
+/// Changement de périphérique du micro
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceEvent {
+    pub kind: DeviceEventKind,
+    /// Nom du périphérique ("default" pour le périphérique par défaut)
+    pub device: String,
+    /// Horodatage (ms depuis UNIX_EPOCH)
+    pub timestamp: u64,
+    /// PTS du changement (µs, horloge `CAPTURE_CLOCK`)
+    pub pts: u64,
+}
 
 pub struct AudioCapture {
+    /// Format de sortie : le flux du périphérique est converti vers ces valeurs
     sample_rate: u32,
+    channels: u16,
     buffer_size: usize,
+    /// Niveaux et spectre remontés dans les métriques
+    meter: AudioMeter,
+    /// Capture aussi le son du système (piste séparée)
+    loopback_enabled: bool,
+    /// Périphérique du micro demandé (`audio_input_device`)
+    device: DeviceSelector,
+    /// Période de surveillance du périphérique (débranchement, retour)
+    device_poll: Duration,
+    backend: Arc<dyn AudioBackend>,
     inner: Arc<AudioInner>,
     metrics: Option<Arc<Metrics>>,
 }
 
 struct AudioInner {
-    input_stream: Mutex<Option<Stream>>,
-    output_stream: Mutex<Option<Stream>>,
+    input_stream: Mutex<Option<Box<dyn AudioStream>>>,
+    output_stream: Mutex<Option<Box<dyn AudioStream>>>,
     running: Mutex<bool>,
-    frame_buffer: SegQueue<Vec<f32>>,
-    thread_handle: Mutex<Option<JoinHandle<()>>>,
+    /// En pause : les flux restent ouverts mais les frames sont jetées
+    paused: AtomicBool,
+    frame_buffer: BoundedQueue<AudioFrame>,
+    /// Format réel du flux d'entrée ouvert et format natif de ses échantillons
+    stream_format: std::sync::Mutex<Option<(AudioFormat, SampleFormat)>>,
+    // Piste loopback ("ce que l'on entend"), alimentée par output_stream
+    loopback_buffer: BoundedQueue<AudioFrame>,
+    loopback_format: std::sync::Mutex<Option<AudioFormat>>,
+    /// Filtres appliqués aux frames du micro (réglables pendant la capture)
+    dsp: std::sync::Mutex<FilterChain>,
+    /// Périphérique sur lequel le micro est ouvert (None = défaut)
+    active_device: std::sync::Mutex<Option<String>>,
+    device_events: BoundedQueue<DeviceEvent>,
+    watchdog: std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+/// Paramètres des callbacks de capture, clonés dans la tâche de surveillance
+#[derive(Clone)]
+struct FrameSinks {
+    inner: Arc<AudioInner>,
+    sample_rate: u32,
+    channels: u16,
+    buffer_size: usize,
+    meter: AudioMeter,
+    metrics: Option<Arc<Metrics>>,
+}
+
+/// Ouvre le micro sur le périphérique demandé, ou sur celui par défaut s'il est absent
+#[derive(Clone)]
+struct MicOpener {
+    backend: Arc<dyn AudioBackend>,
+    selector: DeviceSelector,
+    sinks: FrameSinks,
+}
+
+impl FrameSinks {
+    /// Callback qui convertit le flux au format de sortie (canaux puis fréquence),
+    /// le découpe en frames et alimente la file de la piste `module`
+    fn sink(&self, module: ModuleType) -> AudioCallback {
+        let inner = Arc::clone(&self.inner);
+        let (sample_rate, channels) = (self.sample_rate, self.channels);
+        let frame_len = self.buffer_size * channels as usize;
+        let metrics = self.metrics.clone();
+        let meter = self.meter.clone();
+        let mut resampler: Option<Resampler> = None;
+        let mut pending: Vec<f32> = Vec::new();
+        // PTS de référence et échantillons par canal émis depuis cette référence
+        let mut base_pts: Option<u64> = None;
+        let mut emitted: u64 = 0;
+        let mut last_fps_update = Instant::now();
+        let mut frame_count = 0;
+
+        Box::new(move |samples: &[f32], format: &AudioFormat| {
+            // Le rééchantillonneur suit la fréquence réelle du flux (recréé si elle change)
+            if resampler.as_ref().is_none_or(|r| r.from_rate() != format.sample_rate) {
+                resampler = Some(Resampler::new(format.sample_rate, sample_rate, channels));
+            }
+            let resampler = resampler.as_mut().unwrap();
+            let converted = convert_channels(samples, format.channels, channels);
+            let resampled = resampler.process(&converted);
+
+            // Le bloc reçu se termine à l'arrivée du callback. Le PTS avance au rythme
+            // des échantillons ; on se recale si l'écart avec l'horloge devient trop grand
+            // (reprise après une coupure, dérive de l'horloge du périphérique)
+            let frames_in = |len: usize| (len / channels as usize) as u64;
+            let block_start = CAPTURE_CLOCK.now().saturating_sub(frames_to_us(frames_in(resampled.len()), sample_rate));
+            let pending_frames = frames_in(pending.len());
+            let expected = base_pts.map(|base| base + frames_to_us(emitted + pending_frames, sample_rate));
+            if expected.is_none_or(|e| e.abs_diff(block_start) > PTS_RESYNC_US) {
+                base_pts = Some(block_start.saturating_sub(frames_to_us(pending_frames, sample_rate)));
+                emitted = 0;
+            }
+            pending.extend(resampled);
+
+            while pending.len() >= frame_len {
+                let pts = base_pts.unwrap_or(0) + frames_to_us(emitted, sample_rate);
+                emitted += frames_in(frame_len);
+                // En pause, le PTS continue d'avancer : la reprise reste alignée sur l'horloge
+                if inner.paused.load(Ordering::Relaxed) {
+                    pending.drain(..frame_len);
+                    continue;
+                }
+                let mut frame = AudioFrame {
+                    samples: pending.drain(..frame_len).collect(),
+                    sample_rate,
+                    channels,
+                    timestamp: CAPTURE_CLOCK.unix_millis(pts),
+                    pts,
+                };
+                // Niveaux mesurés avant filtrage : on surveille ce que capte le micro
+                if let Some(m) = &metrics {
+                    m.record_audio_levels(module, meter.measure(&frame));
+                }
+                if module == ModuleType::Audio {
+                    inner.dsp.lock().unwrap().process(&mut frame);
+                }
+                let dropped = inner.buffer_for(module).push(frame);
+                if let Some(m) = &metrics {
+                    m.add_dropped(module, dropped);
+                }
+                frame_count += 1;
+            }
+
+            // Mettre à jour les FPS toutes les 1 secondes
+            if last_fps_update.elapsed() >= Duration::from_secs(1) {
+                if let Some(m) = &metrics {
+                    m.update_fps(module, frame_count);
+                }
+                frame_count = 0;
+                last_fps_update = Instant::now();
+            }
+        })
+    }
+}
+
+impl MicOpener {
+    fn preferred(&self) -> Option<String> {
+        self.selector.resolve(&self.backend.input_devices())
+    }
+
+    fn open(&self) -> Result<(Box<dyn AudioStream>, Option<String>), ModuleError> {
+        match self.preferred() {
+            Some(name) => match self.backend.open_input_device(&name, self.sinks.sink(ModuleType::Audio)) {
+                Ok(stream) => return Ok((stream, Some(name))),
+                Err(e) => eprintln!("[{}] Cannot open input device '{}': {}", MODULE_NAME, name, e),
+            },
+            None if self.selector != DeviceSelector::Default => {
+                eprintln!("[{}] Input device {:?} not found, using default", MODULE_NAME, self.selector);
+            }
+            None => {}
+        }
+        self.backend.open_input(self.sinks.sink(ModuleType::Audio)).map(|stream| (stream, None))
+    }
+
+    /// Ouvre uniquement le périphérique demandé, sans repli
+    fn open_preferred(&self) -> Result<(Box<dyn AudioStream>, Option<String>), ModuleError> {
+        let name = self
+            .preferred()
+            .ok_or_else(|| ModuleError::CaptureError(format!("Input device {:?} not found", self.selector)))?;
+        let stream = self.backend.open_input_device(&name, self.sinks.sink(ModuleType::Audio))?;
+        Ok((stream, Some(name)))
+    }
+}
+
+impl AudioInner {
+    fn record_device_event(&self, kind: DeviceEventKind, device: Option<&str>) {
+        let device = device.unwrap_or("default").to_string();
+        eprintln!("[{}] Input device {:?}: {}", MODULE_NAME, kind, device);
+        let pts = pts_now();
+        let timestamp = CAPTURE_CLOCK.unix_millis(pts);
+        self.device_events.push(DeviceEvent { kind, device, timestamp, pts });
+    }
+
+    /// Surveillance : rouvre le micro si son périphérique a disparu, et revient
+    /// sur le périphérique demandé dès qu'il réapparaît
+    /// Le nouveau flux est ouvert avant de remplacer l'ancien : un flux vivant n'est
+    /// jamais lâché pour un périphérique qui refuse de s'ouvrir.
+    async fn check_input_device(&self, opener: &MicOpener, metrics: Option<&Arc<Metrics>>) {
+        let mut stream = self.input_stream.lock().await;
+        let alive = stream.as_ref().is_some_and(|s| s.is_alive());
+        let active = self.active_device.lock().unwrap().clone();
+
+        if alive && (active.is_some() || opener.preferred().is_none()) {
+            return;
+        }
+        if !alive && stream.take().is_some() {
+            self.record_device_event(DeviceEventKind::Lost, active.as_deref());
+        }
+
+        // Flux vivant sur le périphérique par défaut : seul le périphérique demandé le remplace
+        let opened = if alive { opener.open_preferred() } else { opener.open() };
+        match opened {
+            Ok((new_stream, device)) => {
+                let kind = if device.is_some() || opener.selector == DeviceSelector::Default {
+                    DeviceEventKind::Reattached
+                } else {
+                    DeviceEventKind::Fallback
+                };
+                *self.stream_format.lock().unwrap() = Some((new_stream.format(), new_stream.sample_format()));
+                *self.active_device.lock().unwrap() = device.clone();
+                *stream = Some(new_stream);
+                self.record_device_event(kind, device.as_deref());
+                if let Some(m) = metrics {
+                    m.add_device_switch(ModuleType::Audio);
+                }
+            }
+            // Nouvel essai au prochain tour, le flux courant reste en place
+            Err(e) if !alive && active.is_some() => eprintln!("[{}] Cannot reopen input stream: {}", MODULE_NAME, e),
+            Err(_) => {}
+        }
+    }
+
+    /// Buffer de la piste associée au module de métriques
+    fn buffer_for(&self, module: ModuleType) -> &BoundedQueue<AudioFrame> {
+        match module {
+            ModuleType::AudioLoopback => &self.loopback_buffer,
+            _ => &self.frame_buffer,
+        }
+    }
 }
 
 impl AudioCapture {
     pub fn new() -> Self {
-        let resources = Config::get_available_resources();
+        let file = config::CONFIG.lock().unwrap().file.clone();
+        Self::with_config(&file)
+    }
 
-        let sample_rate = if resources.cpu >= 4 { 48000 } else { 44100 };
-        let buffer_size = if resources.ram >= 8 { 2048 } else { 1024 };
+    /// Construit la capture à partir d'une config explicite (tests, outils)
+    pub fn with_config(file: &ConfigFile) -> Self {
+        // audio_sample_rate fait foi ; 0 = choix selon la machine
+        let sample_rate = match file.audio_sample_rate {
+            0 if file.cpu_cores >= 4 => 48000,
+            0 => 44100,
+            rate => rate,
+        };
+        let buffer_size = if file.ram_gb >= 8 { 2048 } else { 1024 };
 
+        let capacity = capacity_for_ram(file.audio_buffer_capacity, file.ram_gb, 64, 64, 4096);
+        let policy = DropPolicy::parse_or(&file.audio_drop_policy, DropPolicy::DropOldest);
         let inner = AudioInner {
             input_stream: Mutex::new(None),
             output_stream: Mutex::new(None),
             running: Mutex::new(false),
-            frame_buffer: SegQueue::new(),
-            thread_handle: Mutex::new(None),
+            paused: AtomicBool::new(false),
+            frame_buffer: BoundedQueue::new(capacity, policy),
+            stream_format: std::sync::Mutex::new(None),
+            loopback_buffer: BoundedQueue::new(capacity, policy),
+            loopback_format: std::sync::Mutex::new(None),
+            dsp: std::sync::Mutex::new(FilterChain::from_config(file)),
+            active_device: std::sync::Mutex::new(None),
+            device_events: BoundedQueue::new(64, DropPolicy::DropOldest),
+            watchdog: std::sync::Mutex::new(None),
         };
 
         Self {
             sample_rate,
+            channels: file.audio_channels.clamp(1, 8),
             buffer_size,
+            meter: AudioMeter::new(file.audio_spectrum_bands),
+            loopback_enabled: file.audio_loopback_enabled,
+            device: DeviceSelector::parse(&file.audio_input_device),
+            device_poll: Duration::from_millis(file.audio_device_poll_ms),
+            backend: backend_from_config(file),
             inner: Arc::new(inner),
             metrics: None,
         }
     }
 
+    /// Remplace le backend audio (effectif au prochain `start`)
+    pub fn set_backend(&mut self, backend: Arc<dyn AudioBackend>) {
+        self.backend = backend;
+    }
+
+    /// Active la piste loopback (effectif au prochain `start`)
+    pub fn set_loopback_enabled(&mut self, enabled: bool) {
+        self.loopback_enabled = enabled;
+    }
+
+    /// Choisit le périphérique du micro (effectif au prochain `start`)
+    pub fn set_input_device(&mut self, device: DeviceSelector) {
+        self.device = device;
+    }
+
     pub fn attach_metrics(&mut self, metrics: Arc<Metrics>) {
         self.metrics = Some(metrics);
     }
 
-    /// Démarre la capture audio (entrée + sortie) dans des threads séparés
+    /// Démarre la capture audio : ouvre le flux d'entrée du backend (périphérique
+    /// demandé ou par défaut), et le flux loopback si activé. Les blocs reçus sont
+    /// regroupés en frames de `buffer_size` échantillons par canal, une file par piste.
+    /// Une tâche surveille ensuite le périphérique du micro (débranchement, retour).
     /// Appelé uniquement par main.rs
     pub async fn start(&self) {
-        use cpal::traits::{DeviceTrait, HostTrait};
-        
-        let inner = Arc::clone(&self.inner);
-        *inner.running.lock().await = true;
+        eprintln!("[{}:{}] Audio capture v{} starting on {} (buffer: {}, loopback: {})",
+                  MODULE_NAME, MODULE_ID, MODULE_VERSION, self.backend.name(), self.buffer_size, self.loopback_enabled);
 
-        let host = cpal::default_host();
-        let device = host.default_input_device();
-
-        eprintln!("[{}:{}] Audio capture starting (sample_rate: {}, buffer: {})", 
-                  MODULE_NAME, MODULE_ID, self.sample_rate, self.buffer_size);
+        let mut opened = false;
+        let opener = MicOpener {
+            backend: Arc::clone(&self.backend),
+            selector: self.device.clone(),
+            sinks: self.sinks(),
+        };
 
-        let buffer_size = self.buffer_size;
-        let sample_rate = self.sample_rate;
-        let metrics = self.metrics.clone();
+        match opener.open() {
+            Ok((stream, device)) => {
+                let (format, sample_format) = (stream.format(), stream.sample_format());
+                eprintln!("[{}] Input stream opened ({} Hz, {} ch, {:?})",
+                          MODULE_NAME, format.sample_rate, format.channels, sample_format);
+                *self.inner.stream_format.lock().unwrap() = Some((format, sample_format));
+                *self.inner.input_stream.lock().await = Some(stream);
+                let kind = if device.is_none() && self.device != DeviceSelector::Default {
+                    DeviceEventKind::Fallback
+                } else {
+                    DeviceEventKind::Opened
+                };
+                self.inner.record_device_event(kind, device.as_deref());
+                *self.inner.active_device.lock().unwrap() = device;
+                opened = true;
+            }
+            Err(e) => eprintln!("[{}] Cannot open input stream: {}", MODULE_NAME, e),
+        }
 
-        let handle = tokio::spawn(async move {
-            if let Some(dev) = device {
-                if let Ok(desc) = dev.description() {
-                    eprintln!("[{}] Using device: {:?}", MODULE_NAME, desc);
+        if self.loopback_enabled {
+            match self.backend.open_loopback(self.sinks().sink(ModuleType::AudioLoopback)) {
+                Ok(stream) => {
+                    let format = stream.format();
+                    eprintln!("[{}] Loopback stream opened ({} Hz, {} ch)", MODULE_NAME, format.sample_rate, format.channels);
+                    *self.inner.loopback_format.lock().unwrap() = Some(format);
+                    *self.inner.output_stream.lock().await = Some(stream);
+                    opened = true;
                 }
+                Err(e) => eprintln!("[{}] Cannot open loopback stream: {}", MODULE_NAME, e),
             }
+        }
 
-            let expected_fps = (sample_rate / buffer_size as u32).max(1);
-            eprintln!("[{}] Expected audio FPS: {}", MODULE_NAME, expected_fps);
-            let mut last_fps_update = Instant::now();
-            let mut frame_count = 0;
-            
-            while *inner.running.lock().await {
-                // Simuler la lecture de frames audio avec le sample_rate et buffer_size
-                let frame = vec![0.0f32; buffer_size];
-                inner.frame_buffer.push(frame);
-                frame_count += 1;
-                
-                // Mettre à jour les FPS toutes les 1 secondes
-                if last_fps_update.elapsed() >= Duration::from_secs(1) {
-                    if let Some(m) = &metrics {
-                        m.update_fps(ModuleType::Audio, frame_count);
+        *self.inner.running.lock().await = opened;
+
+        if opened && !self.device_poll.is_zero() {
+            let inner = Arc::clone(&self.inner);
+            let metrics = self.metrics.clone();
+            let poll = self.device_poll;
+            let handle = tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(poll).await;
+                    if !*inner.running.lock().await {
+                        break;
                     }
-                    frame_count = 0;
-                    last_fps_update = Instant::now();
+                    inner.check_input_device(&opener, metrics.as_ref()).await;
                 }
-                
-                let sleep_duration = Duration::from_millis(
-                    (buffer_size as u64 * 1000) / sample_rate as u64
-                );
-                tokio::time::sleep(sleep_duration).await;
+            });
+            if let Some(old) = self.inner.watchdog.lock().unwrap().replace(handle) {
+                old.abort();
             }
-            eprintln!("[{}] Audio capture stopped", MODULE_NAME);
-        });
+        }
+    }
 
-        *self.inner.thread_handle.lock().await = Some(handle);
+    fn sinks(&self) -> FrameSinks {
+        FrameSinks {
+            inner: Arc::clone(&self.inner),
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            buffer_size: self.buffer_size,
+            meter: self.meter.clone(),
+            metrics: self.metrics.clone(),
+        }
     }
 
-    /// Stoppe la capture audio
+
+    /// Stoppe la capture audio (la destruction des flux arrête les callbacks)
     pub async fn stop(&self) {
-        let mut running = self.inner.running.lock().await;
-        *running = false;
-        drop(running);
+        *self.inner.running.lock().await = false;
+
+        if let Some(watchdog) = self.inner.watchdog.lock().unwrap().take() {
+            watchdog.abort();
+        }
 
         if let Some(stream) = self.inner.input_stream.lock().await.take() {
             drop(stream);
@@ -124,40 +454,117 @@ impl AudioCapture {
         if let Some(stream) = self.inner.output_stream.lock().await.take() {
             drop(stream);
         }
+    }
 
-        // Attendre que le thread se termine proprement
-        if let Some(h) = self.inner.thread_handle.lock().await.take() {
-            let _ = h.await;
-        }
+    pub async fn is_running(&self) -> bool {
+        *self.inner.running.lock().await
+    }
+
+    /// Format du flux d'entrée (None tant qu'il n'est pas ouvert)
+    pub fn stream_format(&self) -> Option<AudioFormat> {
+        self.inner.stream_format.lock().unwrap().map(|(format, _)| format)
     }
 
     /// Récupère la dernière frame audio
-    pub fn get_frame(&self) -> Option<Vec<f32>> {
+    pub fn get_frame(&self) -> Option<AudioFrame> {
         self.inner.frame_buffer.pop()
     }
 
+    /// Récupère la prochaine frame de la piste loopback (son du système)
+    pub fn get_loopback_frame(&self) -> Option<AudioFrame> {
+        self.inner.loopback_buffer.pop()
+    }
+
+    /// Frames en attente dans la file micro / loopback
+    pub fn buffer_len(&self) -> usize {
+        self.inner.frame_buffer.len()
+    }
+
+    pub fn loopback_buffer_len(&self) -> usize {
+        self.inner.loopback_buffer.len()
+    }
+
+    /// Format du flux loopback (None s'il n'est pas ouvert)
+    pub fn loopback_format(&self) -> Option<AudioFormat> {
+        *self.inner.loopback_format.lock().unwrap()
+    }
+
     pub fn get_current_fps(&self) -> u32 {
         (self.sample_rate / self.buffer_size as u32).max(1)
     }
 
+    /// Remplace la chaîne de filtres du micro (l'état des filtres repart de zéro)
+    pub fn set_filter_chain(&self, chain: FilterChain) {
+        *self.inner.dsp.lock().unwrap() = chain;
+    }
+
+    /// Contourne ou réactive un filtre à chaud ; false s'il n'est pas dans la chaîne
+    pub fn set_filter_bypass(&self, kind: FilterKind, bypassed: bool) -> bool {
+        self.inner.dsp.lock().unwrap().set_bypass(kind, bypassed)
+    }
+
+    pub fn filter_kinds(&self) -> Vec<FilterKind> {
+        self.inner.dsp.lock().unwrap().kinds()
+    }
+
+    /// Format des frames produites (après conversion)
+    pub fn output_format(&self) -> AudioFormat {
+        AudioFormat { sample_rate: self.sample_rate, channels: self.channels }
+    }
+
     /// Vide le buffer pour libérer de la mémoire
     pub fn clear_buffer(&self) {
-        while self.inner.frame_buffer.pop().is_some() {}
+        self.inner.frame_buffer.clear();
+        self.inner.loopback_buffer.clear();
+    }
+
+    /// Frames perdues faute de place dans le buffer
+    pub fn get_dropped(&self) -> u64 {
+        self.inner.frame_buffer.dropped()
+    }
+
+    pub fn buffer_capacity(&self) -> usize {
+        self.inner.frame_buffer.capacity()
     }
 
     /// Affiche les infos sur les devices disponibles (utilise DeviceTrait, HostTrait)
+    /// Périphériques d'entrée du backend (sélectionnables par nom ou index)
+    pub fn list_devices(&self) -> Vec<AudioDevice> {
+        self.backend.input_devices()
+    }
+
+    /// Suspend la capture sans fermer les flux (pas de coupure ni de bascule de périphérique)
+    pub fn pause(&self) {
+        self.inner.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.inner.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.inner.paused.load(Ordering::Relaxed)
+    }
+
+    /// Périphérique sur lequel le micro est ouvert (None = périphérique par défaut)
+    pub fn active_device(&self) -> Option<String> {
+        self.inner.active_device.lock().unwrap().clone()
+    }
+
+    /// Prochain changement de périphérique du micro (ouverture, perte, bascule, retour)
+    pub fn get_device_event(&self) -> Option<DeviceEvent> {
+        self.inner.device_events.pop()
+    }
+
     pub fn list_available_devices(&self) {
         use cpal::traits::{DeviceTrait, HostTrait};
         
         let host = cpal::default_host();
         
-        eprintln!("[{}] Available input devices:", MODULE_NAME);
-        if let Ok(devices) = host.input_devices() {
-            for device in devices {
-                if let Ok(desc) = device.description() {
-                    eprintln!("  - {:?}", desc);
-                }
-            }
+        eprintln!("[{}] Available input devices ({}):", MODULE_NAME, self.backend.name());
+        for device in self.list_devices() {
+            let default = if device.is_default { " (default)" } else { "" };
+            eprintln!("  #{} {}{}", device.index, device.name, default);
         }
         
         eprintln!("[{}] Available output devices:", MODULE_NAME);
@@ -170,20 +577,9 @@ impl AudioCapture {
         }
     }
 
-    /// Retourne le format d'échantillon (utilise SampleFormat, StreamTrait)
-    pub fn get_sample_format(&self) -> &'static str {
-        use cpal::traits::{DeviceTrait, HostTrait};
-        
-        let host = cpal::default_host();
-        let sample_fmt = SampleFormat::F32;
-        
-        if let Some(device) = host.default_input_device() {
-            if let Ok(desc) = device.description() {
-                eprintln!("[{}] Device: {:?}", MODULE_NAME, desc);
-            }
-        }
-        
-        eprintln!("[{}] Using format: {:?}", MODULE_VERSION, sample_fmt);
-        "F32"
+    /// Format natif des échantillons du micro ouvert (None tant qu'il n'est pas ouvert)
+    /// Le backend convertit tous les formats PCM en f32 avant la chaîne de traitement.
+    pub fn get_sample_format(&self) -> Option<SampleFormat> {
+        self.inner.stream_format.lock().unwrap().map(|(_, sample_format)| sample_format)
     }
 }
@@ -0,0 +1,529 @@
+// visualisation_module/src/capture/audio_backend.rs
+
+//! Backends audio pilotés par `AudioCapture`
+//! - `CpalBackend` : flux d'entrée réel via cpal, tout `SampleFormat` converti en f32
+//! - `MockAudioBackend` : formes d'onde déterministes, sans carte son (tests, CI)
+//!
+//! Chaque backend fournit le micro (`open_input`, ou `open_input_device` pour un
+//! périphérique nommé) et, s'il le peut, le son du système (`open_loopback`) comme
+//! flux séparé. Un flux dont le périphérique disparaît n'est plus `is_alive`.
+//!
+//! Les échantillons remis au callback sont entrelacés (L R L R ...) en f32 [-1, 1].
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Data, FromSample, SampleFormat, SizedSample, I24, U24};
+
+use crate::config::ConfigFile;
+use crate::error::ModuleError;
+
+/// Format effectif d'un flux ouvert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// Reçoit chaque bloc d'échantillons entrelacés du flux
+pub type AudioCallback = Box<dyn FnMut(&[f32], &AudioFormat) + Send>;
+
+/// Périphérique d'entrée énuméré par un backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioDevice {
+    pub index: usize,
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// Périphérique demandé dans `audio_input_device` : vide = défaut, `#2` ou `2` = index, sinon nom
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceSelector {
+    Default,
+    Name(String),
+    Index(usize),
+}
+
+impl DeviceSelector {
+    pub fn parse(value: &str) -> Self {
+        let value = value.trim();
+        if value.is_empty() || value.eq_ignore_ascii_case("default") {
+            return DeviceSelector::Default;
+        }
+        match value.strip_prefix('#').unwrap_or(value).parse::<usize>() {
+            Ok(index) => DeviceSelector::Index(index),
+            Err(_) => DeviceSelector::Name(value.to_string()),
+        }
+    }
+
+    /// Nom du périphérique demandé s'il est présent (nom exact d'abord, puis sous-chaîne, sans casse)
+    pub fn resolve(&self, devices: &[AudioDevice]) -> Option<String> {
+        match self {
+            DeviceSelector::Default => None,
+            DeviceSelector::Index(index) => devices.iter().find(|d| d.index == *index).map(|d| d.name.clone()),
+            DeviceSelector::Name(name) => {
+                let wanted = name.to_lowercase();
+                devices
+                    .iter()
+                    .find(|d| d.name.to_lowercase() == wanted)
+                    .or_else(|| devices.iter().find(|d| d.name.to_lowercase().contains(&wanted)))
+                    .map(|d| d.name.clone())
+            }
+        }
+    }
+}
+
+/// Flux ouvert : la capture s'arrête quand il est détruit
+pub trait AudioStream: Send {
+    fn format(&self) -> AudioFormat;
+
+    /// Format natif des échantillons du périphérique (convertis en f32 avant le callback)
+    fn sample_format(&self) -> SampleFormat {
+        SampleFormat::F32
+    }
+
+    /// false si le périphérique a disparu (le flux ne produit plus rien)
+    fn is_alive(&self) -> bool {
+        true
+    }
+}
+
+pub trait AudioBackend: Send + Sync {
+    fn name(&self) -> String;
+
+    /// Périphériques d'entrée disponibles
+    fn input_devices(&self) -> Vec<AudioDevice> {
+        Vec::new()
+    }
+
+    /// Ouvre le flux d'entrée par défaut et commence à appeler `callback`
+    fn open_input(&self, callback: AudioCallback) -> Result<Box<dyn AudioStream>, ModuleError>;
+
+    /// Ouvre le flux d'entrée du périphérique `name` (tel que listé par `input_devices`)
+    fn open_input_device(&self, name: &str, _callback: AudioCallback) -> Result<Box<dyn AudioStream>, ModuleError> {
+        Err(ModuleError::CaptureError(format!("Device selection not supported by {} ({})", self.name(), name)))
+    }
+
+    /// Ouvre le flux loopback (son joué par le système)
+    fn open_loopback(&self, _callback: AudioCallback) -> Result<Box<dyn AudioStream>, ModuleError> {
+        Err(ModuleError::CaptureError(format!("Loopback not supported by {}", self.name())))
+    }
+}
+
+/// Backend sélectionné via `audio_backend` dans la config
+pub fn backend_from_config(file: &ConfigFile) -> Arc<dyn AudioBackend> {
+    match file.audio_backend.to_lowercase().as_str() {
+        "mock" | "synthetic" => Arc::new(MockAudioBackend::new(
+            AudioFormat { sample_rate: if file.audio_sample_rate == 0 { 48000 } else { file.audio_sample_rate }, channels: 2 },
+            Waveform::Sine { frequency: 440.0, amplitude: 0.5 },
+        )
+        .with_loopback(Waveform::Sine { frequency: 220.0, amplitude: 0.25 })),
+        _ => Arc::new(CpalBackend),
+    }
+}
+
+// --- Backend cpal ---
+
+pub struct CpalBackend;
+
+struct CpalStream {
+    _stream: cpal::Stream,
+    format: AudioFormat,
+    sample_format: SampleFormat,
+    /// Passe à false quand cpal signale la disparition du périphérique
+    alive: Arc<AtomicBool>,
+}
+
+impl AudioStream for CpalStream {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn sample_format(&self) -> SampleFormat {
+        self.sample_format
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Relaxed)
+    }
+}
+
+/// Convertit un bloc cpal en f32 quel que soit le format du périphérique
+/// Renvoie None pour les formats non PCM (DSD)
+pub fn data_to_f32(data: &Data) -> Option<Vec<f32>> {
+    fn convert<T>(data: &Data) -> Option<Vec<f32>>
+    where
+        T: SizedSample,
+        f32: FromSample<T>,
+    {
+        Some(data.as_slice::<T>()?.iter().map(|s| s.to_sample::<f32>()).collect())
+    }
+
+    match data.sample_format() {
+        SampleFormat::I8 => convert::<i8>(data),
+        SampleFormat::I16 => convert::<i16>(data),
+        SampleFormat::I24 => convert::<I24>(data),
+        SampleFormat::I32 => convert::<i32>(data),
+        SampleFormat::I64 => convert::<i64>(data),
+        SampleFormat::U8 => convert::<u8>(data),
+        SampleFormat::U16 => convert::<u16>(data),
+        SampleFormat::U24 => convert::<U24>(data),
+        SampleFormat::U32 => convert::<u32>(data),
+        SampleFormat::U64 => convert::<u64>(data),
+        SampleFormat::F32 => convert::<f32>(data),
+        SampleFormat::F64 => convert::<f64>(data),
+        _ => None,
+    }
+}
+
+impl CpalBackend {
+    /// Noms usuels des sources "ce que l'on entend" exposées comme entrées
+    /// (moniteurs PulseAudio/PipeWire, Stereo Mix, périphériques loopback)
+    const LOOPBACK_HINTS: [&'static str; 4] = ["monitor", "loopback", "stereo mix", "what u hear"];
+
+    /// Ouvre un flux d'entrée sur `device` avec la config donnée, converti en f32
+    fn open_stream(
+        device: &cpal::Device,
+        supported: cpal::SupportedStreamConfig,
+        mut callback: AudioCallback,
+    ) -> Result<Box<dyn AudioStream>, ModuleError> {
+        let sample_format = supported.sample_format();
+        if matches!(sample_format, SampleFormat::DsdU8 | SampleFormat::DsdU16 | SampleFormat::DsdU32) {
+            return Err(ModuleError::CaptureError(format!("Unsupported sample format {:?}", sample_format)));
+        }
+        let config = supported.config();
+        let format = AudioFormat { sample_rate: config.sample_rate, channels: config.channels };
+
+        if let Ok(desc) = device.description() {
+            eprintln!("[audio] Device: {:?} ({:?}, {} Hz, {} ch)", desc, sample_format, format.sample_rate, format.channels);
+        }
+
+        let alive = Arc::new(AtomicBool::new(true));
+        let alive_err = Arc::clone(&alive);
+        let stream = device
+            .build_input_stream_raw(
+                &config,
+                sample_format,
+                move |data: &Data, _| {
+                    if let Some(samples) = data_to_f32(data) {
+                        callback(&samples, &format);
+                    }
+                },
+                move |e| {
+                    eprintln!("[audio] Stream error: {}", e);
+                    if matches!(e, cpal::StreamError::DeviceNotAvailable | cpal::StreamError::StreamInvalidated) {
+                        alive_err.store(false, Ordering::Relaxed);
+                    }
+                },
+                None,
+            )
+            .map_err(|e| ModuleError::CaptureError(format!("Cannot build input stream: {}", e)))?;
+        stream
+            .play()
+            .map_err(|e| ModuleError::CaptureError(format!("Cannot start input stream: {}", e)))?;
+
+        Ok(Box::new(CpalStream { _stream: stream, format, sample_format, alive }))
+    }
+
+    fn device_name(device: &cpal::Device) -> Option<String> {
+        device.description().ok().map(|d| d.name().to_string())
+    }
+}
+
+impl AudioBackend for CpalBackend {
+    fn name(&self) -> String {
+        format!("cpal:{:?}", cpal::default_host().id())
+    }
+
+    fn input_devices(&self) -> Vec<AudioDevice> {
+        let host = cpal::default_host();
+        let default = host.default_input_device().and_then(|d| Self::device_name(&d));
+        let Ok(devices) = host.input_devices() else { return Vec::new() };
+        devices
+            .enumerate()
+            .filter_map(|(index, device)| {
+                let name = Self::device_name(&device)?;
+                Some(AudioDevice { index, is_default: default.as_deref() == Some(name.as_str()), name })
+            })
+            .collect()
+    }
+
+    fn open_input(&self, callback: AudioCallback) -> Result<Box<dyn AudioStream>, ModuleError> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or_else(|| ModuleError::CaptureError("No default audio input device".to_string()))?;
+        let supported = device
+            .default_input_config()
+            .map_err(|e| ModuleError::CaptureError(format!("Input config unavailable: {}", e)))?;
+        Self::open_stream(&device, supported, callback)
+    }
+
+    fn open_input_device(&self, name: &str, callback: AudioCallback) -> Result<Box<dyn AudioStream>, ModuleError> {
+        let device = cpal::default_host()
+            .input_devices()
+            .map_err(|e| ModuleError::CaptureError(format!("Cannot list input devices: {}", e)))?
+            .find(|d| Self::device_name(d).as_deref() == Some(name))
+            .ok_or_else(|| ModuleError::CaptureError(format!("Input device '{}' not found", name)))?;
+        let supported = device
+            .default_input_config()
+            .map_err(|e| ModuleError::CaptureError(format!("Input config unavailable: {}", e)))?;
+        Self::open_stream(&device, supported, callback)
+    }
+
+    fn open_loopback(&self, callback: AudioCallback) -> Result<Box<dyn AudioStream>, ModuleError> {
+        let host = cpal::default_host();
+
+        // 1. Source moniteur exposée comme entrée (Linux, Stereo Mix sous Windows)
+        let monitor = host.input_devices().ok().and_then(|mut devices| {
+            devices.find(|d| {
+                Self::device_name(d).is_some_and(|name| {
+                    let name = name.to_lowercase();
+                    Self::LOOPBACK_HINTS.iter().any(|hint| name.contains(hint))
+                })
+            })
+        });
+        if let Some(device) = monitor {
+            if let Ok(supported) = device.default_input_config() {
+                return Self::open_stream(&device, supported, callback);
+            }
+        }
+
+        // 2. Flux d'entrée sur le périphérique de sortie (loopback WASAPI)
+        let device = host
+            .default_output_device()
+            .ok_or_else(|| ModuleError::CaptureError("No loopback source or output device".to_string()))?;
+        let supported = device
+            .default_output_config()
+            .map_err(|e| ModuleError::CaptureError(format!("Output config unavailable: {}", e)))?;
+        Self::open_stream(&device, supported, callback)
+    }
+}
+
+// --- Backend mock ---
+
+/// Signal produit par `MockAudioBackend` (identique sur tous les canaux pour `Sine`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Waveform {
+    Sine { frequency: f32, amplitude: f32 },
+    /// Échantillons entrelacés rejoués en boucle
+    Samples(Vec<f32>),
+}
+
+/// Périphériques d'entrée simulés, branchables et débranchables pendant la capture
+/// Le premier de la liste est le périphérique par défaut.
+#[derive(Debug, Clone, Default)]
+pub struct MockDevices {
+    present: Arc<Mutex<Vec<String>>>,
+    /// Listés mais impossibles à ouvrir (occupés par une autre application)
+    busy: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockDevices {
+    pub fn new(names: &[&str]) -> Self {
+        Self {
+            present: Arc::new(Mutex::new(names.iter().map(|n| n.to_string()).collect())),
+            busy: Arc::default(),
+        }
+    }
+
+    pub fn plug(&self, name: &str) {
+        let mut devices = self.present.lock().unwrap();
+        if !devices.iter().any(|d| d == name) {
+            devices.push(name.to_string());
+        }
+    }
+
+    pub fn unplug(&self, name: &str) {
+        self.present.lock().unwrap().retain(|d| d != name);
+    }
+
+    /// Le périphérique reste listé mais son ouverture échoue tant qu'il est occupé
+    pub fn set_busy(&self, name: &str, busy: bool) {
+        let mut devices = self.busy.lock().unwrap();
+        devices.retain(|d| d != name);
+        if busy {
+            devices.push(name.to_string());
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.present.lock().unwrap().iter().any(|d| d == name)
+    }
+
+    pub fn is_busy(&self, name: &str) -> bool {
+        self.busy.lock().unwrap().iter().any(|d| d == name)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.present.lock().unwrap().clone()
+    }
+}
+
+pub struct MockAudioBackend {
+    format: AudioFormat,
+    waveform: Waveform,
+    devices: MockDevices,
+    /// Signal du flux loopback (None = loopback indisponible)
+    loopback: Option<Waveform>,
+    /// Frames (échantillons par canal) livrées par appel du callback
+    chunk_frames: usize,
+}
+
+impl MockAudioBackend {
+    pub fn new(format: AudioFormat, waveform: Waveform) -> Self {
+        Self { format, waveform, devices: MockDevices::new(&["Mock Microphone"]), loopback: None, chunk_frames: 256 }
+    }
+
+    /// Remplace la liste des périphériques simulés (partagée avec le handle)
+    pub fn with_devices(mut self, devices: MockDevices) -> Self {
+        self.devices = devices;
+        self
+    }
+
+    /// Handle pour brancher / débrancher des périphériques pendant la capture
+    pub fn devices(&self) -> MockDevices {
+        self.devices.clone()
+    }
+
+    /// Fournit aussi un flux loopback avec ce signal
+    pub fn with_loopback(mut self, waveform: Waveform) -> Self {
+        self.loopback = Some(waveform);
+        self
+    }
+
+    pub fn with_chunk_frames(mut self, chunk_frames: usize) -> Self {
+        self.chunk_frames = chunk_frames.max(1);
+        self
+    }
+
+    /// Échantillons entrelacés à partir de la frame `start` (rendu déterministe)
+    pub fn render(&self, start: u64, frames: usize) -> Vec<f32> {
+        let channels = self.format.channels.max(1) as usize;
+        let mut out = Vec::with_capacity(frames * channels);
+        match &self.waveform {
+            Waveform::Sine { frequency, amplitude } => {
+                let step = *frequency as f64 / self.format.sample_rate.max(1) as f64;
+                for n in start..start + frames as u64 {
+                    let phase = (n as f64 * step).fract();
+                    let value = (phase * std::f64::consts::TAU).sin() as f32 * amplitude;
+                    out.extend(std::iter::repeat_n(value, channels));
+                }
+            }
+            Waveform::Samples(samples) if !samples.is_empty() => {
+                let offset = start as usize * channels;
+                out.extend((0..frames * channels).map(|i| samples[(offset + i) % samples.len()]));
+            }
+            Waveform::Samples(_) => out.resize(frames * channels, 0.0),
+        }
+        out
+    }
+}
+
+struct MockStream {
+    format: AudioFormat,
+    stop: Arc<AtomicBool>,
+    /// Faux dès que le périphérique simulé est débranché
+    alive: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl AudioStream for MockStream {
+    fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for MockStream {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl MockAudioBackend {
+    /// Lance le thread générateur du signal `waveform`, lié au périphérique `device` s'il est donné
+    fn spawn(
+        &self,
+        waveform: Waveform,
+        device: Option<String>,
+        mut callback: AudioCallback,
+    ) -> Result<Box<dyn AudioStream>, ModuleError> {
+        if self.format.sample_rate == 0 || self.format.channels == 0 {
+            return Err(ModuleError::ConfigError("Mock audio format must be non-zero".to_string()));
+        }
+        let generator = MockAudioBackend::new(self.format, waveform).with_chunk_frames(self.chunk_frames);
+        let format = self.format;
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_thread = Arc::clone(&stop);
+        let alive = Arc::new(AtomicBool::new(true));
+        let alive_thread = Arc::clone(&alive);
+        let devices = self.devices.clone();
+
+        // Cadence temps réel : un bloc toutes les chunk_frames / sample_rate secondes
+        let handle = std::thread::spawn(move || {
+            let period = Duration::from_secs_f64(generator.chunk_frames as f64 / format.sample_rate as f64);
+            let mut position = 0u64;
+            while !stop_thread.load(Ordering::Relaxed) {
+                // Périphérique débranché : le flux s'interrompt comme un vrai pilote
+                if device.as_deref().is_some_and(|d| !devices.contains(d)) {
+                    alive_thread.store(false, Ordering::Relaxed);
+                    break;
+                }
+                let chunk = generator.render(position, generator.chunk_frames);
+                callback(&chunk, &format);
+                position += generator.chunk_frames as u64;
+                std::thread::sleep(period);
+            }
+        });
+
+        Ok(Box::new(MockStream { format, stop, alive, handle: Some(handle) }))
+    }
+}
+
+impl AudioBackend for MockAudioBackend {
+    fn name(&self) -> String {
+        "mock".to_string()
+    }
+
+    fn input_devices(&self) -> Vec<AudioDevice> {
+        self.devices
+            .names()
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| AudioDevice { index, name, is_default: index == 0 })
+            .collect()
+    }
+
+    fn open_input(&self, callback: AudioCallback) -> Result<Box<dyn AudioStream>, ModuleError> {
+        let default = self.devices.names().into_iter().next();
+        match default {
+            Some(name) => self.spawn(self.waveform.clone(), Some(name), callback),
+            None => Err(ModuleError::CaptureError("Mock backend has no input device".to_string())),
+        }
+    }
+
+    fn open_input_device(&self, name: &str, callback: AudioCallback) -> Result<Box<dyn AudioStream>, ModuleError> {
+        if !self.devices.contains(name) {
+            return Err(ModuleError::CaptureError(format!("Input device '{}' not found", name)));
+        }
+        if self.devices.is_busy(name) {
+            return Err(ModuleError::CaptureError(format!("Input device '{}' is busy", name)));
+        }
+        self.spawn(self.waveform.clone(), Some(name.to_string()), callback)
+    }
+
+    fn open_loopback(&self, callback: AudioCallback) -> Result<Box<dyn AudioStream>, ModuleError> {
+        match &self.loopback {
+            Some(waveform) => self.spawn(waveform.clone(), None, callback),
+            None => Err(ModuleError::CaptureError("Mock backend has no loopback source".to_string())),
+        }
+    }
+}
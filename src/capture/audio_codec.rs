@@ -0,0 +1,624 @@
+// visualisation_module/src/capture/audio_codec.rs
+
+//! Codecs audio des paquets envoyés au pool, sélectionnés via
+//! `audio_compression` / `audio_bitrate`
+//! - `gzip`     : PCM16 compressé GZIP (ancien format)
+//! - `lossless` : prédiction fixe d'ordre 0-4 + codage de Rice (façon FLAC), PCM16 exact
+//! - `adpcm`    : IMA ADPCM 4 bits par échantillon
+//! - `lossy`    : DPCM quantifié en boucle fermée, pas ajusté par bloc pour tenir `audio_bitrate`
+//!
+//! Les frames jugées silencieuses par la VAD sont remplacées par un marqueur
+//! (codec `Silence`, corps = niveau RMS f32 LE) que le décodeur restitue en bruit
+//! de confort de même durée : la chronologie reste continue côté pool.
+//!
+//! Format d'un paquet audio :
+//! version u8 | piste u8 | canaux u8 | fréquence u32 LE | codec u8 | bits u8 |
+//! bloc u16 LE | débit kbps u16 LE | frames u32 LE | pts u64 LE | corps encodé
+
+use std::io::{Read, Write};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use crate::capture::mixer::MixedFrame;
+use crate::config::ConfigFile;
+use crate::error::ModuleError;
+
+/// Version de l'en-tête des paquets audio
+pub const AUDIO_PACKET_VERSION: u8 = 0x06;
+pub const AUDIO_HEADER_LEN: usize = 25;
+
+/// Formats acceptés au décodage : protège le pool d'un en-tête corrompu
+const MIN_SAMPLE_RATE: u32 = 8_000;
+const MAX_SAMPLE_RATE: u32 = 192_000;
+const MAX_CHANNELS: usize = 8;
+/// Taux de compression maximal de deflate : borne la taille décompressée d'un corps GZIP
+const GZIP_MAX_RATIO: usize = 1032;
+
+/// Codec utilisé pour le corps d'un paquet audio (identifiant écrit dans l'en-tête)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioCodec {
+    Gzip = 0,
+    Lossless = 1,
+    Adpcm = 2,
+    Lossy = 3,
+    /// Marqueur de silence (jamais choisi via la config)
+    Silence = 4,
+}
+
+impl AudioCodec {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(AudioCodec::Gzip),
+            1 => Some(AudioCodec::Lossless),
+            2 => Some(AudioCodec::Adpcm),
+            3 => Some(AudioCodec::Lossy),
+            4 => Some(AudioCodec::Silence),
+            _ => None,
+        }
+    }
+
+    /// Nom tel qu'écrit dans `audio_compression`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "gzip" | "flate2" | "pcm" => Some(AudioCodec::Gzip),
+            "lossless" | "flac" => Some(AudioCodec::Lossless),
+            "adpcm" | "ima" | "ima-adpcm" => Some(AudioCodec::Adpcm),
+            "lossy" | "dpcm" => Some(AudioCodec::Lossy),
+            _ => None,
+        }
+    }
+
+    /// Bits par échantillon écrits dans l'en-tête (0 = variable)
+    fn bits(&self) -> u8 {
+        match self {
+            AudioCodec::Gzip | AudioCodec::Lossless => 16,
+            AudioCodec::Adpcm => 4,
+            AudioCodec::Lossy | AudioCodec::Silence => 0,
+        }
+    }
+
+    /// Frames par bloc : un bloc = un jeu de paramètres (ordre, Rice, pas...)
+    fn block_frames(&self) -> u16 {
+        match self {
+            AudioCodec::Gzip | AudioCodec::Silence => 0,
+            AudioCodec::Lossless => 4096,
+            AudioCodec::Adpcm => 1024,
+            AudioCodec::Lossy => 256,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AudioEncoder {
+    codec: AudioCodec,
+    /// Débit cible en kbps (utilisé par le codec avec perte)
+    bitrate: u16,
+}
+
+impl AudioEncoder {
+    pub fn new(codec: AudioCodec, bitrate: u32) -> Self {
+        Self {
+            codec,
+            bitrate: bitrate.clamp(8, 1536) as u16,
+        }
+    }
+
+    pub fn from_config(file: &ConfigFile) -> Self {
+        let codec = AudioCodec::from_name(&file.audio_compression).unwrap_or_else(|| {
+            eprintln!("[audio_codec] Unknown audio_compression '{}', using lossless", file.audio_compression);
+            AudioCodec::Lossless
+        });
+        Self::new(codec, file.audio_bitrate)
+    }
+
+    pub fn codec(&self) -> AudioCodec {
+        self.codec
+    }
+
+    pub fn bitrate(&self) -> u16 {
+        self.bitrate
+    }
+
+    /// Encode une frame du mixeur en paquet complet (en-tête + corps)
+    pub fn encode_packet(&self, frame: &MixedFrame) -> Result<Vec<u8>, ModuleError> {
+        let channels = frame.channels as usize;
+        if channels == 0 || channels > u8::MAX as usize || !frame.samples.len().is_multiple_of(channels) {
+            return Err(ModuleError::ValidationError(format!(
+                "Audio frame of {} samples does not match {} channels",
+                frame.samples.len(),
+                frame.channels
+            )));
+        }
+        let frames = frame.samples.len() / channels;
+        let pcm: Vec<i16> = frame.samples.iter().map(|s| (s.clamp(-1.0, 1.0) * 32767.0) as i16).collect();
+        let block = self.codec.block_frames() as usize;
+
+        let body = match self.codec {
+            AudioCodec::Gzip => {
+                let bytes: Vec<u8> = pcm.iter().flat_map(|s| s.to_le_bytes()).collect();
+                gzip(&bytes)?
+            }
+            AudioCodec::Lossless => encode_blocks(&pcm, channels, block, encode_lossless),
+            AudioCodec::Adpcm => encode_blocks(&pcm, channels, block, encode_adpcm),
+            AudioCodec::Lossy => {
+                // Budget de bits d'un bloc d'un canal pour tenir le débit
+                let rate = frame.sample_rate.max(1) as u64;
+                let budget = (self.bitrate as u64 * 1000 * block as u64 / rate / channels as u64) as usize;
+                encode_blocks(&pcm, channels, block, |w, s| encode_lossy(w, s, budget))
+            }
+            AudioCodec::Silence => return Ok(self.encode_silence(frame, 0.0)),
+        };
+
+        let mut packet = self.header(self.codec, frame, frames);
+        packet.extend(body);
+        Ok(packet)
+    }
+
+    /// Marqueur remplaçant une frame silencieuse : durée et niveau du bruit de fond
+    pub fn encode_silence(&self, frame: &MixedFrame, rms: f32) -> Vec<u8> {
+        let frames = frame.samples.len() / frame.channels.max(1) as usize;
+        let mut packet = self.header(AudioCodec::Silence, frame, frames);
+        packet.extend_from_slice(&rms.max(0.0).to_le_bytes());
+        packet
+    }
+
+    /// Indique si un paquet est un marqueur de silence
+    pub fn is_silence(packet: &[u8]) -> bool {
+        packet.len() > 7 && packet[0] == AUDIO_PACKET_VERSION && packet[7] == AudioCodec::Silence as u8
+    }
+
+    fn header(&self, codec: AudioCodec, frame: &MixedFrame, frames: usize) -> Vec<u8> {
+        let mut header = Vec::with_capacity(AUDIO_HEADER_LEN);
+        header.extend_from_slice(&[AUDIO_PACKET_VERSION, frame.track_id, frame.channels as u8]);
+        header.extend_from_slice(&frame.sample_rate.to_le_bytes());
+        header.push(codec as u8);
+        header.push(codec.bits());
+        header.extend_from_slice(&codec.block_frames().to_le_bytes());
+        header.extend_from_slice(&self.bitrate.to_le_bytes());
+        header.extend_from_slice(&(frames as u32).to_le_bytes());
+        header.extend_from_slice(&frame.pts.to_le_bytes());
+        header
+    }
+
+    /// Décode un paquet produit par `encode_packet` (côté pool)
+    pub fn decode_packet(packet: &[u8]) -> Result<MixedFrame, ModuleError> {
+        if packet.len() < AUDIO_HEADER_LEN {
+            return Err(ModuleError::ValidationError("Audio packet truncated".to_string()));
+        }
+        if packet[0] != AUDIO_PACKET_VERSION {
+            return Err(ModuleError::ValidationError(format!("Unsupported audio packet version {}", packet[0])));
+        }
+        let track_id = packet[1];
+        let channels = packet[2] as usize;
+        let sample_rate = u32::from_le_bytes([packet[3], packet[4], packet[5], packet[6]]);
+        let codec = AudioCodec::from_u8(packet[7])
+            .ok_or_else(|| ModuleError::ValidationError(format!("Unknown audio codec {}", packet[7])))?;
+        let block = u16::from_le_bytes([packet[9], packet[10]]) as usize;
+        let frames = u32::from_le_bytes([packet[13], packet[14], packet[15], packet[16]]) as usize;
+        let pts = u64::from_le_bytes(packet[17..25].try_into().unwrap());
+        if channels == 0 || sample_rate == 0 {
+            return Err(ModuleError::ValidationError("Audio packet without format".to_string()));
+        }
+        if channels > MAX_CHANNELS || !(MIN_SAMPLE_RATE..=MAX_SAMPLE_RATE).contains(&sample_rate) {
+            return Err(ModuleError::ValidationError(format!(
+                "Audio packet format out of range ({} Hz, {} ch)", sample_rate, channels
+            )));
+        }
+        // Une frame ne dépasse jamais la minute
+        let samples = frames
+            .checked_mul(channels)
+            .filter(|_| frames <= sample_rate as usize * 60)
+            .ok_or_else(|| ModuleError::ValidationError(format!("Audio packet of {} frames rejected", frames)))?;
+        if !matches!(codec, AudioCodec::Gzip | AudioCodec::Silence) && block == 0 {
+            return Err(ModuleError::ValidationError("Audio packet without block size".to_string()));
+        }
+        let body = &packet[AUDIO_HEADER_LEN..];
+
+        // Le corps doit pouvoir contenir `frames` : au moins un bit par échantillon pour
+        // les codecs par blocs, deux octets par échantillon avant compression pour GZIP
+        let fits = match codec {
+            AudioCodec::Silence => true,
+            AudioCodec::Gzip => samples * 2 <= body.len().saturating_mul(GZIP_MAX_RATIO),
+            _ => samples <= body.len().saturating_mul(8),
+        };
+        if !fits {
+            return Err(ModuleError::ValidationError(format!(
+                "Audio packet of {} frames does not fit its {}-byte body", frames, body.len()
+            )));
+        }
+
+        if codec == AudioCodec::Silence {
+            let level = body
+                .get(..4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .ok_or_else(|| ModuleError::ValidationError("Silence marker truncated".to_string()))?;
+            return Ok(MixedFrame {
+                track_id,
+                sample_rate,
+                channels: channels as u16,
+                pts,
+                samples: comfort_noise(samples, level),
+            });
+        }
+
+        let pcm = match codec {
+            AudioCodec::Gzip => {
+                // Lecture bornée : un octet de trop suffit à détecter un corps trop long
+                let mut bytes = Vec::new();
+                GzDecoder::new(body)
+                    .take(samples as u64 * 2 + 1)
+                    .read_to_end(&mut bytes)
+                    .map_err(|e| ModuleError::ValidationError(format!("Audio payload corrupted: {}", e)))?;
+                bytes.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect()
+            }
+            AudioCodec::Lossless => decode_blocks(body, channels, frames, block, decode_lossless)?,
+            AudioCodec::Adpcm => decode_blocks(body, channels, frames, block, decode_adpcm)?,
+            AudioCodec::Lossy => decode_blocks(body, channels, frames, block, decode_lossy)?,
+            AudioCodec::Silence => unreachable!("marqueur de silence traité plus haut"),
+        };
+        if pcm.len() != samples {
+            return Err(ModuleError::ValidationError("Audio payload length mismatch".to_string()));
+        }
+
+        Ok(MixedFrame {
+            track_id,
+            sample_rate,
+            channels: channels as u16,
+            pts,
+            samples: pcm.iter().map(|&s| s as f32 / 32767.0).collect(),
+        })
+    }
+}
+
+/// Bruit blanc déterministe de niveau RMS `level` (uniforme : amplitude = RMS x √3)
+fn comfort_noise(len: usize, level: f32) -> Vec<f32> {
+    if !level.is_finite() || level <= 0.0 {
+        return vec![0.0; len];
+    }
+    let amplitude = (level * 3f32.sqrt()).min(1.0);
+    let mut seed = 0x2545_f491u32;
+    (0..len)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            ((seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0) * amplitude
+        })
+        .collect()
+}
+
+fn gzip(data: &[u8]) -> Result<Vec<u8>, ModuleError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder.write_all(data).map_err(|e| ModuleError::IoError(e.to_string()))?;
+    encoder.finish().map_err(|e| ModuleError::IoError(e.to_string()))
+}
+
+// --- Découpage en blocs ---
+
+/// Encode chaque bloc de chaque canal (échantillons désentrelacés) à la suite
+fn encode_blocks(pcm: &[i16], channels: usize, block: usize, mut encode: impl FnMut(&mut BitWriter, &[i32])) -> Vec<u8> {
+    let frames = pcm.len() / channels;
+    let mut writer = BitWriter::default();
+    for start in (0..frames).step_by(block) {
+        let end = (start + block).min(frames);
+        for c in 0..channels {
+            let samples: Vec<i32> = (start..end).map(|f| pcm[f * channels + c] as i32).collect();
+            encode(&mut writer, &samples);
+        }
+    }
+    writer.finish()
+}
+
+fn decode_blocks(
+    body: &[u8],
+    channels: usize,
+    frames: usize,
+    block: usize,
+    decode: fn(&mut BitReader, usize) -> Option<Vec<i32>>,
+) -> Result<Vec<i16>, ModuleError> {
+    let truncated = || ModuleError::ValidationError("Audio payload truncated".to_string());
+    let mut reader = BitReader::new(body);
+    let mut pcm = vec![0i16; frames * channels];
+    for start in (0..frames).step_by(block) {
+        let len = block.min(frames - start);
+        for c in 0..channels {
+            let samples = decode(&mut reader, len).ok_or_else(truncated)?;
+            for (i, s) in samples.into_iter().enumerate() {
+                pcm[(start + i) * channels + c] = s.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+            }
+        }
+    }
+    Ok(pcm)
+}
+
+// --- Sans perte : prédicteurs fixes + Rice ---
+
+const MAX_ORDER: usize = 4;
+const MAX_RICE: u32 = 24;
+
+/// Prédiction fixe d'ordre `order` à partir des échantillons précédents
+fn predict(history: &[i32], order: usize) -> i32 {
+    let n = history.len();
+    let at = |k: usize| history[n - k];
+    match order {
+        0 => 0,
+        1 => at(1),
+        2 => 2 * at(1) - at(2),
+        3 => 3 * at(1) - 3 * at(2) + at(3),
+        _ => 4 * at(1) - 6 * at(2) + 4 * at(3) - at(4),
+    }
+}
+
+fn residuals(samples: &[i32], order: usize) -> Vec<i32> {
+    (order..samples.len()).map(|i| samples[i] - predict(&samples[..i], order)).collect()
+}
+
+fn zigzag(v: i32) -> u32 {
+    ((v << 1) ^ (v >> 31)) as u32
+}
+
+fn unzigzag(u: u32) -> i32 {
+    (u >> 1) as i32 ^ -((u & 1) as i32)
+}
+
+/// Paramètre de Rice minimisant la taille des valeurs
+fn best_rice(values: &[u32]) -> u32 {
+    (0..=MAX_RICE)
+        .min_by_key(|&k| values.iter().map(|&u| (u >> k) as u64 + 1 + k as u64).sum::<u64>())
+        .unwrap_or(0)
+}
+
+fn write_rice(writer: &mut BitWriter, values: &[u32]) {
+    let k = best_rice(values);
+    writer.write(k, 5);
+    for &u in values {
+        writer.write_unary(u >> k);
+        writer.write(u & ((1 << k) - 1), k);
+    }
+}
+
+fn read_rice(reader: &mut BitReader, count: usize) -> Option<Vec<u32>> {
+    let k = reader.read(5)?;
+    (0..count).map(|_| Some((reader.read_unary()? << k) | reader.read(k)?)).collect()
+}
+
+fn encode_lossless(writer: &mut BitWriter, samples: &[i32]) {
+    let order = (0..=MAX_ORDER.min(samples.len()))
+        .min_by_key(|&o| residuals(samples, o).iter().map(|r| r.unsigned_abs() as u64).sum::<u64>())
+        .unwrap_or(0);
+    writer.write(order as u32, 3);
+    for &s in &samples[..order] {
+        writer.write(s as u16 as u32, 16);
+    }
+    let values: Vec<u32> = residuals(samples, order).into_iter().map(zigzag).collect();
+    write_rice(writer, &values);
+}
+
+fn decode_lossless(reader: &mut BitReader, len: usize) -> Option<Vec<i32>> {
+    let order = reader.read(3)? as usize;
+    if order > MAX_ORDER || order > len {
+        return None;
+    }
+    let mut samples: Vec<i32> = (0..order).map(|_| Some(reader.read(16)? as u16 as i16 as i32)).collect::<Option<_>>()?;
+    for u in read_rice(reader, len - order)? {
+        let s = predict(&samples, order).wrapping_add(unzigzag(u));
+        samples.push(s.clamp(i16::MIN as i32, i16::MAX as i32));
+    }
+    Some(samples)
+}
+
+// --- IMA ADPCM ---
+
+const IMA_INDEX: [i32; 16] = [-1, -1, -1, -1, 2, 4, 6, 8, -1, -1, -1, -1, 2, 4, 6, 8];
+
+const IMA_STEPS: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66, 73, 80, 88, 97,
+    107, 118, 130, 143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408, 449, 494, 544, 598, 658, 724, 796,
+    876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066, 2272, 2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871,
+    5358, 5894, 6484, 7132, 7845, 8630, 9493, 10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623,
+    27086, 29794, 32767,
+];
+
+/// État du décodeur IMA, partagé par l'encodeur pour rester synchrone
+struct ImaState {
+    predictor: i32,
+    index: i32,
+}
+
+impl ImaState {
+    fn decode(&mut self, nibble: u32) -> i32 {
+        let step = IMA_STEPS[self.index as usize];
+        let mut diff = step >> 3;
+        if nibble & 4 != 0 {
+            diff += step;
+        }
+        if nibble & 2 != 0 {
+            diff += step >> 1;
+        }
+        if nibble & 1 != 0 {
+            diff += step >> 2;
+        }
+        self.predictor += if nibble & 8 != 0 { -diff } else { diff };
+        self.predictor = self.predictor.clamp(i16::MIN as i32, i16::MAX as i32);
+        self.index = (self.index + IMA_INDEX[nibble as usize]).clamp(0, 88);
+        self.predictor
+    }
+
+    fn encode(&mut self, sample: i32) -> u32 {
+        let step = IMA_STEPS[self.index as usize];
+        let mut diff = sample - self.predictor;
+        let mut nibble = 0;
+        if diff < 0 {
+            nibble = 8;
+            diff = -diff;
+        }
+        if diff >= step {
+            nibble |= 4;
+            diff -= step;
+        }
+        if diff >= step >> 1 {
+            nibble |= 2;
+            diff -= step >> 1;
+        }
+        if diff >= step >> 2 {
+            nibble |= 1;
+        }
+        self.decode(nibble);
+        nibble
+    }
+}
+
+/// Index de pas initial adapté à l'amplitude des premières variations du bloc
+fn initial_index(samples: &[i32]) -> i32 {
+    let diff = samples.windows(2).take(8).map(|w| (w[1] - w[0]).abs()).max().unwrap_or(0);
+    IMA_STEPS.iter().position(|&s| s >= diff / 2).unwrap_or(88) as i32
+}
+
+fn encode_adpcm(writer: &mut BitWriter, samples: &[i32]) {
+    let Some(&first) = samples.first() else { return };
+    let mut state = ImaState { predictor: first, index: initial_index(samples) };
+    writer.write(first as u16 as u32, 16);
+    writer.write(state.index as u32, 7);
+    for &s in &samples[1..] {
+        writer.write(state.encode(s), 4);
+    }
+}
+
+fn decode_adpcm(reader: &mut BitReader, len: usize) -> Option<Vec<i32>> {
+    if len == 0 {
+        return Some(Vec::new());
+    }
+    let first = reader.read(16)? as u16 as i16 as i32;
+    let index = reader.read(7)? as i32;
+    if index > 88 {
+        return None;
+    }
+    let mut state = ImaState { predictor: first, index };
+    let mut samples = Vec::with_capacity(len);
+    samples.push(first);
+    for _ in 1..len {
+        samples.push(state.decode(reader.read(4)?));
+    }
+    Some(samples)
+}
+
+// --- Avec perte : DPCM quantifié ---
+
+const MAX_SHIFT: u32 = 15;
+
+/// Quantifie un bloc en boucle fermée (prédiction d'ordre 2 sur les valeurs reconstruites)
+fn quantize(samples: &[i32], shift: u32) -> Vec<u32> {
+    let step = 1i32 << shift;
+    let (mut r1, mut r2) = (0i32, 0i32);
+    samples
+        .iter()
+        .map(|&x| {
+            let pred = (2 * r1 - r2).clamp(i16::MIN as i32, i16::MAX as i32);
+            let e = x - pred;
+            let q = if e >= 0 { (e + step / 2) >> shift } else { -((-e + step / 2) >> shift) };
+            (r2, r1) = (r1, (pred + q * step).clamp(i16::MIN as i32, i16::MAX as i32));
+            zigzag(q)
+        })
+        .collect()
+}
+
+fn rice_size(values: &[u32]) -> usize {
+    let k = best_rice(values);
+    5 + values.iter().map(|&u| (u >> k) as usize + 1 + k as usize).sum::<usize>()
+}
+
+/// Choisit le plus petit pas dont le bloc tient dans `budget` bits
+fn encode_lossy(writer: &mut BitWriter, samples: &[i32], budget: usize) {
+    let (shift, values) = (0..=MAX_SHIFT)
+        .map(|shift| (shift, quantize(samples, shift)))
+        .find(|(_, values)| 4 + rice_size(values) <= budget)
+        .unwrap_or_else(|| (MAX_SHIFT, quantize(samples, MAX_SHIFT)));
+    writer.write(shift, 4);
+    write_rice(writer, &values);
+}
+
+fn decode_lossy(reader: &mut BitReader, len: usize) -> Option<Vec<i32>> {
+    let shift = reader.read(4)?;
+    let step = 1i32 << shift;
+    let (mut r1, mut r2) = (0i32, 0i32);
+    let values = read_rice(reader, len)?;
+    Some(
+        values
+            .into_iter()
+            .map(|u| {
+                let pred = (2 * r1 - r2).clamp(i16::MIN as i32, i16::MAX as i32);
+                let value = pred.saturating_add(unzigzag(u).saturating_mul(step));
+                (r2, r1) = (r1, value.clamp(i16::MIN as i32, i16::MAX as i32));
+                r1
+            })
+            .collect(),
+    )
+}
+
+// --- Flux de bits (MSB d'abord) ---
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        if bits == 0 {
+            return;
+        }
+        self.acc = (self.acc << bits) | (value as u64 & ((1u64 << bits) - 1));
+        self.bits += bits;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.bytes.push((self.acc >> self.bits) as u8);
+        }
+    }
+
+    /// `n` zéros puis un 1
+    fn write_unary(&mut self, mut n: u32) {
+        while n >= 16 {
+            self.write(0, 16);
+            n -= 16;
+        }
+        self.write(1, n + 1);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push((self.acc << (8 - self.bits)) as u8);
+        }
+        self.bytes
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn bit(&mut self) -> Option<u32> {
+        let byte = *self.data.get(self.pos / 8)?;
+        let bit = (byte >> (7 - self.pos % 8)) & 1;
+        self.pos += 1;
+        Some(bit as u32)
+    }
+
+    fn read(&mut self, bits: u32) -> Option<u32> {
+        (0..bits).try_fold(0u32, |acc, _| Some((acc << 1) | self.bit()?))
+    }
+
+    fn read_unary(&mut self) -> Option<u32> {
+        let mut n = 0;
+        while self.bit()? == 0 {
+            n += 1;
+        }
+        Some(n)
+    }
+}
@@ -0,0 +1,70 @@
+// visualisation_module/src/capture/clock.rs
+
+//! Horloge de capture commune à l'écran, à l'audio et aux entrées
+//! - PTS : microsecondes monotones depuis le démarrage de l'horloge
+//! - ancre : heure murale (µs depuis UNIX_EPOCH) correspondant au PTS 0
+//!
+//! Les `timestamp` (ms murales) des frames et événements sont dérivés du PTS via
+//! l'ancre : `timestamp - pts / 1000` est constant pendant une session, ce qui
+//! permet au pool d'aligner les flux et de détecter un redémarrage du module.
+
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+lazy_static::lazy_static! {
+    /// Horloge partagée par tous les modules de capture
+    pub static ref CAPTURE_CLOCK: CaptureClock = CaptureClock::new();
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CaptureClock {
+    origin: Instant,
+    anchor_unix_us: u64,
+}
+
+impl Default for CaptureClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CaptureClock {
+    pub fn new() -> Self {
+        let anchor = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        Self { origin: Instant::now(), anchor_unix_us: anchor.as_micros() as u64 }
+    }
+
+    /// PTS courant (µs)
+    pub fn now(&self) -> u64 {
+        self.pts_at(Instant::now())
+    }
+
+    /// PTS d'un instant donné (0 s'il précède l'origine)
+    pub fn pts_at(&self, instant: Instant) -> u64 {
+        instant.saturating_duration_since(self.origin).as_micros() as u64
+    }
+
+    /// Heure murale du PTS 0 (µs depuis UNIX_EPOCH)
+    pub fn anchor_unix_us(&self) -> u64 {
+        self.anchor_unix_us
+    }
+
+    /// Heure murale (ms depuis UNIX_EPOCH) d'un PTS
+    pub fn unix_millis(&self, pts: u64) -> u64 {
+        (self.anchor_unix_us + pts) / 1000
+    }
+}
+
+/// PTS courant de l'horloge partagée
+pub fn pts_now() -> u64 {
+    CAPTURE_CLOCK.now()
+}
+
+/// Durée (µs) de `frames` échantillons par canal à `sample_rate`
+pub fn frames_to_us(frames: u64, sample_rate: u32) -> u64 {
+    (frames as u128 * 1_000_000 / sample_rate.max(1) as u128) as u64
+}
+
+/// Nombre d'échantillons par canal couverts par `us` à `sample_rate` (arrondi au plus proche)
+pub fn us_to_frames(us: u64, sample_rate: u32) -> u64 {
+    ((us as u128 * sample_rate as u128 + 500_000) / 1_000_000) as u64
+}
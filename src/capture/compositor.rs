@@ -0,0 +1,199 @@
+// visualisation_module/src/capture/compositor.rs
+
+//! Composition multi-écrans dans un canevas unique (équivalent d'une scène
+//! "capture d'écran" OBS couvrant tout le bureau)
+//! - `position`   : chaque écran à sa position réelle sur le bureau virtuel
+//! - `horizontal` : écrans côte à côte dans l'ordre des index
+//! - `vertical`   : écrans empilés dans l'ordre des index
+//!
+//! Le canevas garde la dernière frame de chaque écran et est toujours produit en BGRA.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::capture::frame::{PixelFormat, Rect, ScreenFrame};
+use crate::config::ConfigFile;
+
+/// `display_index` porté par les frames composites
+pub const COMPOSITE_DISPLAY: u16 = u16::MAX;
+
+/// Couleur des zones du canevas non couvertes par un écran (BGRA)
+const BACKGROUND: [u8; 4] = [0, 0, 0, 255];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CanvasLayout {
+    Position,
+    Horizontal,
+    Vertical,
+}
+
+impl CanvasLayout {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "position" | "desktop" => Some(CanvasLayout::Position),
+            "horizontal" => Some(CanvasLayout::Horizontal),
+            "vertical" => Some(CanvasLayout::Vertical),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct DisplaySlot {
+    frame: ScreenFrame,
+    /// Décalage de la zone capturée dans l'écran (découpe ROI)
+    offset: (u32, u32),
+}
+
+#[derive(Clone)]
+pub struct Compositor {
+    layout: CanvasLayout,
+    scale: f32,
+    /// Origine de chaque écran sur le bureau virtuel
+    origins: HashMap<u16, (i32, i32)>,
+    /// Origines fixées par la config, jamais écrasées par la détection
+    configured: Vec<u16>,
+    slots: BTreeMap<u16, DisplaySlot>,
+}
+
+impl Compositor {
+    pub fn new(layout: CanvasLayout, scale: f32) -> Self {
+        Self {
+            layout,
+            scale: if scale.is_finite() && scale > 0.0 { scale.min(4.0) } else { 1.0 },
+            origins: HashMap::new(),
+            configured: Vec::new(),
+            slots: BTreeMap::new(),
+        }
+    }
+
+    pub fn from_config(file: &ConfigFile) -> Self {
+        let layout = CanvasLayout::from_name(&file.screen_layout).unwrap_or_else(|| {
+            eprintln!("[compositor] Unknown screen_layout '{}', using position", file.screen_layout);
+            CanvasLayout::Position
+        });
+        let mut compositor = Self::new(layout, file.screen_scale);
+        for p in &file.screen_positions {
+            compositor.set_origin(p.display, p.x, p.y);
+        }
+        compositor
+    }
+
+    pub fn layout(&self) -> CanvasLayout {
+        self.layout
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Fixe l'origine d'un écran (prioritaire sur la détection du backend)
+    pub fn set_origin(&mut self, display: u16, x: i32, y: i32) {
+        self.origins.insert(display, (x, y));
+        if !self.configured.contains(&display) {
+            self.configured.push(display);
+        }
+    }
+
+    /// Origine remontée par le backend, ignorée si la config en fixe une
+    pub fn detect_origin(&mut self, display: u16, origin: Option<(i32, i32)>) {
+        if let Some(origin) = origin {
+            if !self.configured.contains(&display) {
+                self.origins.insert(display, origin);
+            }
+        }
+    }
+
+    /// Enregistre la dernière frame d'un écran, `region` étant la découpe appliquée
+    pub fn push(&mut self, frame: ScreenFrame, region: Option<&Rect>) {
+        let offset = region.map_or((0, 0), |r| (r.x, r.y));
+        self.slots.insert(frame.display_index, DisplaySlot { frame, offset });
+    }
+
+    /// Oublie les écrans qui ne sont plus capturés
+    pub fn retain(&mut self, keep: impl Fn(u16) -> bool) {
+        self.slots.retain(|display, _| keep(*display));
+    }
+
+    /// Position (avant mise à l'échelle) de chaque écran dans le canevas
+    fn placements(&self) -> Vec<(i64, i64, &ScreenFrame)> {
+        let mut placed = Vec::with_capacity(self.slots.len());
+        let (mut cursor, mut right_edge) = (0i64, 0i64);
+
+        for (display, slot) in &self.slots {
+            if slot.frame.width == 0 || slot.frame.height == 0 {
+                continue;
+            }
+            let (w, h) = (slot.frame.width as i64, slot.frame.height as i64);
+            let (x, y) = match self.layout {
+                CanvasLayout::Horizontal => (cursor, 0),
+                CanvasLayout::Vertical => (0, cursor),
+                CanvasLayout::Position => match self.origins.get(display) {
+                    Some(&(ox, oy)) => (ox as i64 + slot.offset.0 as i64, oy as i64 + slot.offset.1 as i64),
+                    // Position inconnue : à droite des écrans déjà placés
+                    None => (right_edge, 0),
+                },
+            };
+            cursor += if self.layout == CanvasLayout::Vertical { h } else { w };
+            right_edge = right_edge.max(x + w);
+            placed.push((x, y, &slot.frame));
+        }
+
+        // Ramener le coin haut-gauche du bureau en (0, 0) (écrans à gauche/au-dessus du principal)
+        let min_x = placed.iter().map(|p| p.0).min().unwrap_or(0);
+        let min_y = placed.iter().map(|p| p.1).min().unwrap_or(0);
+        placed.into_iter().map(|(x, y, f)| (x - min_x, y - min_y, f)).collect()
+    }
+
+    /// Produit le canevas à partir des dernières frames de chaque écran
+    pub fn compose(&self) -> Option<ScreenFrame> {
+        let placed = self.placements();
+        if placed.is_empty() {
+            return None;
+        }
+
+        let scale = self.scale as f64;
+        let scaled = |v: i64| (v as f64 * scale).round() as usize;
+        let canvas_w = placed.iter().map(|(x, _, f)| scaled(x + f.width as i64)).max()?.max(1);
+        let canvas_h = placed.iter().map(|(_, y, f)| scaled(y + f.height as i64)).max()?.max(1);
+
+        let mut data = Vec::with_capacity(canvas_w * canvas_h * 4);
+        for _ in 0..canvas_w * canvas_h {
+            data.extend_from_slice(&BACKGROUND);
+        }
+
+        for (x, y, frame) in &placed {
+            let (x0, y0) = (scaled(*x), scaled(*y));
+            let x1 = scaled(x + frame.width as i64).min(canvas_w);
+            let y1 = scaled(y + frame.height as i64).min(canvas_h);
+            let bpp = frame.format.bytes_per_pixel();
+
+            // Échantillonnage au plus proche voisin
+            for cy in y0..y1 {
+                let sy = (((cy - y0) as f64 / scale) as usize).min(frame.height as usize - 1);
+                let row = &frame.data[sy * frame.stride as usize..];
+                for cx in x0..x1 {
+                    let sx = (((cx - x0) as f64 / scale) as usize).min(frame.width as usize - 1);
+                    let px = &row[sx * bpp..sx * bpp + bpp];
+                    let bgra = match frame.format {
+                        PixelFormat::Bgra8 => [px[0], px[1], px[2], 255],
+                        PixelFormat::Rgba8 | PixelFormat::Rgb8 => [px[2], px[1], px[0], 255],
+                    };
+                    let i = (cy * canvas_w + cx) * 4;
+                    data[i..i + 4].copy_from_slice(&bgra);
+                }
+            }
+        }
+
+        Some(ScreenFrame {
+            data,
+            width: canvas_w as u32,
+            height: canvas_h as u32,
+            stride: (canvas_w * 4) as u32,
+            format: PixelFormat::Bgra8,
+            display_index: COMPOSITE_DISPLAY,
+            timestamp: placed.iter().map(|(_, _, f)| f.timestamp).max().unwrap_or(0),
+            pts: placed.iter().map(|(_, _, f)| f.pts).max().unwrap_or(0),
+            masked: placed.iter().any(|(_, _, f)| f.masked),
+        })
+    }
+}
@@ -0,0 +1,292 @@
+// visualisation_module/src/capture/delta.rs
+
+//! Encodage différentiel par tuiles des frames écran
+//! Chaque frame est découpée en tuiles comparées à la frame précédente du même écran :
+//! seules les tuiles modifiées partent, avec une keyframe complète périodique.
+//!
+//! Keyframe : kind=0 u8 | display u16 | seq u32 | paquet `ScreenEncoder` complet
+//! Delta    : kind=1 u8 | display u16 | seq u32 | timestamp u64 | pts u64 | tile_size u16 | count u32
+//!            | flags u8 | (tx u16, ty u16) * count | paquet `ScreenEncoder` de la bande de tuiles (si count > 0)
+//!            flags : bit 0 = frame masquée (porté même sans tuile modifiée)
+//!
+//! La bande de tuiles est une image de `tile_size` de large où les tuiles modifiées
+//! sont empilées verticalement : elle passe par le même codec que les keyframes.
+
+use std::collections::HashMap;
+
+use crate::capture::encoder::ScreenEncoder;
+use crate::capture::frame::{PixelFormat, ScreenFrame};
+use crate::error::ModuleError;
+
+pub const PACKET_KEYFRAME: u8 = 0;
+pub const PACKET_DELTA: u8 = 1;
+
+const DELTA_FLAG_MASKED: u8 = 0x01;
+/// Corps d'un delta avant les coordonnées des tuiles
+const DELTA_HEADER_LEN: usize = 23;
+
+/// Au-delà de cette proportion de tuiles modifiées, une keyframe coûte moins cher
+const FULL_FRAME_RATIO: f32 = 0.5;
+
+struct EncoderState {
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    pixels: Vec<u8>,
+    seq: u32,
+    since_keyframe: u32,
+    force_keyframe: bool,
+}
+
+pub struct DeltaEncoder {
+    tile_size: u32,
+    keyframe_interval: u32,
+    states: HashMap<u16, EncoderState>,
+}
+
+impl DeltaEncoder {
+    /// `keyframe_interval` : nombre de frames entre deux keyframes (0 = keyframes uniquement)
+    pub fn new(tile_size: u32, keyframe_interval: u32) -> Self {
+        Self {
+            tile_size: tile_size.clamp(8, 1024),
+            keyframe_interval,
+            states: HashMap::new(),
+        }
+    }
+
+    pub fn tile_size(&self) -> u32 {
+        self.tile_size
+    }
+
+    /// Force une keyframe au prochain paquet (tous les écrans)
+    pub fn request_keyframe(&mut self) {
+        for state in self.states.values_mut() {
+            state.force_keyframe = true;
+        }
+    }
+
+    /// Encode une frame en keyframe ou en delta selon l'état de son écran
+    pub fn encode(&mut self, frame: &ScreenFrame, encoder: &ScreenEncoder) -> Result<Vec<u8>, ModuleError> {
+        frame.validate()?;
+        let pixels = frame.to_packed();
+        let tile_size = self.tile_size;
+        let interval = self.keyframe_interval;
+
+        let dirty = match self.states.get(&frame.display_index) {
+            Some(s) if s.width == frame.width
+                && s.height == frame.height
+                && s.format == frame.format
+                && !s.force_keyframe
+                && interval > 0
+                && s.since_keyframe < interval =>
+            {
+                let tiles = Self::dirty_tiles(&s.pixels, &pixels, frame, tile_size);
+                let total = frame.width.div_ceil(tile_size) * frame.height.div_ceil(tile_size);
+                if tiles.len() as f32 > total as f32 * FULL_FRAME_RATIO { None } else { Some(tiles) }
+            }
+            _ => None,
+        };
+
+        let seq = self.states.get(&frame.display_index).map_or(0, |s| s.seq.wrapping_add(1));
+        let mut packet = Vec::new();
+
+        let since_keyframe = match dirty {
+            None => {
+                packet.push(PACKET_KEYFRAME);
+                packet.extend_from_slice(&frame.display_index.to_le_bytes());
+                packet.extend_from_slice(&seq.to_le_bytes());
+                packet.extend(encoder.encode_packet(frame)?);
+                1
+            }
+            Some(tiles) => {
+                packet.push(PACKET_DELTA);
+                packet.extend_from_slice(&frame.display_index.to_le_bytes());
+                packet.extend_from_slice(&seq.to_le_bytes());
+                packet.extend_from_slice(&frame.timestamp.to_le_bytes());
+                packet.extend_from_slice(&frame.pts.to_le_bytes());
+                packet.extend_from_slice(&(tile_size as u16).to_le_bytes());
+                packet.extend_from_slice(&(tiles.len() as u32).to_le_bytes());
+                packet.push(if frame.masked { DELTA_FLAG_MASKED } else { 0 });
+                for (tx, ty) in &tiles {
+                    packet.extend_from_slice(&(*tx as u16).to_le_bytes());
+                    packet.extend_from_slice(&(*ty as u16).to_le_bytes());
+                }
+                if !tiles.is_empty() {
+                    let strip = Self::build_strip(&pixels, frame, &tiles, tile_size);
+                    packet.extend(encoder.encode_packet(&strip)?);
+                }
+                self.states[&frame.display_index].since_keyframe + 1
+            }
+        };
+
+        self.states.insert(frame.display_index, EncoderState {
+            width: frame.width,
+            height: frame.height,
+            format: frame.format,
+            pixels,
+            seq,
+            since_keyframe,
+            force_keyframe: false,
+        });
+        Ok(packet)
+    }
+
+    /// Liste (tx, ty) des tuiles dont au moins un pixel a changé
+    fn dirty_tiles(prev: &[u8], cur: &[u8], frame: &ScreenFrame, tile_size: u32) -> Vec<(u32, u32)> {
+        let row_len = frame.row_len();
+        let bpp = frame.format.bytes_per_pixel();
+        let mut tiles = Vec::new();
+
+        for ty in 0..frame.height.div_ceil(tile_size) {
+            for tx in 0..frame.width.div_ceil(tile_size) {
+                let (x0, y0) = ((tx * tile_size) as usize, (ty * tile_size) as usize);
+                let x1 = ((tx + 1) * tile_size).min(frame.width) as usize;
+                let y1 = ((ty + 1) * tile_size).min(frame.height) as usize;
+                let changed = (y0..y1).any(|y| {
+                    let range = y * row_len + x0 * bpp..y * row_len + x1 * bpp;
+                    prev[range.clone()] != cur[range]
+                });
+                if changed {
+                    tiles.push((tx, ty));
+                }
+            }
+        }
+        tiles
+    }
+
+    /// Empile les tuiles modifiées dans une image `tile_size` x (tile_size * n)
+    fn build_strip(pixels: &[u8], frame: &ScreenFrame, tiles: &[(u32, u32)], tile_size: u32) -> ScreenFrame {
+        let bpp = frame.format.bytes_per_pixel();
+        let row_len = frame.row_len();
+        let strip_row = tile_size as usize * bpp;
+        let mut data = vec![0u8; strip_row * tile_size as usize * tiles.len()];
+
+        for (n, (tx, ty)) in tiles.iter().enumerate() {
+            let (x0, y0) = ((tx * tile_size) as usize, (ty * tile_size) as usize);
+            let w = (tile_size as usize).min(frame.width as usize - x0);
+            let h = (tile_size as usize).min(frame.height as usize - y0);
+            for row in 0..h {
+                let src = (y0 + row) * row_len + x0 * bpp;
+                let dst = (n * tile_size as usize + row) * strip_row;
+                data[dst..dst + w * bpp].copy_from_slice(&pixels[src..src + w * bpp]);
+            }
+        }
+
+        ScreenFrame {
+            data,
+            width: tile_size,
+            height: tile_size * tiles.len() as u32,
+            stride: strip_row as u32,
+            format: frame.format,
+            display_index: frame.display_index,
+            timestamp: frame.timestamp,
+            pts: frame.pts,
+            masked: frame.masked,
+        }
+    }
+}
+
+/// Reconstruction des frames complètes côté pool
+#[derive(Default)]
+pub struct DeltaDecoder {
+    frames: HashMap<u16, (u32, ScreenFrame)>,
+}
+
+impl DeltaDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Décode un paquet et renvoie la frame complète reconstruite
+    /// Erreur si un delta arrive sans keyframe ou après un paquet perdu
+    pub fn decode(&mut self, packet: &[u8]) -> Result<ScreenFrame, ModuleError> {
+        if packet.len() < 7 {
+            return Err(ModuleError::ValidationError("Screen delta packet truncated".to_string()));
+        }
+        let display = u16::from_le_bytes([packet[1], packet[2]]);
+        let seq = u32::from_le_bytes(packet[3..7].try_into().unwrap());
+
+        match packet[0] {
+            PACKET_KEYFRAME => {
+                let frame = ScreenEncoder::decode_packet(&packet[7..])?;
+                self.frames.insert(display, (seq, frame.clone()));
+                Ok(frame)
+            }
+            PACKET_DELTA => self.apply_delta(display, seq, &packet[7..]),
+            kind => Err(ModuleError::ValidationError(format!("Unknown screen packet kind {}", kind))),
+        }
+    }
+
+    fn apply_delta(&mut self, display: u16, seq: u32, body: &[u8]) -> Result<ScreenFrame, ModuleError> {
+        let truncated = || ModuleError::ValidationError("Screen delta packet truncated".to_string());
+        let (last_seq, base) = self.frames.get(&display).ok_or_else(|| {
+            ModuleError::ValidationError(format!("Delta for display {} without keyframe", display))
+        })?;
+        if seq != last_seq.wrapping_add(1) {
+            return Err(ModuleError::ValidationError(format!(
+                "Delta sequence gap on display {} ({} -> {}), waiting for keyframe", display, last_seq, seq
+            )));
+        }
+        if body.len() < DELTA_HEADER_LEN {
+            return Err(truncated());
+        }
+
+        let timestamp = u64::from_le_bytes(body[0..8].try_into().unwrap());
+        let pts = u64::from_le_bytes(body[8..16].try_into().unwrap());
+        let tile_size = u16::from_le_bytes([body[16], body[17]]) as usize;
+        let count = u32::from_le_bytes(body[18..22].try_into().unwrap()) as usize;
+        let flags = body[22];
+        if tile_size == 0 {
+            return Err(ModuleError::ValidationError("Delta tile size is zero".to_string()));
+        }
+        let coords_end = count.checked_mul(4).and_then(|n| n.checked_add(DELTA_HEADER_LEN)).ok_or_else(truncated)?;
+        if body.len() < coords_end {
+            return Err(truncated());
+        }
+
+        let mut frame = base.clone();
+        frame.timestamp = timestamp;
+        frame.pts = pts;
+        frame.masked = flags & DELTA_FLAG_MASKED != 0;
+
+        if count > 0 {
+            let strip = ScreenEncoder::decode_packet(&body[coords_end..])?;
+            if strip.format != frame.format || strip.width as usize != tile_size {
+                return Err(ModuleError::ValidationError("Delta strip does not match keyframe".to_string()));
+            }
+            let bpp = frame.format.bytes_per_pixel();
+            let (row_len, strip_row) = (frame.stride as usize, strip.stride as usize);
+            // La bande doit contenir `count` tuiles complètes
+            let strip_rows = count.checked_mul(tile_size).ok_or_else(truncated)?;
+            let strip_len = strip_rows.checked_mul(strip_row).ok_or_else(truncated)?;
+            if (strip.height as usize) < strip_rows || strip_row < tile_size * bpp || strip.data.len() < strip_len {
+                return Err(ModuleError::ValidationError(format!(
+                    "Delta strip too small for {} tile(s) of {}px", count, tile_size
+                )));
+            }
+            if frame.data.len() < row_len * frame.height as usize || row_len < frame.width as usize * bpp {
+                return Err(ModuleError::ValidationError("Delta base frame inconsistent".to_string()));
+            }
+
+            for n in 0..count {
+                let at = DELTA_HEADER_LEN + n * 4;
+                let tx = u16::from_le_bytes([body[at], body[at + 1]]) as usize;
+                let ty = u16::from_le_bytes([body[at + 2], body[at + 3]]) as usize;
+                let (x0, y0) = (tx * tile_size, ty * tile_size);
+                if x0 >= frame.width as usize || y0 >= frame.height as usize {
+                    return Err(ModuleError::ValidationError(format!("Tile ({}, {}) out of frame", tx, ty)));
+                }
+                let w = tile_size.min(frame.width as usize - x0);
+                let h = tile_size.min(frame.height as usize - y0);
+                for row in 0..h {
+                    let src = (n * tile_size + row) * strip_row;
+                    let dst = (y0 + row) * row_len + x0 * bpp;
+                    frame.data[dst..dst + w * bpp].copy_from_slice(&strip.data[src..src + w * bpp]);
+                }
+            }
+        }
+
+        self.frames.insert(display, (seq, frame.clone()));
+        Ok(frame)
+    }
+}
@@ -0,0 +1,325 @@
+// visualisation_module/src/capture/dsp.rs
+
+//! Chaîne de filtres appliquée aux frames du micro avant l'encodage
+//! - `gate`      : porte de bruit (coupe tout ce qui reste sous le seuil)
+//! - `highpass`  : passe-haut Butterworth 2e ordre (souffle, ronflement, chocs)
+//! - `compressor`: compresseur de dynamique avec gain de compensation
+//! - `normalize` : normalisation lente du niveau RMS vers une cible
+//!
+//! L'ordre est celui de `audio_dsp_chain` ; chaque étage peut être contourné à chaud.
+//! Le son du système n'est pas filtré.
+
+use crate::capture::audio::AudioFrame;
+use crate::capture::meter::to_db;
+use crate::config::ConfigFile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilterKind {
+    NoiseGate,
+    HighPass,
+    Compressor,
+    Normalize,
+}
+
+impl FilterKind {
+    /// Nom tel qu'écrit dans `audio_dsp_chain`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "gate" | "noise_gate" | "noisegate" => Some(FilterKind::NoiseGate),
+            "highpass" | "high_pass" | "hpf" => Some(FilterKind::HighPass),
+            "compressor" | "compress" => Some(FilterKind::Compressor),
+            "normalize" | "normalise" | "loudness" => Some(FilterKind::Normalize),
+            _ => None,
+        }
+    }
+}
+
+/// Étage de la chaîne : traite des échantillons entrelacés sur place
+pub trait AudioFilter: Send {
+    fn kind(&self) -> FilterKind;
+    fn process(&mut self, samples: &mut [f32], channels: usize, sample_rate: u32);
+}
+
+/// Coefficient d'un lissage exponentiel de constante de temps `ms`
+fn smoothing(ms: f32, sample_rate: u32) -> f32 {
+    if ms <= 0.0 {
+        return 0.0;
+    }
+    (-1.0 / (ms * 0.001 * sample_rate.max(1) as f32)).exp()
+}
+
+fn from_db(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+// --- Porte de bruit ---
+
+pub struct NoiseGate {
+    threshold: f32,
+    attack_ms: f32,
+    release_ms: f32,
+    envelope: f32,
+    gain: f32,
+}
+
+impl NoiseGate {
+    pub fn new(threshold_db: f32, attack_ms: f32, release_ms: f32) -> Self {
+        Self { threshold: from_db(threshold_db), attack_ms, release_ms, envelope: 0.0, gain: 0.0 }
+    }
+}
+
+impl AudioFilter for NoiseGate {
+    fn kind(&self) -> FilterKind {
+        FilterKind::NoiseGate
+    }
+
+    fn process(&mut self, samples: &mut [f32], channels: usize, sample_rate: u32) {
+        let attack = smoothing(self.attack_ms, sample_rate);
+        let release = smoothing(self.release_ms, sample_rate);
+        for frame in samples.chunks_exact_mut(channels) {
+            // Enveloppe crête : montée immédiate, descente à la vitesse du relâchement
+            let level = frame.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            self.envelope = level.max(self.envelope * release);
+            let (target, coef) = if self.envelope >= self.threshold { (1.0, attack) } else { (0.0, release) };
+            self.gain = target + (self.gain - target) * coef;
+            frame.iter_mut().for_each(|s| *s *= self.gain);
+        }
+    }
+}
+
+// --- Passe-haut ---
+
+pub struct HighPass {
+    cutoff_hz: f32,
+    /// Coefficients (b0, b1, b2, a1, a2) calculés pour `rate`
+    coefs: [f32; 5],
+    rate: u32,
+    /// x[n-1], x[n-2], y[n-1], y[n-2] par canal
+    state: Vec<[f32; 4]>,
+}
+
+impl HighPass {
+    pub fn new(cutoff_hz: f32) -> Self {
+        Self { cutoff_hz: cutoff_hz.max(1.0), coefs: [0.0; 5], rate: 0, state: Vec::new() }
+    }
+
+    /// Biquad passe-haut de Butterworth (Q = 1/√2)
+    fn design(&mut self, sample_rate: u32) {
+        let cutoff = self.cutoff_hz.min(sample_rate as f32 * 0.45);
+        let w0 = std::f32::consts::TAU * cutoff / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let cos = w0.cos();
+        let a0 = 1.0 + alpha;
+        self.coefs = [
+            (1.0 + cos) / 2.0 / a0,
+            -(1.0 + cos) / a0,
+            (1.0 + cos) / 2.0 / a0,
+            -2.0 * cos / a0,
+            (1.0 - alpha) / a0,
+        ];
+        self.rate = sample_rate;
+    }
+}
+
+impl AudioFilter for HighPass {
+    fn kind(&self) -> FilterKind {
+        FilterKind::HighPass
+    }
+
+    fn process(&mut self, samples: &mut [f32], channels: usize, sample_rate: u32) {
+        if self.rate != sample_rate {
+            self.design(sample_rate.max(1));
+        }
+        if self.state.len() != channels {
+            self.state = vec![[0.0; 4]; channels];
+        }
+        let [b0, b1, b2, a1, a2] = self.coefs;
+        for frame in samples.chunks_exact_mut(channels) {
+            for (s, st) in frame.iter_mut().zip(self.state.iter_mut()) {
+                let x = *s;
+                let y = b0 * x + b1 * st[0] + b2 * st[1] - a1 * st[2] - a2 * st[3];
+                *st = [x, st[0], y, st[2]];
+                *s = y;
+            }
+        }
+    }
+}
+
+// --- Compresseur ---
+
+pub struct Compressor {
+    threshold_db: f32,
+    ratio: f32,
+    attack_ms: f32,
+    release_ms: f32,
+    makeup: f32,
+    /// Réduction de gain courante (dB, >= 0)
+    reduction_db: f32,
+}
+
+impl Compressor {
+    pub fn new(threshold_db: f32, ratio: f32, attack_ms: f32, release_ms: f32, makeup_db: f32) -> Self {
+        Self {
+            threshold_db,
+            ratio: ratio.max(1.0),
+            attack_ms,
+            release_ms,
+            makeup: from_db(makeup_db),
+            reduction_db: 0.0,
+        }
+    }
+}
+
+impl AudioFilter for Compressor {
+    fn kind(&self) -> FilterKind {
+        FilterKind::Compressor
+    }
+
+    fn process(&mut self, samples: &mut [f32], channels: usize, sample_rate: u32) {
+        let attack = smoothing(self.attack_ms, sample_rate);
+        let release = smoothing(self.release_ms, sample_rate);
+        let slope = 1.0 - 1.0 / self.ratio;
+        for frame in samples.chunks_exact_mut(channels) {
+            let level_db = to_db(frame.iter().fold(0.0f32, |m, s| m.max(s.abs())));
+            let target = (level_db - self.threshold_db).max(0.0) * slope;
+            let coef = if target > self.reduction_db { attack } else { release };
+            self.reduction_db = target + (self.reduction_db - target) * coef;
+            let gain = from_db(-self.reduction_db) * self.makeup;
+            frame.iter_mut().for_each(|s| *s *= gain);
+        }
+    }
+}
+
+// --- Normalisation ---
+
+/// Niveau sous lequel la normalisation n'amplifie plus (silence, bruit de fond)
+const NORMALIZE_FLOOR_DB: f32 = -60.0;
+
+/// Atténuation maximale appliquée par la normalisation
+const NORMALIZE_MAX_CUT_DB: f32 = 24.0;
+
+pub struct Normalizer {
+    target_db: f32,
+    max_gain_db: f32,
+    /// Gain courant en dB, ajusté d'une frame à l'autre
+    gain_db: f32,
+}
+
+impl Normalizer {
+    pub fn new(target_db: f32, max_gain_db: f32) -> Self {
+        Self { target_db, max_gain_db: max_gain_db.max(0.0), gain_db: 0.0 }
+    }
+}
+
+impl AudioFilter for Normalizer {
+    fn kind(&self) -> FilterKind {
+        FilterKind::Normalize
+    }
+
+    fn process(&mut self, samples: &mut [f32], _channels: usize, _sample_rate: u32) {
+        if samples.is_empty() {
+            return;
+        }
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+        let level_db = to_db(rms);
+        let target_gain = if level_db < NORMALIZE_FLOOR_DB {
+            self.gain_db
+        } else {
+            (self.target_db - level_db).clamp(-NORMALIZE_MAX_CUT_DB, self.max_gain_db)
+        };
+
+        // Rampe linéaire sur la frame, un quart du chemin par frame vers le gain visé
+        let start = self.gain_db;
+        let end = start + (target_gain - start) * 0.25;
+        let n = samples.len() as f32;
+        for (i, s) in samples.iter_mut().enumerate() {
+            let gain = from_db(start + (end - start) * i as f32 / n);
+            *s = (*s * gain).clamp(-1.0, 1.0);
+        }
+        self.gain_db = end;
+    }
+}
+
+// --- Chaîne ---
+
+struct Stage {
+    filter: Box<dyn AudioFilter>,
+    bypassed: bool,
+}
+
+#[derive(Default)]
+pub struct FilterChain {
+    stages: Vec<Stage>,
+}
+
+impl FilterChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construit la chaîne dans l'ordre de `audio_dsp_chain` (noms inconnus ignorés)
+    pub fn from_config(file: &ConfigFile) -> Self {
+        let mut chain = Self::new();
+        for name in &file.audio_dsp_chain {
+            let filter: Box<dyn AudioFilter> = match FilterKind::from_name(name) {
+                Some(FilterKind::NoiseGate) => Box::new(NoiseGate::new(
+                    file.audio_gate_threshold_db,
+                    file.audio_gate_attack_ms,
+                    file.audio_gate_release_ms,
+                )),
+                Some(FilterKind::HighPass) => Box::new(HighPass::new(file.audio_highpass_hz)),
+                Some(FilterKind::Compressor) => Box::new(Compressor::new(
+                    file.audio_compressor_threshold_db,
+                    file.audio_compressor_ratio,
+                    file.audio_compressor_attack_ms,
+                    file.audio_compressor_release_ms,
+                    file.audio_compressor_makeup_db,
+                )),
+                Some(FilterKind::Normalize) => Box::new(Normalizer::new(
+                    file.audio_normalize_target_db,
+                    file.audio_normalize_max_gain_db,
+                )),
+                None => {
+                    eprintln!("[dsp] Unknown audio filter '{}', ignored", name);
+                    continue;
+                }
+            };
+            chain.push(filter);
+        }
+        chain
+    }
+
+    /// Ajoute un étage en fin de chaîne
+    pub fn push(&mut self, filter: Box<dyn AudioFilter>) {
+        self.stages.push(Stage { filter, bypassed: false });
+    }
+
+    pub fn kinds(&self) -> Vec<FilterKind> {
+        self.stages.iter().map(|s| s.filter.kind()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Contourne (ou réactive) les étages du type donné ; false si absent de la chaîne
+    pub fn set_bypass(&mut self, kind: FilterKind, bypassed: bool) -> bool {
+        let mut found = false;
+        for stage in self.stages.iter_mut().filter(|s| s.filter.kind() == kind) {
+            stage.bypassed = bypassed;
+            found = true;
+        }
+        found
+    }
+
+    pub fn is_bypassed(&self, kind: FilterKind) -> bool {
+        self.stages.iter().any(|s| s.filter.kind() == kind && s.bypassed)
+    }
+
+    pub fn process(&mut self, frame: &mut AudioFrame) {
+        let channels = frame.channels.max(1) as usize;
+        for stage in self.stages.iter_mut().filter(|s| !s.bypassed) {
+            stage.filter.process(&mut frame.samples, channels, frame.sample_rate);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+// visualisation_module/src/capture/encoder.rs
+
+//! Encodeurs d'image pour les frames écran (PNG, JPEG, WebP sans perte, QOI)
+//! sélectionnés via `screen_compression` / `screen_quality`
+//!
+//! Format d'un paquet écran :
+//! en-tête `ScreenFrame` | codec u8 | qualité u8 | corps encodé
+
+use std::io::{Read, Write};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::codecs::qoi::QoiEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{ColorType, ImageEncoder, ImageFormat};
+
+use crate::capture::frame::{PixelFormat, ScreenFrame};
+use crate::config::ConfigFile;
+use crate::error::ModuleError;
+
+/// Codec utilisé pour le corps d'un paquet écran (identifiant écrit dans l'en-tête)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenCodec {
+    /// Pixels bruts compressés GZIP (format source conservé)
+    Gzip = 0,
+    Png = 1,
+    Jpeg = 2,
+    WebpLossless = 3,
+    Qoi = 4,
+}
+
+impl ScreenCodec {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(ScreenCodec::Gzip),
+            1 => Some(ScreenCodec::Png),
+            2 => Some(ScreenCodec::Jpeg),
+            3 => Some(ScreenCodec::WebpLossless),
+            4 => Some(ScreenCodec::Qoi),
+            _ => None,
+        }
+    }
+
+    /// Nom tel qu'écrit dans `screen_compression`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "gzip" | "flate2" | "raw" => Some(ScreenCodec::Gzip),
+            "png" => Some(ScreenCodec::Png),
+            "jpeg" | "jpg" => Some(ScreenCodec::Jpeg),
+            "webp" => Some(ScreenCodec::WebpLossless),
+            "qoi" => Some(ScreenCodec::Qoi),
+            _ => None,
+        }
+    }
+
+    fn image_format(&self) -> Option<ImageFormat> {
+        match self {
+            ScreenCodec::Gzip => None,
+            ScreenCodec::Png => Some(ImageFormat::Png),
+            ScreenCodec::Jpeg => Some(ImageFormat::Jpeg),
+            ScreenCodec::WebpLossless => Some(ImageFormat::WebP),
+            ScreenCodec::Qoi => Some(ImageFormat::Qoi),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScreenEncoder {
+    codec: ScreenCodec,
+    /// Qualité 1-100 (utilisée par JPEG ; les codecs sans perte l'ignorent)
+    quality: u8,
+}
+
+impl ScreenEncoder {
+    pub fn new(codec: ScreenCodec, quality: u32) -> Self {
+        Self {
+            codec,
+            quality: quality.clamp(1, 100) as u8,
+        }
+    }
+
+    pub fn from_config(file: &ConfigFile) -> Self {
+        let codec = ScreenCodec::from_name(&file.screen_compression).unwrap_or_else(|| {
+            eprintln!("[encoder] Unknown screen_compression '{}', using png", file.screen_compression);
+            ScreenCodec::Png
+        });
+        Self::new(codec, file.screen_quality)
+    }
+
+    pub fn codec(&self) -> ScreenCodec {
+        self.codec
+    }
+
+    pub fn quality(&self) -> u8 {
+        self.quality
+    }
+
+    /// Encode une frame en paquet complet (en-tête + corps)
+    pub fn encode_packet(&self, frame: &ScreenFrame) -> Result<Vec<u8>, ModuleError> {
+        frame.validate()?;
+
+        let (body, format) = match self.codec.image_format() {
+            None => (Self::gzip(&frame.to_packed())?, frame.format),
+            Some(_) => (self.encode_image(frame)?, PixelFormat::Rgb8),
+        };
+
+        // L'en-tête décrit les pixels obtenus après décodage du corps
+        let header = ScreenFrame {
+            data: Vec::new(),
+            width: frame.width,
+            height: frame.height,
+            stride: frame.width * format.bytes_per_pixel() as u32,
+            format,
+            display_index: frame.display_index,
+            timestamp: frame.timestamp,
+            pts: frame.pts,
+            masked: frame.masked,
+        }
+        .encode_header();
+
+        let mut packet = Vec::with_capacity(header.len() + 2 + body.len());
+        packet.extend(header);
+        packet.push(self.codec as u8);
+        packet.push(self.quality);
+        packet.extend(body);
+        Ok(packet)
+    }
+
+    /// Décode un paquet produit par `encode_packet` (côté pool)
+    pub fn decode_packet(packet: &[u8]) -> Result<ScreenFrame, ModuleError> {
+        let (mut frame, rest) = ScreenFrame::decode_header(packet)?;
+        if rest.len() < 2 {
+            return Err(ModuleError::ValidationError("Screen codec header truncated".to_string()));
+        }
+        let codec = ScreenCodec::from_u8(rest[0])
+            .ok_or_else(|| ModuleError::ValidationError(format!("Unknown screen codec {}", rest[0])))?;
+        let body = &rest[2..];
+
+        frame.data = match codec.image_format() {
+            None => {
+                let mut data = Vec::new();
+                GzDecoder::new(body)
+                    .read_to_end(&mut data)
+                    .map_err(|e| ModuleError::ValidationError(format!("Screen payload corrupted: {}", e)))?;
+                data
+            }
+            Some(format) => image::load_from_memory_with_format(body, format)
+                .map_err(|e| ModuleError::ValidationError(format!("Screen image corrupted: {}", e)))?
+                .to_rgb8()
+                .into_raw(),
+        };
+        frame.validate()?;
+        Ok(frame)
+    }
+
+    fn encode_image(&self, frame: &ScreenFrame) -> Result<Vec<u8>, ModuleError> {
+        let rgb = Self::to_rgb(frame);
+        let (w, h) = (frame.width, frame.height);
+        let mut out = Vec::new();
+
+        let result = match self.codec {
+            ScreenCodec::Png => PngEncoder::new_with_quality(&mut out, CompressionType::Fast, FilterType::Adaptive)
+                .write_image(&rgb, w, h, ColorType::Rgb8),
+            ScreenCodec::Jpeg => JpegEncoder::new_with_quality(&mut out, self.quality)
+                .write_image(&rgb, w, h, ColorType::Rgb8),
+            ScreenCodec::WebpLossless => WebPEncoder::new_lossless(&mut out)
+                .write_image(&rgb, w, h, ColorType::Rgb8),
+            ScreenCodec::Qoi => QoiEncoder::new(&mut out)
+                .write_image(&rgb, w, h, ColorType::Rgb8),
+            ScreenCodec::Gzip => unreachable!("gzip n'est pas un codec image"),
+        };
+
+        result.map_err(|e| ModuleError::CaptureError(format!("{:?} encoding failed: {}", self.codec, e)))?;
+        Ok(out)
+    }
+
+    /// Convertit les pixels source en RGB8 compact (l'alpha écran est toujours opaque)
+    fn to_rgb(frame: &ScreenFrame) -> Vec<u8> {
+        let bpp = frame.format.bytes_per_pixel();
+        let mut rgb = Vec::with_capacity(frame.width as usize * frame.height as usize * 3);
+        for row in frame.data.chunks(frame.stride as usize).take(frame.height as usize) {
+            for px in row[..frame.row_len()].chunks_exact(bpp) {
+                match frame.format {
+                    PixelFormat::Bgra8 => rgb.extend_from_slice(&[px[2], px[1], px[0]]),
+                    PixelFormat::Rgba8 | PixelFormat::Rgb8 => rgb.extend_from_slice(&px[..3]),
+                }
+            }
+        }
+        rgb
+    }
+
+    fn gzip(data: &[u8]) -> Result<Vec<u8>, ModuleError> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(data).map_err(|e| ModuleError::IoError(e.to_string()))?;
+        encoder.finish().map_err(|e| ModuleError::IoError(e.to_string()))
+    }
+}
@@ -0,0 +1,100 @@
+// visualisation_module/src/capture/fps_controller.rs
+
+//! Régulation adaptative du FPS écran entre `screen_min_fps` et `screen_max_fps`
+//! - monte vite quand le contenu ou l'input bouge
+//! - redescend doucement vers le minimum à l'arrêt
+//! - recule quand le CPU ou la file du transmitter saturent
+
+use std::time::Duration;
+
+use crate::capture::frame::ScreenFrame;
+
+/// Mesures prises en compte à chaque évaluation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FpsInputs {
+    /// Au moins une frame a changé depuis la dernière évaluation
+    pub content_changed: bool,
+    /// Activité clavier/souris récente
+    pub input_active: bool,
+    /// Charge CPU globale (%)
+    pub cpu_percent: f32,
+    /// Paquets écran en attente dans le transmitter
+    pub queue_depth: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdaptiveFps {
+    min_fps: u32,
+    max_fps: u32,
+    current: f32,
+    cpu_limit: f32,
+    queue_limit: usize,
+}
+
+impl AdaptiveFps {
+    pub fn new(min_fps: u32, max_fps: u32, initial_fps: u32) -> Self {
+        let min_fps = min_fps.max(1);
+        let max_fps = max_fps.max(min_fps);
+        Self {
+            min_fps,
+            max_fps,
+            current: initial_fps.clamp(min_fps, max_fps) as f32,
+            cpu_limit: 85.0,
+            queue_limit: 250,
+        }
+    }
+
+    /// Seuils de recul : CPU (%) et profondeur de file du transmitter
+    pub fn set_limits(&mut self, cpu_limit: f32, queue_limit: usize) {
+        self.cpu_limit = cpu_limit;
+        self.queue_limit = queue_limit.max(1);
+    }
+
+    pub fn current_fps(&self) -> u32 {
+        self.current.round() as u32
+    }
+
+    pub fn frame_interval(&self) -> Duration {
+        Duration::from_secs_f32(1.0 / self.current.max(1.0))
+    }
+
+    /// Évalue les mesures et renvoie le nouveau FPS
+    pub fn update(&mut self, inputs: &FpsInputs) -> u32 {
+        let (min, max) = (self.min_fps as f32, self.max_fps as f32);
+
+        self.current = if inputs.cpu_percent > self.cpu_limit || inputs.queue_depth > self.queue_limit {
+            // Surcharge : on divise par deux
+            self.current * 0.5
+        } else if inputs.content_changed || inputs.input_active {
+            // Activité : on rejoint la moitié de l'écart vers le max à chaque pas
+            self.current + ((max - self.current) * 0.5).max(1.0)
+        } else {
+            // Repos : décroissance lente vers le min
+            self.current - ((self.current - min) * 0.25).max(1.0)
+        }
+        .clamp(min, max);
+
+        self.current_fps()
+    }
+}
+
+/// Empreinte rapide d'une frame (FNV-1a sur des mots de 8 octets)
+/// Sert à détecter un changement de contenu sans garder la frame précédente
+pub fn frame_fingerprint(frame: &ScreenFrame) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let row_len = frame.row_len();
+    for row in frame.data.chunks(frame.stride.max(1) as usize).take(frame.height as usize) {
+        let row = &row[..row_len.min(row.len())];
+        let words = row.chunks_exact(8);
+        let tail = words.remainder();
+        for word in words {
+            hash ^= u64::from_le_bytes(word.try_into().unwrap());
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        for &b in tail {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
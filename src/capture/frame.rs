@@ -0,0 +1,188 @@
+// visualisation_module/src/capture/frame.rs
+
+//! Enveloppe des frames écran : pixels + métadonnées nécessaires au décodage
+//! (géométrie, format, écran d'origine, horodatage, masquage)
+
+use crate::error::ModuleError;
+
+/// Version de l'en-tête sérialisé des frames écran
+pub const SCREEN_HEADER_VERSION: u8 = 0x04;
+
+/// Taille fixe de l'en-tête sérialisé (octets)
+pub const SCREEN_HEADER_LEN: usize = 1 + 1 + 2 + 4 + 4 + 4 + 8 + 8 + 1;
+
+/// Bit de `flags` : des masques de confidentialité ont été appliqués
+pub const FRAME_FLAG_MASKED: u8 = 0x01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    Bgra8 = 1,
+    Rgba8 = 2,
+    Rgb8 = 3,
+}
+
+impl PixelFormat {
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            PixelFormat::Bgra8 | PixelFormat::Rgba8 => 4,
+            PixelFormat::Rgb8 => 3,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(PixelFormat::Bgra8),
+            2 => Some(PixelFormat::Rgba8),
+            3 => Some(PixelFormat::Rgb8),
+            _ => None,
+        }
+    }
+}
+
+/// Rectangle en pixels dans le repère d'un écran
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Intersection avec un écran `width` x `height` (None si vide)
+    pub fn clip(&self, width: u32, height: u32) -> Option<Rect> {
+        let x1 = self.x.saturating_add(self.width).min(width);
+        let y1 = self.y.saturating_add(self.height).min(height);
+        if self.x >= x1 || self.y >= y1 {
+            return None;
+        }
+        Some(Rect::new(self.x, self.y, x1 - self.x, y1 - self.y))
+    }
+}
+
+/// Frame écran capturée avec ses métadonnées
+#[derive(Debug, Clone)]
+pub struct ScreenFrame {
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    /// Octets par ligne (peut dépasser width * bpp selon le driver)
+    pub stride: u32,
+    pub format: PixelFormat,
+    pub display_index: u16,
+    /// Horodatage de capture (ms depuis UNIX_EPOCH)
+    pub timestamp: u64,
+    /// PTS de capture (µs, horloge `CAPTURE_CLOCK`)
+    pub pts: u64,
+    /// Au moins un masque de confidentialité a été appliqué
+    pub masked: bool,
+}
+
+impl ScreenFrame {
+    /// Longueur utile d'une ligne de pixels, sans padding
+    pub fn row_len(&self) -> usize {
+        self.width as usize * self.format.bytes_per_pixel()
+    }
+
+    /// Vérifie la cohérence entre géométrie et taille du buffer
+    pub fn validate(&self) -> Result<(), ModuleError> {
+        if (self.stride as usize) < self.row_len() {
+            return Err(ModuleError::ValidationError(format!(
+                "Stride {} smaller than row length {}", self.stride, self.row_len()
+            )));
+        }
+        let expected = self.stride as usize * self.height as usize;
+        if self.data.len() < expected {
+            return Err(ModuleError::ValidationError(format!(
+                "Frame buffer too small: {} < {}", self.data.len(), expected
+            )));
+        }
+        Ok(())
+    }
+
+    /// Copie les pixels en supprimant le padding de fin de ligne (stride == row_len)
+    pub fn to_packed(&self) -> Vec<u8> {
+        let row_len = self.row_len();
+        let stride = self.stride as usize;
+        if stride == row_len {
+            return self.data[..row_len * self.height as usize].to_vec();
+        }
+        let mut packed = Vec::with_capacity(row_len * self.height as usize);
+        for row in self.data.chunks(stride).take(self.height as usize) {
+            packed.extend_from_slice(&row[..row_len]);
+        }
+        packed
+    }
+
+    /// Découpe la frame au rectangle donné (borné à l'écran), pixels compactés
+    /// Renvoie None si le rectangle est hors de l'écran
+    pub fn crop(&self, rect: &Rect) -> Option<ScreenFrame> {
+        let rect = rect.clip(self.width, self.height)?;
+        let bpp = self.format.bytes_per_pixel();
+        let row_len = rect.width as usize * bpp;
+        let mut data = Vec::with_capacity(row_len * rect.height as usize);
+        for y in rect.y..rect.y + rect.height {
+            let start = y as usize * self.stride as usize + rect.x as usize * bpp;
+            data.extend_from_slice(&self.data[start..start + row_len]);
+        }
+        Some(ScreenFrame {
+            data,
+            width: rect.width,
+            height: rect.height,
+            stride: row_len as u32,
+            format: self.format,
+            display_index: self.display_index,
+            timestamp: self.timestamp,
+            pts: self.pts,
+            masked: self.masked,
+        })
+    }
+
+    /// Sérialise l'en-tête (little-endian) :
+    /// version u8 | format u8 | display u16 | width u32 | height u32 | stride u32 | timestamp u64 | pts u64 | flags u8
+    pub fn encode_header(&self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(SCREEN_HEADER_LEN);
+        header.push(SCREEN_HEADER_VERSION);
+        header.push(self.format as u8);
+        header.extend_from_slice(&self.display_index.to_le_bytes());
+        header.extend_from_slice(&self.width.to_le_bytes());
+        header.extend_from_slice(&self.height.to_le_bytes());
+        header.extend_from_slice(&self.stride.to_le_bytes());
+        header.extend_from_slice(&self.timestamp.to_le_bytes());
+        header.extend_from_slice(&self.pts.to_le_bytes());
+        header.push(if self.masked { FRAME_FLAG_MASKED } else { 0 });
+        header
+    }
+
+    /// Relit un en-tête et renvoie une frame sans pixels + le reste du paquet
+    pub fn decode_header(bytes: &[u8]) -> Result<(ScreenFrame, &[u8]), ModuleError> {
+        if bytes.len() < SCREEN_HEADER_LEN {
+            return Err(ModuleError::ValidationError("Screen header truncated".to_string()));
+        }
+        if bytes[0] != SCREEN_HEADER_VERSION {
+            return Err(ModuleError::ValidationError(format!(
+                "Unsupported screen header version {}", bytes[0]
+            )));
+        }
+        let format = PixelFormat::from_u8(bytes[1])
+            .ok_or_else(|| ModuleError::ValidationError(format!("Unknown pixel format {}", bytes[1])))?;
+        let u32_at = |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+
+        let frame = ScreenFrame {
+            data: Vec::new(),
+            width: u32_at(4),
+            height: u32_at(8),
+            stride: u32_at(12),
+            format,
+            display_index: u16::from_le_bytes([bytes[2], bytes[3]]),
+            timestamp: u64::from_le_bytes(bytes[16..24].try_into().unwrap()),
+            pts: u64::from_le_bytes(bytes[24..32].try_into().unwrap()),
+            masked: bytes[32] & FRAME_FLAG_MASKED != 0,
+        };
+        Ok((frame, &bytes[SCREEN_HEADER_LEN..]))
+    }
+}
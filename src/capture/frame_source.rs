@@ -0,0 +1,288 @@
+// visualisation_module/src/capture/frame_source.rs
+
+//! Sources de frames pilotées par `ScreenCapture`
+//! - `ScrapSource` : capture réelle d'un écran via scrap
+//! - `SyntheticSource` : mire de test générée (CI headless, tests du pipeline)
+
+use std::io::ErrorKind;
+use scrap::{Capturer, Display};
+
+use crate::capture::clock::{pts_now, CAPTURE_CLOCK};
+use crate::capture::frame::{PixelFormat, ScreenFrame};
+use crate::config::ConfigFile;
+use crate::error::ModuleError;
+
+/// Octets par pixel des frames produites (BGRA)
+pub const BYTES_PER_PIXEL: usize = 4;
+
+/// Une source de frames écran (un moniteur réel ou synthétique)
+///
+/// Les implémentations ne sont pas forcément `Send` (scrap::Capturer ne l'est pas) :
+/// elles sont créées et utilisées uniquement dans le thread de capture.
+pub trait FrameSource {
+    fn name(&self) -> String;
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+
+    /// Origine de l'écran sur le bureau virtuel, si le backend la connaît
+    fn origin(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    /// Renvoie la prochaine frame, ou `Ok(None)` si aucune n'est encore prête
+    fn next_frame(&mut self) -> Result<Option<ScreenFrame>, ModuleError>;
+}
+
+/// Backend de capture sélectionné via `screen_backend` dans la config
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScreenBackend {
+    Scrap,
+    Synthetic { width: usize, height: usize, displays: usize },
+}
+
+impl ScreenBackend {
+    pub fn from_config(file: &ConfigFile) -> Self {
+        match file.screen_backend.to_lowercase().as_str() {
+            "synthetic" => ScreenBackend::Synthetic {
+                width: file.screen_synthetic_width,
+                height: file.screen_synthetic_height,
+                displays: file.screen_synthetic_displays,
+            },
+            "scrap" => ScreenBackend::Scrap,
+            other => {
+                eprintln!("[frame_source] Unknown screen_backend '{}', using scrap", other);
+                ScreenBackend::Scrap
+            }
+        }
+    }
+
+    /// Ouvre toutes les sources du backend
+    /// À appeler DANS le thread de capture (scrap::Capturer n'est pas Send)
+    pub fn open(&self) -> Result<Vec<Box<dyn FrameSource>>, ModuleError> {
+        match self {
+            ScreenBackend::Scrap => ScrapSource::open_all(),
+            ScreenBackend::Synthetic { width, height, displays } => {
+                if *width == 0 || *height == 0 {
+                    return Err(ModuleError::ConfigError(
+                        "Synthetic screen resolution must be non-zero".to_string(),
+                    ));
+                }
+                Ok((0..(*displays).max(1))
+                    .map(|i| Box::new(SyntheticSource::new(i, *width, *height)) as Box<dyn FrameSource>)
+                    .collect())
+            }
+        }
+    }
+}
+
+// --- Backend scrap ---
+
+pub struct ScrapSource {
+    index: usize,
+    capturer: Capturer,
+}
+
+impl ScrapSource {
+    /// Ouvre un capturer par écran détecté
+    pub fn open_all() -> Result<Vec<Box<dyn FrameSource>>, ModuleError> {
+        let displays = match Display::all() {
+            Ok(d) if !d.is_empty() => d,
+            _ => vec![Display::primary()
+                .map_err(|e| ModuleError::CaptureError(format!("No display available: {}", e)))?],
+        };
+
+        let mut sources: Vec<Box<dyn FrameSource>> = Vec::new();
+        for (index, display) in displays.into_iter().enumerate() {
+            match Capturer::new(display) {
+                Ok(capturer) => sources.push(Box::new(ScrapSource { index, capturer })),
+                Err(e) => eprintln!("[screen] Display {} ignored: {}", index, e),
+            }
+        }
+
+        if sources.is_empty() {
+            return Err(ModuleError::CaptureError("No display could be opened".to_string()));
+        }
+        Ok(sources)
+    }
+}
+
+impl FrameSource for ScrapSource {
+    fn name(&self) -> String {
+        format!("scrap:{}", self.index)
+    }
+
+    fn width(&self) -> usize {
+        self.capturer.width()
+    }
+
+    fn height(&self) -> usize {
+        self.capturer.height()
+    }
+
+    // scrap n'expose pas la position des écrans : origine via `screen_positions`
+
+    fn next_frame(&mut self) -> Result<Option<ScreenFrame>, ModuleError> {
+        let (width, height) = (self.capturer.width(), self.capturer.height());
+        match self.capturer.frame() {
+            Ok(frame) => {
+                // scrap ne donne pas le stride : on le déduit de la taille du buffer
+                let stride = frame.len().checked_div(height).unwrap_or(width * BYTES_PER_PIXEL);
+                let pts = pts_now();
+                Ok(Some(ScreenFrame {
+                    data: frame.to_vec(),
+                    width: width as u32,
+                    height: height as u32,
+                    stride: stride as u32,
+                    format: PixelFormat::Bgra8,
+                    display_index: self.index as u16,
+                    timestamp: CAPTURE_CLOCK.unix_millis(pts),
+                    pts,
+                    masked: false,
+                }))
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(ModuleError::CaptureError(e.to_string())),
+        }
+    }
+}
+
+// --- Backend synthétique ---
+
+/// Mire de test : barres de couleur, carré mobile et horodatage incrusté
+pub struct SyntheticSource {
+    index: usize,
+    width: usize,
+    height: usize,
+    frame_index: u64,
+}
+
+/// Police bitmap 3x5 pour les chiffres 0-9 (une ligne = 3 bits)
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Barres de couleur (BGRA)
+const BARS: [[u8; 4]; 7] = [
+    [192, 192, 192, 255],
+    [0, 192, 192, 255],
+    [192, 192, 0, 255],
+    [0, 192, 0, 255],
+    [192, 0, 192, 255],
+    [0, 0, 192, 255],
+    [192, 0, 0, 255],
+];
+
+impl SyntheticSource {
+    pub fn new(index: usize, width: usize, height: usize) -> Self {
+        Self { index, width, height, frame_index: 0 }
+    }
+
+    /// Génère la frame n° `frame_index` avec l'horodatage `timestamp_ms` incrusté
+    pub fn render(&self, frame_index: u64, timestamp_ms: u128) -> Vec<u8> {
+        let (w, h) = (self.width, self.height);
+        let mut buf = vec![0u8; w * h * BYTES_PER_PIXEL];
+
+        // Barres de couleur verticales
+        for y in 0..h {
+            for x in 0..w {
+                let bar = BARS[(x * BARS.len() / w).min(BARS.len() - 1)];
+                let i = (y * w + x) * BYTES_PER_PIXEL;
+                buf[i..i + BYTES_PER_PIXEL].copy_from_slice(&bar);
+            }
+        }
+
+        // Carré blanc qui rebondit horizontalement, décalé par écran
+        let size = (w.min(h) / 8).max(1);
+        let span = w.saturating_sub(size).max(1) as u64;
+        let step = (frame_index * 8 + self.index as u64 * 97) % (span * 2);
+        let box_x = (if step < span { step } else { span * 2 - step }) as usize;
+        let box_y = (h.saturating_sub(size)) / 2;
+        Self::fill_rect(&mut buf, w, h, box_x, box_y, size, size, [255, 255, 255, 255]);
+
+        // Horodatage et numéro de frame incrustés en haut à gauche
+        let scale = (h / 120).max(1);
+        let text = format!("{}", timestamp_ms % 100_000_000);
+        Self::draw_digits(&mut buf, w, h, 4 * scale, 4 * scale, scale, &text);
+        let text = format!("{}", frame_index);
+        Self::draw_digits(&mut buf, w, h, 4 * scale, 12 * scale, scale, &text);
+
+        buf
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn fill_rect(buf: &mut [u8], w: usize, h: usize, x0: usize, y0: usize, rw: usize, rh: usize, color: [u8; 4]) {
+        for y in y0..(y0 + rh).min(h) {
+            for x in x0..(x0 + rw).min(w) {
+                let i = (y * w + x) * BYTES_PER_PIXEL;
+                buf[i..i + BYTES_PER_PIXEL].copy_from_slice(&color);
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn draw_digits(buf: &mut [u8], w: usize, h: usize, x0: usize, y0: usize, scale: usize, text: &str) {
+        // Fond noir pour la lisibilité
+        let text_w = text.len() * 4 * scale;
+        Self::fill_rect(buf, w, h, x0.saturating_sub(scale), y0.saturating_sub(scale), text_w + scale, 7 * scale, [0, 0, 0, 255]);
+
+        for (n, ch) in text.chars().enumerate() {
+            let Some(d) = ch.to_digit(10) else { continue };
+            let glyph = DIGITS[d as usize];
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) != 0 {
+                        let px = x0 + (n * 4 + col) * scale;
+                        let py = y0 + row * scale;
+                        Self::fill_rect(buf, w, h, px, py, scale, scale, [255, 255, 255, 255]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl FrameSource for SyntheticSource {
+    fn name(&self) -> String {
+        format!("synthetic:{}", self.index)
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Écrans synthétiques alignés côte à côte
+    fn origin(&self) -> Option<(i32, i32)> {
+        Some(((self.index * self.width) as i32, 0))
+    }
+
+    fn next_frame(&mut self) -> Result<Option<ScreenFrame>, ModuleError> {
+        let pts = pts_now();
+        let timestamp = CAPTURE_CLOCK.unix_millis(pts);
+        let data = self.render(self.frame_index, timestamp as u128);
+        self.frame_index += 1;
+        Ok(Some(ScreenFrame {
+            data,
+            width: self.width as u32,
+            height: self.height as u32,
+            stride: (self.width * BYTES_PER_PIXEL) as u32,
+            format: PixelFormat::Bgra8,
+            display_index: self.index as u16,
+            timestamp,
+            pts,
+            masked: false,
+        }))
+    }
+}
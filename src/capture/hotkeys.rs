@@ -0,0 +1,180 @@
+// visualisation_module/src/capture/hotkeys.rs
+
+//! Raccourcis globaux de pilotage de l'enregistrement, détectés par le listener rdev
+//! - actions : `start_stop`, `pause_resume`, `marker`, `save_replay`
+//! - touches au format `KeyChord` (`ctrl+shift+r`) ; gauche et droite confondues
+//!   pour ctrl, shift et meta
+//! - un raccourci se déclenche à l'appui de la touche qui complète la combinaison ;
+//!   la répétition automatique ne le redéclenche pas
+//! - `input_hotkeys_exclude` : l'appui (et le relâchement) de cette touche n'entre pas
+//!   dans le flux d'entrées ; les modificateurs déjà enfoncés restent enregistrés
+//!
+//! Chaque déclenchement produit un `ControlEvent`, lu via `InputCapture::get_control_event`.
+
+use crate::capture::input::InputEventType;
+use crate::capture::keys::KeyChord;
+use crate::config::ConfigFile;
+use crate::error::ModuleError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    /// Démarre / arrête la capture
+    StartStop,
+    /// Suspend / reprend la capture
+    PauseResume,
+    /// Repère horodaté dans l'enregistrement
+    Marker,
+    /// Sauvegarde du tampon de rejeu (tenu par le pool)
+    SaveReplay,
+}
+
+impl HotkeyAction {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "start_stop" | "toggle" | "toggle_capture" => Some(HotkeyAction::StartStop),
+            "pause_resume" | "pause" => Some(HotkeyAction::PauseResume),
+            "marker" | "add_marker" => Some(HotkeyAction::Marker),
+            "save_replay" | "save_replay_buffer" => Some(HotkeyAction::SaveReplay),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HotkeyAction::StartStop => "start_stop",
+            HotkeyAction::PauseResume => "pause_resume",
+            HotkeyAction::Marker => "marker",
+            HotkeyAction::SaveReplay => "save_replay",
+        }
+    }
+}
+
+/// Déclenchement d'un raccourci, horodaté comme l'appui qui l'a provoqué
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlEvent {
+    pub action: HotkeyAction,
+    /// Horodatage (ms depuis UNIX_EPOCH)
+    pub timestamp: u128,
+    /// PTS de l'appui (µs, horloge `CAPTURE_CLOCK`)
+    pub pts: u64,
+}
+
+/// Verdict pour un événement d'entrée
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HotkeyMatch {
+    pub action: Option<HotkeyAction>,
+    /// L'événement ne doit pas être enregistré
+    pub suppress: bool,
+}
+
+/// Côté gauche et droit d'un modificateur confondus
+fn fold_side(key: &str) -> &str {
+    match key {
+        "ctrl_right" => "ctrl_left",
+        "shift_right" => "shift_left",
+        "meta_right" => "meta_left",
+        other => other,
+    }
+}
+
+struct Hotkey {
+    action: HotkeyAction,
+    keys: Vec<&'static str>,
+}
+
+impl Hotkey {
+    fn matches(&self, pressed: &[String]) -> bool {
+        self.keys.len() == pressed.len() && self.keys.iter().all(|k| pressed.iter().any(|p| p == k))
+    }
+}
+
+#[derive(Default)]
+pub struct HotkeyMatcher {
+    hotkeys: Vec<Hotkey>,
+    exclude: bool,
+    /// Touches enfoncées (noms stables, côtés confondus), dans l'ordre d'appui
+    pressed: Vec<String>,
+    /// Touche ayant déclenché le raccourci en cours, jusqu'à son relâchement
+    trigger: Option<String>,
+}
+
+impl HotkeyMatcher {
+    pub fn new(exclude: bool) -> Self {
+        Self { exclude, ..Default::default() }
+    }
+
+    /// Raccourcis de la config (les entrées invalides sont ignorées)
+    pub fn from_config(file: &ConfigFile) -> Self {
+        let mut matcher = Self::new(file.input_hotkeys_exclude);
+        for binding in &file.input_hotkeys {
+            let result = HotkeyAction::from_name(&binding.action)
+                .ok_or_else(|| ModuleError::ConfigError(format!("Unknown hotkey action '{}'", binding.action)))
+                .and_then(|action| matcher.bind(action, &binding.keys));
+            if let Err(e) = result {
+                eprintln!("[hotkeys] Hotkey '{}' ignored: {}", binding.keys, e);
+            }
+        }
+        matcher
+    }
+
+    /// Associe une combinaison à une action
+    pub fn bind(&mut self, action: HotkeyAction, keys: &str) -> Result<(), ModuleError> {
+        let mut folded: Vec<&'static str> = Vec::new();
+        for key in KeyChord::parse(keys)?.keys() {
+            let key = fold_side(key);
+            if !folded.contains(&key) {
+                folded.push(key);
+            }
+        }
+        let pressed: Vec<String> = folded.iter().map(|k| k.to_string()).collect();
+        if let Some(other) = self.hotkeys.iter().find(|h| h.matches(&pressed)) {
+            return Err(ModuleError::ValidationError(format!(
+                "Hotkey '{}' already bound to {}",
+                keys,
+                other.action.name()
+            )));
+        }
+        self.hotkeys.push(Hotkey { action, keys: folded });
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hotkeys.is_empty()
+    }
+
+    /// Suit l'état du clavier ; indique si l'événement déclenche un raccourci
+    /// et s'il doit être exclu de l'enregistrement
+    pub fn on_event(&mut self, event: &InputEventType) -> HotkeyMatch {
+        if self.hotkeys.is_empty() {
+            return HotkeyMatch::default();
+        }
+        match event {
+            InputEventType::KeyPress { key } => {
+                let key = fold_side(key).to_string();
+                if self.pressed.contains(&key) {
+                    // Répétition automatique
+                    let held = self.trigger.as_ref() == Some(&key);
+                    return HotkeyMatch { action: None, suppress: held && self.exclude };
+                }
+                self.pressed.push(key.clone());
+                match self.hotkeys.iter().find(|h| h.matches(&self.pressed)) {
+                    Some(hotkey) => {
+                        self.trigger = Some(key);
+                        HotkeyMatch { action: Some(hotkey.action), suppress: self.exclude }
+                    }
+                    None => HotkeyMatch::default(),
+                }
+            }
+            InputEventType::KeyRelease { key } => {
+                let key = fold_side(key);
+                self.pressed.retain(|k| k != key);
+                if self.trigger.as_deref() == Some(key) {
+                    self.trigger = None;
+                    return HotkeyMatch { action: None, suppress: self.exclude };
+                }
+                HotkeyMatch::default()
+            }
+            _ => HotkeyMatch::default(),
+        }
+    }
+}
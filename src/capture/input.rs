@@ -4,23 +4,39 @@ const MODULE_NAME: &str = "input";
 const MODULE_ID: u8 = 3;
 const MODULE_VERSION: &str = "1.0";
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
-use std::time::{Duration, SystemTime};
-use crossbeam::queue::SegQueue;
+use std::time::Duration;
 use rdev::{listen, EventType};
+use serde::{Deserialize, Serialize};
 use enigo::{Enigo, MouseButton, Key};
 use enigo::{KeyboardControllable, MouseControllable};
+use crate::capture::clock::{pts_now, CAPTURE_CLOCK};
+use crate::capture::hotkeys::{ControlEvent, HotkeyMatcher};
+use crate::capture::input_coalesce::MoveCoalescer;
+use crate::capture::keys::{button_name, key_name, KeyChord};
+use crate::capture::replay::{EnigoInjector, InputInjector};
+use crate::config::{self, ConfigFile};
 use crate::error::ModuleError;
+use crate::metrics::{Metrics, ModuleType};
+use crate::utils::queue::{capacity_for_ram, BoundedQueue, DropPolicy};
 
-#[derive(Debug, Clone)]
+/// Événement d'entrée horodaté ; schéma sérialisé décrit dans `input_codec`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InputEvent {
+    #[serde(rename = "event")]
     pub event_type: InputEventType,
+    /// Horodatage (ms depuis UNIX_EPOCH)
     pub timestamp: u128,
+    /// PTS de l'événement (µs, horloge `CAPTURE_CLOCK`)
+    pub pts: u64,
 }
 
-#[derive(Debug, Clone)]
+/// Touches et boutons portent les noms stables de `keys`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum InputEventType {
     KeyPress { key: String },
     KeyRelease { key: String },
@@ -28,38 +44,111 @@ pub enum InputEventType {
     MouseClick { button: String, x: i32, y: i32 },
     MouseRelease { button: String, x: i32, y: i32 },
     Scroll { dx: i32, dy: i32 },
+    /// Repère de pilotage (`marker`, `save_replay`) transmis au pool ; jamais rejoué
+    Control { action: String },
+}
+
+impl InputEvent {
+    /// Deux mouvements souris successifs peuvent fusionner (seule la position finale compte)
+    pub fn is_mergeable(queued: &InputEvent, new: &InputEvent) -> bool {
+        matches!(
+            (&queued.event_type, &new.event_type),
+            (InputEventType::MouseMove { .. }, InputEventType::MouseMove { .. })
+        )
+    }
 }
 
 pub struct InputCapture {
     inner: Arc<InputInner>,
+    metrics: Option<Arc<Metrics>>,
 }
 
 struct InputInner {
     running: Mutex<bool>,
-    event_buffer: SegQueue<InputEvent>,
+    event_buffer: BoundedQueue<InputEvent>,
     fps: u32,
     last_mouse_x: Mutex<i32>,
     last_mouse_y: Mutex<i32>,
     thread_handle: Mutex<Option<JoinHandle<()>>>,
+    /// Touches maintenues par `send_key_down`, relâchées à l'arrêt
+    held_keys: std::sync::Mutex<Vec<&'static str>>,
+    /// Regroupement des mouvements souris par fenêtre d'échantillonnage
+    coalescer: std::sync::Mutex<MoveCoalescer>,
+    /// Raccourcis globaux et événements de contrôle qu'ils produisent
+    hotkeys: std::sync::Mutex<HotkeyMatcher>,
+    control_events: BoundedQueue<ControlEvent>,
+    /// En pause : seuls les raccourcis sont encore traités
+    paused: AtomicBool,
+}
+
+impl InputInner {
+    /// Passe un événement capturé par le regroupement des mouvements puis le met en file
+    fn record(&self, event: InputEvent, metrics: Option<&Arc<Metrics>>) {
+        let hotkey = self.hotkeys.lock().unwrap().on_event(&event.event_type);
+        if let Some(action) = hotkey.action {
+            eprintln!("[{}] Hotkey {}", MODULE_NAME, action.name());
+            self.control_events.push(ControlEvent { action, timestamp: event.timestamp, pts: event.pts });
+        }
+        if hotkey.suppress || self.paused.load(Ordering::Relaxed) {
+            return;
+        }
+        let ready = self.coalescer.lock().unwrap().push(event);
+        self.enqueue(ready, metrics);
+    }
+
+    /// Met en file les événements prêts (clics et touches sans perte, mouvements regroupés)
+    fn enqueue(&self, events: Vec<InputEvent>, metrics: Option<&Arc<Metrics>>) {
+        for evt in events {
+            let dropped = self.event_buffer.push(evt);
+            if let Some(m) = metrics {
+                m.add_dropped(ModuleType::Input, dropped);
+            }
+        }
+    }
 }
 
 impl InputCapture {
     pub fn new() -> Self {
+        let file = config::CONFIG.lock().unwrap().file.clone();
+        Self::with_config(&file)
+    }
+
+    /// Construit la capture à partir d'une config explicite (tests, outils)
+    pub fn with_config(file: &ConfigFile) -> Self {
         Self {
             inner: Arc::new(InputInner {
                 running: Mutex::new(false),
-                event_buffer: SegQueue::new(),
-                fps: 60,
+                // Coalesce : un mouvement souris remplace le mouvement précédent
+                event_buffer: BoundedQueue::with_merge(
+                    capacity_for_ram(file.input_buffer_capacity, file.ram_gb, 1024, 1024, 65536),
+                    DropPolicy::parse_or(&file.input_drop_policy, DropPolicy::Coalesce),
+                    InputEvent::is_mergeable,
+                ),
+                fps: match file.input_sample_rate {
+                    0 => 60,
+                    rate => rate.min(1000),
+                },
                 last_mouse_x: Mutex::new(0),
                 last_mouse_y: Mutex::new(0),
                 thread_handle: Mutex::new(None),
+                held_keys: std::sync::Mutex::new(Vec::new()),
+                coalescer: std::sync::Mutex::new(MoveCoalescer::from_config(file)),
+                hotkeys: std::sync::Mutex::new(HotkeyMatcher::from_config(file)),
+                control_events: BoundedQueue::new(64, DropPolicy::DropOldest),
+                paused: AtomicBool::new(false),
             }),
+            metrics: None,
         }
     }
 
+    pub fn attach_metrics(&mut self, metrics: Arc<Metrics>) {
+        self.metrics = Some(metrics);
+    }
+
     pub async fn start(&self) {
         let inner = Arc::clone(&self.inner);
         *inner.running.lock().await = true;
+        let metrics = self.metrics.clone();
 
         let handle = tokio::spawn(async move {
             let inner_clone = Arc::clone(&inner);
@@ -70,27 +159,25 @@ impl InputCapture {
                         return;
                     }
 
+                let pts = pts_now();
+                let timestamp = CAPTURE_CLOCK.unix_millis(pts) as u128;
                 let input_event = match event.event_type {
                     EventType::KeyPress(key) => {
                         Some(InputEvent {
                             event_type: InputEventType::KeyPress {
-                                key: format!("{:?}", key),
+                                key: key_name(key),
                             },
-                            timestamp: SystemTime::now()
-                                .duration_since(SystemTime::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_millis(),
+                            timestamp,
+                            pts,
                         })
                     }
                     EventType::KeyRelease(key) => {
                         Some(InputEvent {
                             event_type: InputEventType::KeyRelease {
-                                key: format!("{:?}", key),
+                                key: key_name(key),
                             },
-                            timestamp: SystemTime::now()
-                                .duration_since(SystemTime::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_millis(),
+                            timestamp,
+                            pts,
                         })
                     }
                     EventType::MouseMove { x, y } => {
@@ -104,10 +191,8 @@ impl InputCapture {
                                 x: x as i32,
                                 y: y as i32,
                             },
-                            timestamp: SystemTime::now()
-                                .duration_since(SystemTime::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_millis(),
+                            timestamp,
+                            pts,
                         })
                     }
                     EventType::ButtonPress(button) => {
@@ -115,14 +200,12 @@ impl InputCapture {
                         let y = *inner_blocking.last_mouse_y.blocking_lock();
                         Some(InputEvent {
                             event_type: InputEventType::MouseClick {
-                                button: format!("{:?}", button),
+                                button: button_name(button),
                                 x,
                                 y,
                             },
-                            timestamp: SystemTime::now()
-                                .duration_since(SystemTime::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_millis(),
+                            timestamp,
+                            pts,
                         })
                     }
                     EventType::ButtonRelease(button) => {
@@ -130,14 +213,12 @@ impl InputCapture {
                         let y = *inner_blocking.last_mouse_y.blocking_lock();
                         Some(InputEvent {
                             event_type: InputEventType::MouseRelease {
-                                button: format!("{:?}", button),
+                                button: button_name(button),
                                 x,
                                 y,
                             },
-                            timestamp: SystemTime::now()
-                                .duration_since(SystemTime::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_millis(),
+                            timestamp,
+                            pts,
                         })
                     }
                     EventType::Wheel { delta_x, delta_y } => {
@@ -146,16 +227,17 @@ impl InputCapture {
                                 dx: delta_x as i32,
                                 dy: delta_y as i32,
                             },
-                            timestamp: SystemTime::now()
-                                .duration_since(SystemTime::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_millis(),
+                            timestamp,
+                            pts,
                         })
                     }
                 };
 
                 if let Some(evt) = input_event {
-                    inner_blocking.event_buffer.push(evt);
+                    if let Some(m) = &metrics {
+                        m.mark_activity(ModuleType::Input);
+                    }
+                    inner_blocking.record(evt, metrics.as_ref());
                 }
                 });
             });
@@ -166,17 +248,78 @@ impl InputCapture {
 
     pub async fn stop(&self) {
         *self.inner.running.lock().await = false;
-        
+        if let Err(e) = self.release_keys() {
+            eprintln!("[{}] Cannot release held keys: {}", MODULE_NAME, e);
+        }
+
         // Attendre que le thread de listening se termine
         if let Some(h) = self.inner.thread_handle.lock().await.take() {
             let _ = h.await;
         }
+        // Dernière fenêtre de mouvements
+        let pending = self.inner.coalescer.lock().unwrap().flush();
+        self.inner.enqueue(pending, self.metrics.as_ref());
+    }
+
+    /// Suspend l'enregistrement des entrées ; le listener reste actif pour les raccourcis
+    pub fn pause(&self) {
+        self.inner.paused.store(true, Ordering::Relaxed);
+        // Mouvements en attente : antérieurs à la pause, ils restent dans le flux
+        let pending = self.inner.coalescer.lock().unwrap().flush();
+        self.inner.enqueue(pending, self.metrics.as_ref());
+    }
+
+    pub fn resume(&self) {
+        self.inner.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.inner.paused.load(Ordering::Relaxed)
+    }
+
+    /// Ajoute un événement capturé hors du listener (autre source, tests)
+    pub fn push_event(&self, event: InputEvent) {
+        self.inner.record(event, self.metrics.as_ref());
     }
 
     pub fn get_event(&self) -> Option<InputEvent> {
+        // Fenêtre de mouvements échue : sa position finale devient disponible
+        let expired = self.inner.coalescer.lock().unwrap().flush_expired(pts_now());
+        self.inner.enqueue(expired, self.metrics.as_ref());
         self.inner.event_buffer.pop()
     }
 
+    /// Prochain raccourci déclenché (start/stop, pause, repère, sauvegarde du rejeu)
+    pub fn get_control_event(&self) -> Option<ControlEvent> {
+        self.inner.control_events.pop()
+    }
+
+    /// Insère un raccourci dans le flux d'entrées transmis, à son PTS d'origine
+    /// Passe même en pause ou à l'arrêt : le pool doit voir les repères
+    pub fn push_control(&self, event: &ControlEvent) {
+        let event = InputEvent {
+            event_type: InputEventType::Control { action: event.action.name().to_string() },
+            timestamp: event.timestamp,
+            pts: event.pts,
+        };
+        let ready = self.inner.coalescer.lock().unwrap().push(event);
+        self.inner.enqueue(ready, self.metrics.as_ref());
+    }
+
+    /// Mouvements souris absorbés par le regroupement
+    pub fn get_coalesced(&self) -> u64 {
+        self.inner.coalescer.lock().unwrap().coalesced()
+    }
+
+    /// Événements perdus faute de place dans le buffer
+    pub fn get_dropped(&self) -> u64 {
+        self.inner.event_buffer.dropped()
+    }
+
+    pub fn buffer_capacity(&self) -> usize {
+        self.inner.event_buffer.capacity()
+    }
+
     pub fn get_current_fps(&self) -> u32 {
         self.inner.fps
     }
@@ -186,43 +329,65 @@ impl InputCapture {
     }
 
     // === Contrôle d'entrée ===
+    /// Appui complet sur une touche ou une combinaison (`ctrl+shift+t`) :
+    /// touches enfoncées dans l'ordre puis relâchées dans l'ordre inverse
     pub fn send_key_press(&self, key: &str) -> Result<(), ModuleError> {
-        // Validation: max 50 caractères et alphanumeric
-        if key.is_empty() || key.len() > 50 {
-            return Err(ModuleError::ValidationError("Key length must be 1-50 characters".to_string()));
-        }
-        
-        if !key.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-')) {
-            return Err(ModuleError::ValidationError("Key contains invalid characters".to_string()));
-        }
+        let chord = Self::parse_chord(key)?;
+        Self::inject_keys(&chord.events())?;
 
-        let mut enigo = Enigo::new();
-        
-        // enigo 0.1: utiliser key_click avec Key enum
-        match key.to_lowercase().as_str() {
-            "a" | "b" | "c" | "d" | "e" | "f" | "g" | "h" | "i" | "j" |
-            "k" | "l" | "m" | "n" | "o" | "p" | "q" | "r" | "s" | "t" |
-            "u" | "v" | "w" | "x" | "y" | "z" => {
-                let ch = key.chars().next().unwrap();
-                enigo.key_click(Key::Layout(ch));
-            }
-            "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9" => {
-                let ch = key.chars().next().unwrap();
-                enigo.key_click(Key::Layout(ch));
-            }
-            "return" | "enter" => {
-                enigo.key_click(Key::Return);
-            }
-            "space" => {
-                enigo.key_click(Key::Space);
+        eprintln!("[{}:{}] Key pressed: {} (v{})", MODULE_NAME, MODULE_ID, key, MODULE_VERSION);
+        Ok(())
+    }
+
+    /// Enfonce une touche ou une combinaison sans la relâcher (`send_key_up` ou arrêt)
+    pub fn send_key_down(&self, key: &str) -> Result<(), ModuleError> {
+        let chord = Self::parse_chord(key)?;
+        Self::inject_keys(&chord.press_events())?;
+        let mut held = self.inner.held_keys.lock().unwrap();
+        for k in chord.keys() {
+            if !held.contains(k) {
+                held.push(k);
             }
-            _ => {}
         }
-        
-        eprintln!("[{}:{}] Key pressed: {} (v{})", MODULE_NAME, MODULE_ID, key, MODULE_VERSION);
         Ok(())
     }
 
+    /// Relâche une touche ou une combinaison enfoncée par `send_key_down`
+    pub fn send_key_up(&self, key: &str) -> Result<(), ModuleError> {
+        let chord = Self::parse_chord(key)?;
+        Self::inject_keys(&chord.release_events())?;
+        self.inner.held_keys.lock().unwrap().retain(|k| !chord.keys().contains(k));
+        Ok(())
+    }
+
+    /// Touches actuellement maintenues, dans l'ordre d'appui
+    pub fn held_keys(&self) -> Vec<String> {
+        self.inner.held_keys.lock().unwrap().iter().map(|k| k.to_string()).collect()
+    }
+
+    /// Relâche toutes les touches maintenues (ordre inverse d'appui)
+    pub fn release_keys(&self) -> Result<(), ModuleError> {
+        let held: Vec<&str> = self.inner.held_keys.lock().unwrap().drain(..).rev().collect();
+        if held.is_empty() {
+            return Ok(());
+        }
+        let events: Vec<_> = held.iter().map(|k| InputEventType::KeyRelease { key: k.to_string() }).collect();
+        Self::inject_keys(&events)
+    }
+
+    fn parse_chord(key: &str) -> Result<KeyChord, ModuleError> {
+        // Validation: max 50 caractères
+        if key.trim().is_empty() || key.len() > 50 {
+            return Err(ModuleError::ValidationError("Key length must be 1-50 characters".to_string()));
+        }
+        KeyChord::parse(key)
+    }
+
+    fn inject_keys(events: &[InputEventType]) -> Result<(), ModuleError> {
+        let mut injector = EnigoInjector::new();
+        events.iter().try_for_each(|event| injector.inject(event))
+    }
+
     pub fn send_text(&self, text: &str) -> Result<(), ModuleError> {
         // Validation: max 10000 caractères
         if text.is_empty() || text.len() > 10000 {
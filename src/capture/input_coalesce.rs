@@ -0,0 +1,146 @@
+// visualisation_module/src/capture/input_coalesce.rs
+
+//! Regroupement des mouvements souris par fenêtre d'échantillonnage (`input_sample_rate`)
+//! - une fenêtre s'ouvre au premier mouvement et dure 1 / `input_sample_rate`
+//! - à sa fermeture, seul le dernier mouvement est émis (position finale)
+//! - `input_move_extrema` : émet aussi les points extrêmes du trajet (x/y min/max),
+//!   dans l'ordre chronologique, pour conserver la forme d'un geste rapide
+//! - touches, clics et défilement ne sont jamais regroupés : ils vident d'abord la
+//!   fenêtre en cours, l'ordre des événements est donc préservé
+//!
+//! `input_sample_rate = 0` désactive le regroupement.
+
+use std::time::Duration;
+
+use crate::capture::input::{InputEvent, InputEventType};
+use crate::config::ConfigFile;
+
+/// Points retenus pendant une fenêtre (indices dans `moves`)
+#[derive(Default)]
+struct Extrema {
+    min_x: usize,
+    max_x: usize,
+    min_y: usize,
+    max_y: usize,
+}
+
+pub struct MoveCoalescer {
+    /// Durée d'une fenêtre en µs (0 = pas de regroupement)
+    window_us: u64,
+    keep_extrema: bool,
+    /// Mouvements de la fenêtre ouverte
+    moves: Vec<InputEvent>,
+    extrema: Extrema,
+    /// Mouvements absorbés depuis la création
+    coalesced: u64,
+}
+
+fn position(event: &InputEvent) -> (i32, i32) {
+    match event.event_type {
+        InputEventType::MouseMove { x, y } => (x, y),
+        _ => (0, 0),
+    }
+}
+
+impl MoveCoalescer {
+    pub fn new(window: Duration, keep_extrema: bool) -> Self {
+        Self {
+            window_us: window.as_micros() as u64,
+            keep_extrema,
+            moves: Vec::new(),
+            extrema: Extrema::default(),
+            coalesced: 0,
+        }
+    }
+
+    pub fn from_config(file: &ConfigFile) -> Self {
+        let window = match file.input_sample_rate {
+            0 => Duration::ZERO,
+            rate => Duration::from_secs(1) / rate,
+        };
+        Self::new(window, file.input_move_extrema)
+    }
+
+    pub fn window(&self) -> Duration {
+        Duration::from_micros(self.window_us)
+    }
+
+    /// Mouvements absorbés (non émis)
+    pub fn coalesced(&self) -> u64 {
+        self.coalesced
+    }
+
+    /// Une fenêtre est ouverte (mouvements en attente)
+    pub fn has_pending(&self) -> bool {
+        !self.moves.is_empty()
+    }
+
+    /// Ajoute un événement ; renvoie les événements prêts à être mis en file, dans l'ordre
+    pub fn push(&mut self, event: InputEvent) -> Vec<InputEvent> {
+        if !matches!(event.event_type, InputEventType::MouseMove { .. }) || self.window_us == 0 {
+            let mut ready = self.flush();
+            ready.push(event);
+            return ready;
+        }
+
+        let ready = match self.moves.first() {
+            Some(first) if event.pts.saturating_sub(first.pts) >= self.window_us => self.flush(),
+            _ => Vec::new(),
+        };
+        self.add_move(event);
+        ready
+    }
+
+    /// Ferme la fenêtre si elle a expiré à `now_pts` (µs, horloge de capture)
+    pub fn flush_expired(&mut self, now_pts: u64) -> Vec<InputEvent> {
+        match self.moves.first() {
+            Some(first) if now_pts.saturating_sub(first.pts) >= self.window_us => self.flush(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Ferme la fenêtre en cours quelle que soit son ancienneté
+    pub fn flush(&mut self) -> Vec<InputEvent> {
+        if self.moves.is_empty() {
+            return Vec::new();
+        }
+        let last = self.moves.len() - 1;
+        let mut keep = vec![last];
+        if self.keep_extrema {
+            let e = &self.extrema;
+            keep.extend([e.min_x, e.max_x, e.min_y, e.max_y]);
+            keep.sort_unstable();
+            keep.dedup();
+        }
+        self.coalesced += (self.moves.len() - keep.len()) as u64;
+
+        let mut moves = std::mem::take(&mut self.moves);
+        self.extrema = Extrema::default();
+        // Retrait par indices décroissants : les indices restants ne bougent pas
+        let mut kept: Vec<InputEvent> = keep.iter().rev().map(|&i| moves.swap_remove(i)).collect();
+        kept.reverse();
+        kept
+    }
+
+    fn add_move(&mut self, event: InputEvent) {
+        let (x, y) = position(&event);
+        let index = self.moves.len();
+        if index > 0 {
+            let e = &mut self.extrema;
+            let at = |i: usize| position(&self.moves[i]);
+            if x < at(e.min_x).0 {
+                e.min_x = index;
+            }
+            if x > at(e.max_x).0 {
+                e.max_x = index;
+            }
+            if y < at(e.min_y).1 {
+                e.min_y = index;
+            }
+            if y > at(e.max_y).1 {
+                e.max_y = index;
+            }
+        }
+        self.moves.push(event);
+    }
+}
@@ -0,0 +1,185 @@
+// visualisation_module/src/capture/input_codec.rs
+
+//! Sérialisation des `InputEvent` envoyés au pool, sélectionnée via `input_serialization`
+//! Le décodeur reconnaît les deux formats : le pool et les outils de rejeu le partagent.
+//!
+//! JSON (`json`) : un objet par événement, touches et boutons nommés par `keys`
+//!   {"event":{"type":"key_press","key":"a"},"timestamp":1700000000000,"pts":1234}
+//!   types : key_press / key_release {key}, mouse_move {x, y},
+//!           mouse_click / mouse_release {button, x, y}, scroll {dx, dy}, control {action}
+//!
+//! Binaire (`binary`) :
+//! version u8 | type u8 | timestamp u64 LE (ms) | pts u64 LE (µs) | corps
+//!   key_press (1), key_release (2)     : longueur u8 | nom UTF-8
+//!   mouse_move (3), scroll (6)         : x/dx i32 LE | y/dy i32 LE
+//!   mouse_click (4), mouse_release (5) : longueur u8 | nom UTF-8 | x i32 LE | y i32 LE
+//!   control (7)                        : longueur u8 | action UTF-8
+
+use crate::capture::input::{InputEvent, InputEventType};
+use crate::config::ConfigFile;
+use crate::error::ModuleError;
+
+/// Version du format binaire (ne peut pas être confondue avec le `{` du JSON)
+pub const INPUT_PACKET_VERSION: u8 = 0x01;
+pub const INPUT_HEADER_LEN: usize = 18;
+
+const KIND_KEY_PRESS: u8 = 1;
+const KIND_KEY_RELEASE: u8 = 2;
+const KIND_MOUSE_MOVE: u8 = 3;
+const KIND_MOUSE_CLICK: u8 = 4;
+const KIND_MOUSE_RELEASE: u8 = 5;
+const KIND_SCROLL: u8 = 6;
+const KIND_CONTROL: u8 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Json,
+    Binary,
+}
+
+impl InputFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "json" => Some(InputFormat::Json),
+            "binary" | "bin" | "compact" => Some(InputFormat::Binary),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct InputEncoder {
+    format: InputFormat,
+}
+
+impl InputEncoder {
+    pub fn new(format: InputFormat) -> Self {
+        Self { format }
+    }
+
+    /// Format de la config (JSON si le nom est inconnu)
+    pub fn from_config(file: &ConfigFile) -> Self {
+        let format = InputFormat::from_name(&file.input_serialization).unwrap_or_else(|| {
+            eprintln!("[input] Unknown input_serialization '{}', using json", file.input_serialization);
+            InputFormat::Json
+        });
+        Self::new(format)
+    }
+
+    pub fn format(&self) -> InputFormat {
+        self.format
+    }
+
+    pub fn encode(&self, event: &InputEvent) -> Result<Vec<u8>, ModuleError> {
+        match self.format {
+            InputFormat::Json => serde_json::to_vec(event)
+                .map_err(|e| ModuleError::ValidationError(format!("Input event not serializable: {}", e))),
+            InputFormat::Binary => encode_binary(event),
+        }
+    }
+
+    /// Décode un événement JSON ou binaire (côté pool, rejeu)
+    pub fn decode(bytes: &[u8]) -> Result<InputEvent, ModuleError> {
+        match bytes.first() {
+            Some(&INPUT_PACKET_VERSION) => decode_binary(bytes),
+            Some(b'{') => serde_json::from_slice(bytes)
+                .map_err(|e| ModuleError::ValidationError(format!("Invalid input event JSON: {}", e))),
+            Some(v) => Err(ModuleError::ValidationError(format!("Unsupported input packet version {}", v))),
+            None => Err(ModuleError::ValidationError("Input packet empty".to_string())),
+        }
+    }
+}
+
+fn push_name(out: &mut Vec<u8>, name: &str) -> Result<(), ModuleError> {
+    let len = u8::try_from(name.len())
+        .map_err(|_| ModuleError::ValidationError(format!("Input name too long ({} bytes)", name.len())))?;
+    out.push(len);
+    out.extend_from_slice(name.as_bytes());
+    Ok(())
+}
+
+fn encode_binary(event: &InputEvent) -> Result<Vec<u8>, ModuleError> {
+    let mut out = Vec::with_capacity(INPUT_HEADER_LEN + 16);
+    let kind = match &event.event_type {
+        InputEventType::KeyPress { .. } => KIND_KEY_PRESS,
+        InputEventType::KeyRelease { .. } => KIND_KEY_RELEASE,
+        InputEventType::MouseMove { .. } => KIND_MOUSE_MOVE,
+        InputEventType::MouseClick { .. } => KIND_MOUSE_CLICK,
+        InputEventType::MouseRelease { .. } => KIND_MOUSE_RELEASE,
+        InputEventType::Scroll { .. } => KIND_SCROLL,
+        InputEventType::Control { .. } => KIND_CONTROL,
+    };
+    out.extend_from_slice(&[INPUT_PACKET_VERSION, kind]);
+    out.extend_from_slice(&(event.timestamp as u64).to_le_bytes());
+    out.extend_from_slice(&event.pts.to_le_bytes());
+
+    match &event.event_type {
+        InputEventType::KeyPress { key } | InputEventType::KeyRelease { key } => push_name(&mut out, key)?,
+        InputEventType::Control { action } => push_name(&mut out, action)?,
+        InputEventType::MouseMove { x, y } => {
+            out.extend_from_slice(&x.to_le_bytes());
+            out.extend_from_slice(&y.to_le_bytes());
+        }
+        InputEventType::MouseClick { button, x, y } | InputEventType::MouseRelease { button, x, y } => {
+            push_name(&mut out, button)?;
+            out.extend_from_slice(&x.to_le_bytes());
+            out.extend_from_slice(&y.to_le_bytes());
+        }
+        InputEventType::Scroll { dx, dy } => {
+            out.extend_from_slice(&dx.to_le_bytes());
+            out.extend_from_slice(&dy.to_le_bytes());
+        }
+    }
+    Ok(out)
+}
+
+/// Lecteur séquentiel du corps binaire
+struct Cursor<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ModuleError> {
+        if self.bytes.len() < n {
+            return Err(ModuleError::ValidationError("Input packet truncated".to_string()));
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn i32(&mut self) -> Result<i32, ModuleError> {
+        Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn name(&mut self) -> Result<String, ModuleError> {
+        let len = self.take(1)?[0] as usize;
+        String::from_utf8(self.take(len)?.to_vec())
+            .map_err(|_| ModuleError::ValidationError("Input name is not UTF-8".to_string()))
+    }
+}
+
+fn decode_binary(bytes: &[u8]) -> Result<InputEvent, ModuleError> {
+    if bytes.len() < INPUT_HEADER_LEN {
+        return Err(ModuleError::ValidationError("Input packet truncated".to_string()));
+    }
+    let kind = bytes[1];
+    let timestamp = u64::from_le_bytes(bytes[2..10].try_into().unwrap()) as u128;
+    let pts = u64::from_le_bytes(bytes[10..18].try_into().unwrap());
+    let mut body = Cursor { bytes: &bytes[INPUT_HEADER_LEN..] };
+
+    let event_type = match kind {
+        KIND_KEY_PRESS => InputEventType::KeyPress { key: body.name()? },
+        KIND_KEY_RELEASE => InputEventType::KeyRelease { key: body.name()? },
+        KIND_MOUSE_MOVE => InputEventType::MouseMove { x: body.i32()?, y: body.i32()? },
+        KIND_MOUSE_CLICK => InputEventType::MouseClick { button: body.name()?, x: body.i32()?, y: body.i32()? },
+        KIND_MOUSE_RELEASE => InputEventType::MouseRelease { button: body.name()?, x: body.i32()?, y: body.i32()? },
+        KIND_SCROLL => InputEventType::Scroll { dx: body.i32()?, dy: body.i32()? },
+        KIND_CONTROL => InputEventType::Control { action: body.name()? },
+        other => return Err(ModuleError::ValidationError(format!("Unknown input event type {}", other))),
+    };
+    if !body.bytes.is_empty() {
+        return Err(ModuleError::ValidationError("Input packet has trailing bytes".to_string()));
+    }
+    Ok(InputEvent { event_type, timestamp, pts })
+}
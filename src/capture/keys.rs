@@ -0,0 +1,342 @@
+// visualisation_module/src/capture/keys.rs
+
+//! Noms stables des touches et boutons dans les événements d'entrée
+//! Indépendants des `Debug` de rdev : ce sont ceux du schéma `InputEvent`
+//! (JSON et binaire), relus par le pool et les outils de rejeu.
+//!
+//! - lettres et chiffres : `a`..`z`, `0`..`9`
+//! - fonctions : `f1`..`f12` ; pavé numérique : `kp_0`..`kp_9`, `kp_enter`...
+//! - modificateurs : `shift_left`, `ctrl_left`, `alt`, `alt_gr`, `meta_left`...
+//! - touche inconnue : `unknown_<code>` ; bouton inconnu : `button_<n>`
+//!
+//! Saisie (`send_key_press`, rejeu) : `canonical_key_name` accepte aussi des synonymes
+//! (`ctrl`, `esc`, `pgup`, `-`...), `enigo_key` donne la touche injectable et
+//! `KeyChord` décrit une combinaison `ctrl+shift+t`.
+
+use rdev::{Button, Key};
+
+use enigo::Key as EnigoKey;
+
+use crate::capture::input::InputEventType;
+use crate::error::ModuleError;
+
+/// Table de correspondance touche rdev <-> nom stable
+const KEY_NAMES: &[(Key, &str)] = &[
+    (Key::Alt, "alt"),
+    (Key::AltGr, "alt_gr"),
+    (Key::Backspace, "backspace"),
+    (Key::CapsLock, "caps_lock"),
+    (Key::ControlLeft, "ctrl_left"),
+    (Key::ControlRight, "ctrl_right"),
+    (Key::Delete, "delete"),
+    (Key::DownArrow, "down"),
+    (Key::End, "end"),
+    (Key::Escape, "escape"),
+    (Key::F1, "f1"),
+    (Key::F2, "f2"),
+    (Key::F3, "f3"),
+    (Key::F4, "f4"),
+    (Key::F5, "f5"),
+    (Key::F6, "f6"),
+    (Key::F7, "f7"),
+    (Key::F8, "f8"),
+    (Key::F9, "f9"),
+    (Key::F10, "f10"),
+    (Key::F11, "f11"),
+    (Key::F12, "f12"),
+    (Key::Home, "home"),
+    (Key::LeftArrow, "left"),
+    (Key::MetaLeft, "meta_left"),
+    (Key::MetaRight, "meta_right"),
+    (Key::PageDown, "page_down"),
+    (Key::PageUp, "page_up"),
+    (Key::Return, "enter"),
+    (Key::RightArrow, "right"),
+    (Key::ShiftLeft, "shift_left"),
+    (Key::ShiftRight, "shift_right"),
+    (Key::Space, "space"),
+    (Key::Tab, "tab"),
+    (Key::UpArrow, "up"),
+    (Key::PrintScreen, "print_screen"),
+    (Key::ScrollLock, "scroll_lock"),
+    (Key::Pause, "pause"),
+    (Key::NumLock, "num_lock"),
+    (Key::BackQuote, "backquote"),
+    (Key::Num0, "0"),
+    (Key::Num1, "1"),
+    (Key::Num2, "2"),
+    (Key::Num3, "3"),
+    (Key::Num4, "4"),
+    (Key::Num5, "5"),
+    (Key::Num6, "6"),
+    (Key::Num7, "7"),
+    (Key::Num8, "8"),
+    (Key::Num9, "9"),
+    (Key::Minus, "minus"),
+    (Key::Equal, "equal"),
+    (Key::KeyA, "a"),
+    (Key::KeyB, "b"),
+    (Key::KeyC, "c"),
+    (Key::KeyD, "d"),
+    (Key::KeyE, "e"),
+    (Key::KeyF, "f"),
+    (Key::KeyG, "g"),
+    (Key::KeyH, "h"),
+    (Key::KeyI, "i"),
+    (Key::KeyJ, "j"),
+    (Key::KeyK, "k"),
+    (Key::KeyL, "l"),
+    (Key::KeyM, "m"),
+    (Key::KeyN, "n"),
+    (Key::KeyO, "o"),
+    (Key::KeyP, "p"),
+    (Key::KeyQ, "q"),
+    (Key::KeyR, "r"),
+    (Key::KeyS, "s"),
+    (Key::KeyT, "t"),
+    (Key::KeyU, "u"),
+    (Key::KeyV, "v"),
+    (Key::KeyW, "w"),
+    (Key::KeyX, "x"),
+    (Key::KeyY, "y"),
+    (Key::KeyZ, "z"),
+    (Key::LeftBracket, "left_bracket"),
+    (Key::RightBracket, "right_bracket"),
+    (Key::SemiColon, "semicolon"),
+    (Key::Quote, "quote"),
+    (Key::BackSlash, "backslash"),
+    (Key::IntlBackslash, "intl_backslash"),
+    (Key::Comma, "comma"),
+    (Key::Dot, "dot"),
+    (Key::Slash, "slash"),
+    (Key::Insert, "insert"),
+    (Key::KpReturn, "kp_enter"),
+    (Key::KpMinus, "kp_minus"),
+    (Key::KpPlus, "kp_plus"),
+    (Key::KpMultiply, "kp_multiply"),
+    (Key::KpDivide, "kp_divide"),
+    (Key::Kp0, "kp_0"),
+    (Key::Kp1, "kp_1"),
+    (Key::Kp2, "kp_2"),
+    (Key::Kp3, "kp_3"),
+    (Key::Kp4, "kp_4"),
+    (Key::Kp5, "kp_5"),
+    (Key::Kp6, "kp_6"),
+    (Key::Kp7, "kp_7"),
+    (Key::Kp8, "kp_8"),
+    (Key::Kp9, "kp_9"),
+    (Key::KpDelete, "kp_delete"),
+    (Key::Function, "fn"),
+];
+
+/// Synonymes acceptés en saisie -> nom stable
+const KEY_ALIASES: &[(&str, &str)] = &[
+    ("ctrl", "ctrl_left"),
+    ("control", "ctrl_left"),
+    ("shift", "shift_left"),
+    ("altgr", "alt_gr"),
+    ("meta", "meta_left"),
+    ("super", "meta_left"),
+    ("win", "meta_left"),
+    ("cmd", "meta_left"),
+    ("return", "enter"),
+    ("esc", "escape"),
+    ("del", "delete"),
+    ("ins", "insert"),
+    ("pgup", "page_up"),
+    ("pgdn", "page_down"),
+    ("print", "print_screen"),
+    ("prtsc", "print_screen"),
+    ("capslock", "caps_lock"),
+    ("numlock", "num_lock"),
+    ("-", "minus"),
+    ("=", "equal"),
+    ("[", "left_bracket"),
+    ("]", "right_bracket"),
+    (";", "semicolon"),
+    ("'", "quote"),
+    ("\\", "backslash"),
+    (",", "comma"),
+    (".", "dot"),
+    ("/", "slash"),
+    ("`", "backquote"),
+];
+
+/// Nom stable d'une touche rdev
+pub fn key_name(key: Key) -> String {
+    if let Key::Unknown(code) = key {
+        return format!("unknown_{}", code);
+    }
+    KEY_NAMES
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map_or_else(|| format!("{:?}", key).to_lowercase(), |(_, name)| name.to_string())
+}
+
+/// Touche rdev d'un nom stable (inverse de `key_name`)
+pub fn key_from_name(name: &str) -> Option<Key> {
+    let name = name.to_lowercase();
+    if let Some(code) = name.strip_prefix("unknown_") {
+        return code.parse().ok().map(Key::Unknown);
+    }
+    KEY_NAMES.iter().find(|(_, n)| *n == name).map(|(k, _)| *k)
+}
+
+/// Nom stable d'un bouton de souris rdev
+pub fn button_name(button: Button) -> String {
+    match button {
+        Button::Left => "left".to_string(),
+        Button::Right => "right".to_string(),
+        Button::Middle => "middle".to_string(),
+        Button::Unknown(n) => format!("button_{}", n),
+    }
+}
+
+/// Nom stable d'une touche saisie (nom stable ou synonyme, casse ignorée)
+pub fn canonical_key_name(name: &str) -> Option<&'static str> {
+    let name = name.trim().to_lowercase();
+    let name = KEY_ALIASES.iter().find(|(alias, _)| *alias == name).map_or(name.as_str(), |(_, n)| *n);
+    KEY_NAMES.iter().find(|(_, n)| *n == name).map(|(_, n)| *n)
+}
+
+/// Touche enigo d'un nom stable ou synonyme (None si l'injection ne la gère pas)
+pub fn enigo_key(name: &str) -> Option<EnigoKey> {
+    let name = canonical_key_name(name)?;
+    let layout = |ch: char| Some(EnigoKey::Layout(ch));
+    match name {
+        "ctrl_left" => Some(EnigoKey::LControl),
+        "ctrl_right" => Some(EnigoKey::RControl),
+        "shift_left" => Some(EnigoKey::LShift),
+        "shift_right" => Some(EnigoKey::RShift),
+        "alt" => Some(EnigoKey::Alt),
+        "meta_left" | "meta_right" => Some(EnigoKey::Meta),
+        "enter" => Some(EnigoKey::Return),
+        "space" => Some(EnigoKey::Space),
+        "tab" => Some(EnigoKey::Tab),
+        "escape" => Some(EnigoKey::Escape),
+        "backspace" => Some(EnigoKey::Backspace),
+        "delete" => Some(EnigoKey::Delete),
+        "home" => Some(EnigoKey::Home),
+        "end" => Some(EnigoKey::End),
+        "page_up" => Some(EnigoKey::PageUp),
+        "page_down" => Some(EnigoKey::PageDown),
+        "up" => Some(EnigoKey::UpArrow),
+        "down" => Some(EnigoKey::DownArrow),
+        "left" => Some(EnigoKey::LeftArrow),
+        "right" => Some(EnigoKey::RightArrow),
+        "caps_lock" => Some(EnigoKey::CapsLock),
+        "f1" => Some(EnigoKey::F1),
+        "f2" => Some(EnigoKey::F2),
+        "f3" => Some(EnigoKey::F3),
+        "f4" => Some(EnigoKey::F4),
+        "f5" => Some(EnigoKey::F5),
+        "f6" => Some(EnigoKey::F6),
+        "f7" => Some(EnigoKey::F7),
+        "f8" => Some(EnigoKey::F8),
+        "f9" => Some(EnigoKey::F9),
+        "f10" => Some(EnigoKey::F10),
+        "f11" => Some(EnigoKey::F11),
+        "f12" => Some(EnigoKey::F12),
+        "minus" => layout('-'),
+        "equal" => layout('='),
+        "left_bracket" => layout('['),
+        "right_bracket" => layout(']'),
+        "semicolon" => layout(';'),
+        "quote" => layout('\''),
+        "backslash" => layout('\\'),
+        "comma" => layout(','),
+        "dot" => layout('.'),
+        "slash" => layout('/'),
+        "backquote" => layout('`'),
+        #[cfg(not(target_os = "macos"))]
+        "insert" => Some(EnigoKey::Insert),
+        #[cfg(not(target_os = "macos"))]
+        "num_lock" => Some(EnigoKey::Numlock),
+        #[cfg(not(target_os = "macos"))]
+        "print_screen" => Some(EnigoKey::Print),
+        #[cfg(not(target_os = "macos"))]
+        "pause" => Some(EnigoKey::Pause),
+        #[cfg(target_os = "linux")]
+        "scroll_lock" => Some(EnigoKey::ScrollLock),
+        // Lettres et chiffres
+        letter if letter.len() == 1 => letter.chars().next().and_then(layout),
+        #[cfg(target_os = "linux")]
+        other => x11_keycode(other).map(EnigoKey::Raw),
+        #[cfg(not(target_os = "linux"))]
+        _ => None,
+    }
+}
+
+/// Keycodes X11 (evdev) des touches sans équivalent enigo
+#[cfg(target_os = "linux")]
+fn x11_keycode(name: &str) -> Option<u16> {
+    let code = match name {
+        "alt_gr" => 108,
+        "intl_backslash" => 94,
+        "kp_0" => 90,
+        "kp_1" => 87,
+        "kp_2" => 88,
+        "kp_3" => 89,
+        "kp_4" => 83,
+        "kp_5" => 84,
+        "kp_6" => 85,
+        "kp_7" => 79,
+        "kp_8" => 80,
+        "kp_9" => 81,
+        "kp_enter" => 104,
+        "kp_minus" => 82,
+        "kp_plus" => 86,
+        "kp_multiply" => 63,
+        "kp_divide" => 106,
+        "kp_delete" => 91,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Combinaison de touches (`ctrl+shift+t`) : appuyées dans l'ordre, maintenues,
+/// puis relâchées dans l'ordre inverse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChord {
+    keys: Vec<&'static str>,
+}
+
+impl KeyChord {
+    /// Touches séparées par `+` ; toute touche non injectable est refusée
+    pub fn parse(spec: &str) -> Result<Self, ModuleError> {
+        let mut keys = Vec::new();
+        for part in spec.split('+') {
+            if part.trim().is_empty() {
+                return Err(ModuleError::ValidationError(format!("Empty key in '{}'", spec)));
+            }
+            let key = canonical_key_name(part)
+                .filter(|name| enigo_key(name).is_some())
+                .ok_or_else(|| ModuleError::ValidationError(format!("Unknown key '{}'", part.trim())))?;
+            if keys.contains(&key) {
+                return Err(ModuleError::ValidationError(format!("Key '{}' repeated in '{}'", key, spec)));
+            }
+            keys.push(key);
+        }
+        Ok(Self { keys })
+    }
+
+    /// Noms stables, dans l'ordre d'appui
+    pub fn keys(&self) -> &[&'static str] {
+        &self.keys
+    }
+
+    pub fn press_events(&self) -> Vec<InputEventType> {
+        self.keys.iter().map(|k| InputEventType::KeyPress { key: k.to_string() }).collect()
+    }
+
+    pub fn release_events(&self) -> Vec<InputEventType> {
+        self.keys.iter().rev().map(|k| InputEventType::KeyRelease { key: k.to_string() }).collect()
+    }
+
+    /// Appui complet : toutes les touches enfoncées puis relâchées
+    pub fn events(&self) -> Vec<InputEventType> {
+        let mut events = self.press_events();
+        events.extend(self.release_events());
+        events
+    }
+}
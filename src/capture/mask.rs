@@ -0,0 +1,138 @@
+// visualisation_module/src/capture/mask.rs
+
+//! Masques de confidentialité appliqués dans le thread de capture,
+//! avant que la frame n'atteigne le buffer (gestionnaires de mots de passe,
+//! fenêtres de chat, zones de données client...)
+//! Les coordonnées sont celles de l'écran complet, indépendamment de la découpe ROI.
+
+use crate::capture::frame::{PixelFormat, Rect, ScreenFrame};
+use crate::config::ScreenMask;
+use crate::error::ModuleError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskStyle {
+    /// Remplissage uni (couleur RGB)
+    Fill { color: [u8; 3] },
+    /// Moyenne par blocs de `block` x `block` pixels
+    Pixelate { block: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivacyMask {
+    pub display: u16,
+    pub rect: Rect,
+    pub style: MaskStyle,
+}
+
+impl PrivacyMask {
+    pub fn fill(display: u16, rect: Rect) -> Self {
+        Self { display, rect, style: MaskStyle::Fill { color: [0, 0, 0] } }
+    }
+
+    pub fn pixelate(display: u16, rect: Rect, block: u32) -> Self {
+        Self { display, rect, style: MaskStyle::Pixelate { block: block.max(2) } }
+    }
+
+    pub fn from_config(mask: &ScreenMask) -> Result<Self, ModuleError> {
+        let rect = Rect::new(mask.x, mask.y, mask.width, mask.height);
+        let style = match mask.style.to_lowercase().as_str() {
+            "fill" | "solid" => MaskStyle::Fill { color: mask.color },
+            "pixelate" | "pixelize" => MaskStyle::Pixelate { block: mask.block.max(2) },
+            other => {
+                return Err(ModuleError::ConfigError(format!("Unknown screen mask style '{}'", other)));
+            }
+        };
+        Ok(Self { display: mask.display, rect, style })
+    }
+
+    /// Lit les masques de la config ; un masque invalide est remplacé par un
+    /// remplissage uni plutôt qu'ignoré (on ne laisse jamais passer la zone)
+    pub fn all_from_config(masks: &[ScreenMask]) -> Vec<Self> {
+        masks
+            .iter()
+            .map(|m| {
+                Self::from_config(m).unwrap_or_else(|e| {
+                    eprintln!("[mask] {}, falling back to solid fill", e);
+                    Self::fill(m.display, Rect::new(m.x, m.y, m.width, m.height))
+                })
+            })
+            .collect()
+    }
+
+    /// Applique le masque s'il concerne l'écran de la frame ; renvoie true si des pixels ont été masqués
+    pub fn apply(&self, frame: &mut ScreenFrame) -> bool {
+        if self.display != frame.display_index {
+            return false;
+        }
+        let Some(rect) = self.rect.clip(frame.width, frame.height) else { return false };
+        match self.style {
+            MaskStyle::Fill { color } => fill(frame, &rect, color),
+            MaskStyle::Pixelate { block } => pixelate(frame, &rect, block),
+        }
+        true
+    }
+}
+
+/// Applique tous les masques et marque la frame si au moins un l'a touchée
+pub fn apply_masks(frame: &mut ScreenFrame, masks: &[PrivacyMask]) -> bool {
+    let mut masked = false;
+    for mask in masks {
+        masked |= mask.apply(frame);
+    }
+    frame.masked |= masked;
+    masked
+}
+
+/// Octets d'un pixel RGB dans le format de la frame
+fn pixel_bytes(format: PixelFormat, [r, g, b]: [u8; 3]) -> ([u8; 4], usize) {
+    match format {
+        PixelFormat::Bgra8 => ([b, g, r, 255], 4),
+        PixelFormat::Rgba8 => ([r, g, b, 255], 4),
+        PixelFormat::Rgb8 => ([r, g, b, 0], 3),
+    }
+}
+
+fn fill(frame: &mut ScreenFrame, rect: &Rect, color: [u8; 3]) {
+    let (px, bpp) = pixel_bytes(frame.format, color);
+    let stride = frame.stride as usize;
+    for y in rect.y..rect.y + rect.height {
+        let row = y as usize * stride;
+        for x in rect.x..rect.x + rect.width {
+            let i = row + x as usize * bpp;
+            frame.data[i..i + bpp].copy_from_slice(&px[..bpp]);
+        }
+    }
+}
+
+fn pixelate(frame: &mut ScreenFrame, rect: &Rect, block: u32) {
+    let bpp = frame.format.bytes_per_pixel();
+    let stride = frame.stride as usize;
+    let (x_end, y_end) = (rect.x + rect.width, rect.y + rect.height);
+
+    for by in (rect.y..y_end).step_by(block as usize) {
+        for bx in (rect.x..x_end).step_by(block as usize) {
+            let cell = Rect::new(bx, by, block.min(x_end - bx), block.min(y_end - by));
+
+            // Moyenne des composantes couleur du bloc
+            let mut sum = [0u64; 3];
+            for y in cell.y..cell.y + cell.height {
+                for x in cell.x..cell.x + cell.width {
+                    let i = y as usize * stride + x as usize * bpp;
+                    for (c, s) in sum.iter_mut().enumerate() {
+                        *s += frame.data[i + c] as u64;
+                    }
+                }
+            }
+            let n = (cell.width * cell.height) as u64;
+            let avg = sum.map(|s| (s / n) as u8);
+
+            // `avg` est dans l'ordre du format : on le réécrit tel quel
+            for y in cell.y..cell.y + cell.height {
+                for x in cell.x..cell.x + cell.width {
+                    let i = y as usize * stride + x as usize * bpp;
+                    frame.data[i..i + 3].copy_from_slice(&avg);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+// visualisation_module/src/capture/meter.rs
+
+//! Mesure des niveaux audio dans le pipeline de capture
+//! - RMS et crête en dBFS, échantillons écrêtés
+//! - spectre grossier optionnel (`audio_spectrum_bands` bandes logarithmiques, FFT)
+//!
+//! Les mesures sont remontées à `Metrics` par piste (micro, son du système).
+
+use crate::capture::audio::AudioFrame;
+use crate::metrics::AudioLevels;
+
+/// Niveau plancher en dBFS (silence numérique)
+pub const SILENCE_DB: f32 = -100.0;
+
+/// Amplitude à partir de laquelle un échantillon est compté comme écrêté
+const CLIP_LEVEL: f32 = 0.999;
+
+/// Taille maximale de la fenêtre FFT
+const MAX_FFT: usize = 2048;
+
+/// Fréquence basse de la première bande du spectre
+const LOWEST_BAND_HZ: f32 = 20.0;
+
+#[derive(Debug, Clone)]
+pub struct AudioMeter {
+    /// Nombre de bandes du spectre (0 = pas de FFT)
+    bands: usize,
+}
+
+pub fn to_db(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return SILENCE_DB;
+    }
+    (20.0 * amplitude.log10()).max(SILENCE_DB)
+}
+
+impl AudioMeter {
+    pub fn new(bands: usize) -> Self {
+        Self { bands: bands.min(64) }
+    }
+
+    pub fn bands(&self) -> usize {
+        self.bands
+    }
+
+    /// Niveaux d'une frame (tous canaux confondus)
+    pub fn measure(&self, frame: &AudioFrame) -> AudioLevels {
+        let mut sum = 0.0f64;
+        let mut peak = 0.0f32;
+        let mut clipped = 0u64;
+        for &s in &frame.samples {
+            let a = s.abs();
+            sum += (a * a) as f64;
+            peak = peak.max(a);
+            if a >= CLIP_LEVEL {
+                clipped += 1;
+            }
+        }
+        let rms = if frame.samples.is_empty() { 0.0 } else { (sum / frame.samples.len() as f64).sqrt() as f32 };
+
+        AudioLevels {
+            rms_db: to_db(rms),
+            peak_db: to_db(peak),
+            clipped,
+            spectrum_db: if self.bands > 0 { self.spectrum(frame) } else { Vec::new() },
+        }
+    }
+
+    /// Énergie par bande (dBFS) sur la fin de la frame ramenée en mono
+    fn spectrum(&self, frame: &AudioFrame) -> Vec<f32> {
+        let channels = frame.channels.max(1) as usize;
+        let mono: Vec<f32> = frame
+            .samples
+            .chunks_exact(channels)
+            .map(|c| c.iter().sum::<f32>() / channels as f32)
+            .collect();
+        // Plus grande puissance de deux disponible
+        let available = mono.len().min(MAX_FFT);
+        if available < 2 {
+            return vec![SILENCE_DB; self.bands];
+        }
+        let size = 1usize << available.ilog2();
+
+        // Fenêtre de Hann sur les `size` derniers échantillons
+        let window = &mono[mono.len() - size..];
+        let mut re: Vec<f32> = window
+            .iter()
+            .enumerate()
+            .map(|(i, s)| s * 0.5 * (1.0 - (std::f32::consts::TAU * i as f32 / (size - 1) as f32).cos()))
+            .collect();
+        let mut im = vec![0.0f32; size];
+        fft(&mut re, &mut im);
+
+        // Amplitude normalisée : une sinusoïde pleine échelle donne ~0 dB
+        let norm = 4.0 / size as f32;
+        let magnitudes: Vec<f32> = (0..size / 2).map(|k| (re[k] * re[k] + im[k] * im[k]).sqrt() * norm).collect();
+
+        let nyquist = frame.sample_rate as f32 / 2.0;
+        let bin_hz = frame.sample_rate as f32 / size as f32;
+        let ratio = (nyquist / LOWEST_BAND_HZ).max(1.0);
+        (0..self.bands)
+            .map(|b| {
+                let low = LOWEST_BAND_HZ * ratio.powf(b as f32 / self.bands as f32);
+                let high = LOWEST_BAND_HZ * ratio.powf((b + 1) as f32 / self.bands as f32);
+                let first = ((low / bin_hz) as usize).min(magnitudes.len() - 1);
+                let last = ((high / bin_hz) as usize).clamp(first + 1, magnitudes.len());
+                let peak = magnitudes.get(first..last).map_or(0.0, |m| m.iter().copied().fold(0.0, f32::max));
+                to_db(peak)
+            })
+            .collect()
+    }
+}
+
+/// FFT radix-2 en place (taille puissance de deux)
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -std::f32::consts::TAU / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (w_re, w_im) = ((angle * k as f32).cos(), (angle * k as f32).sin());
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
@@ -0,0 +1,371 @@
+// visualisation_module/src/capture/mixer.rs
+
+//! Mixeur audio entre `AudioCapture` et le `Preprocessor`
+//! - gain, mute et pan par source (micro, son du système)
+//! - sortie en un flux mixé unique ou en pistes séparées (une par source)
+//!
+//! Les sorties ont `audio_channels` canaux entrelacés (le pan ne s'applique qu'en
+//! stéréo) ; chaque frame porte l'identifiant de sa piste, recopié dans l'en-tête
+//! du paquet audio avec la fréquence et le nombre de canaux.
+//!
+//! En mode mixé, les sources sont alignées sur leur PTS avant d'être additionnées.
+//! Une source en retard est attendue au plus `audio_mix_wait_ms` : au-delà, le mix
+//! part sans elle (silence) jusqu'à son retour.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::capture::audio::{AudioCapture, AudioFrame};
+use crate::capture::clock::{frames_to_us, us_to_frames};
+use crate::capture::resample::convert_channels;
+use crate::config::ConfigFile;
+
+/// Identifiants de piste écrits dans les paquets audio
+pub const TRACK_MIXED: u8 = 0;
+pub const TRACK_MIC: u8 = 1;
+pub const TRACK_SYSTEM: u8 = 2;
+
+/// Gain maximal accepté (≈ +18 dB)
+const MAX_GAIN: f32 = 8.0;
+
+/// Écart de PTS maximal compensé entre deux sources ; au-delà, le silence inséré est borné
+const MAX_ALIGN_US: u64 = 1_000_000;
+
+/// Sources attendues par le mix en présence du loopback
+const MIXED_SOURCES: [u8; 2] = [TRACK_MIC, TRACK_SYSTEM];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MixMode {
+    /// Toutes les sources additionnées sur la piste `TRACK_MIXED`
+    Mixed,
+    /// Une piste par source (`TRACK_MIC`, `TRACK_SYSTEM`)
+    Separate,
+}
+
+impl MixMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "mixed" | "mix" => Some(MixMode::Mixed),
+            "separate" | "tracks" => Some(MixMode::Separate),
+            _ => None,
+        }
+    }
+}
+
+/// Réglages d'une source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackSettings {
+    /// Gain linéaire (1.0 = inchangé)
+    pub gain: f32,
+    pub muted: bool,
+    /// Balance -1.0 (gauche) .. 1.0 (droite)
+    pub pan: f32,
+}
+
+impl Default for TrackSettings {
+    fn default() -> Self {
+        Self { gain: 1.0, muted: false, pan: 0.0 }
+    }
+}
+
+/// Frame audio en sortie du mixeur
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixedFrame {
+    pub track_id: u8,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// PTS du premier échantillon (µs, horloge de capture)
+    pub pts: u64,
+    /// Échantillons entrelacés
+    pub samples: Vec<f32>,
+}
+
+/// Échantillons rendus d'une source, pas encore mixés
+struct Pending {
+    /// PTS du premier échantillon
+    pts: u64,
+    samples: Vec<f32>,
+}
+
+/// État du mix micro + système entre deux appels à `pull`
+#[derive(Default)]
+struct MixState {
+    pending: HashMap<u8, Pending>,
+    sample_rate: u32,
+    /// PTS du prochain échantillon mixé : ce qui arrive avant est écarté
+    cursor: Option<u64>,
+    /// Début de l'attente d'une source absente ; reste posé tant qu'elle manque
+    waiting_since: Option<Instant>,
+}
+
+pub struct AudioMixer {
+    /// Canaux des frames produites
+    channels: u16,
+    mode: Mutex<MixMode>,
+    tracks: Mutex<HashMap<u8, TrackSettings>>,
+    /// Attente maximale d'une source en retard (mode mixé)
+    max_wait: Duration,
+    state: Mutex<MixState>,
+}
+
+/// Décalage (échantillons par canal) d'un PTS par rapport au début du mix
+fn offset_frames(pts: u64, start: u64, sample_rate: u32) -> usize {
+    us_to_frames(pts.saturating_sub(start).min(MAX_ALIGN_US), sample_rate) as usize
+}
+
+impl AudioMixer {
+    pub fn new(mode: MixMode) -> Self {
+        Self {
+            channels: 2,
+            mode: Mutex::new(mode),
+            tracks: Mutex::new(HashMap::new()),
+            max_wait: Duration::from_millis(50),
+            state: Mutex::new(MixState::default()),
+        }
+    }
+
+    pub fn from_config(file: &ConfigFile) -> Self {
+        let mode = MixMode::from_name(&file.audio_mix_mode).unwrap_or_else(|| {
+            eprintln!("[mixer] Unknown audio_mix_mode '{}', using mixed", file.audio_mix_mode);
+            MixMode::Mixed
+        });
+        let mixer = Self::new(mode)
+            .with_channels(file.audio_channels)
+            .with_max_wait(Duration::from_millis(file.audio_mix_wait_ms));
+        mixer.set_track(TRACK_MIC, TrackSettings {
+            gain: file.audio_mic_gain,
+            muted: file.audio_mic_muted,
+            pan: file.audio_mic_pan,
+        });
+        mixer.set_track(TRACK_SYSTEM, TrackSettings {
+            gain: file.audio_system_gain,
+            muted: file.audio_system_muted,
+            pan: file.audio_system_pan,
+        });
+        mixer
+    }
+
+    pub fn with_channels(mut self, channels: u16) -> Self {
+        self.channels = channels.clamp(1, 8);
+        self
+    }
+
+    pub fn with_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn mode(&self) -> MixMode {
+        *self.mode.lock().unwrap()
+    }
+
+    pub fn set_mode(&self, mode: MixMode) {
+        *self.mode.lock().unwrap() = mode;
+    }
+
+    pub fn track(&self, track: u8) -> TrackSettings {
+        self.tracks.lock().unwrap().get(&track).copied().unwrap_or_default()
+    }
+
+    /// Remplace les réglages d'une source (valeurs bornées)
+    pub fn set_track(&self, track: u8, settings: TrackSettings) {
+        let settings = TrackSettings {
+            gain: if settings.gain.is_finite() { settings.gain.clamp(0.0, MAX_GAIN) } else { 1.0 },
+            muted: settings.muted,
+            pan: if settings.pan.is_finite() { settings.pan.clamp(-1.0, 1.0) } else { 0.0 },
+        };
+        self.tracks.lock().unwrap().insert(track, settings);
+    }
+
+    pub fn set_gain(&self, track: u8, gain: f32) {
+        self.set_track(track, TrackSettings { gain, ..self.track(track) });
+    }
+
+    pub fn set_muted(&self, track: u8, muted: bool) {
+        self.set_track(track, TrackSettings { muted, ..self.track(track) });
+    }
+
+    pub fn set_pan(&self, track: u8, pan: f32) {
+        self.set_track(track, TrackSettings { pan, ..self.track(track) });
+    }
+
+    /// Applique gain/mute/pan d'une source et la ramène aux canaux du mixeur
+    fn render_track(&self, track: u8, frame: &AudioFrame) -> Vec<f32> {
+        let settings = self.track(track);
+        let mut samples = convert_channels(&frame.samples, frame.channels, self.channels);
+        if settings.muted {
+            samples.iter_mut().for_each(|s| *s = 0.0);
+            return samples;
+        }
+
+        if self.channels == 2 {
+            // Balance linéaire : le côté opposé au pan est atténué
+            let left = settings.gain * (1.0 - settings.pan.max(0.0));
+            let right = settings.gain * (1.0 + settings.pan.min(0.0));
+            for lr in samples.chunks_exact_mut(2) {
+                lr[0] *= left;
+                lr[1] *= right;
+            }
+        } else {
+            samples.iter_mut().for_each(|s| *s *= settings.gain);
+        }
+        samples
+    }
+
+    /// Mixe ou sépare les sources `(piste, frame)`
+    /// Les frames sont supposées à la même fréquence (celle de la capture)
+    pub fn mix(&self, sources: &[(u8, &AudioFrame)]) -> Vec<MixedFrame> {
+        let channels = self.channels;
+        let sample_rate = sources.first().map_or(0, |(_, f)| f.sample_rate);
+        let rendered = sources.iter().map(|(track, frame)| (*track, frame.pts, self.render_track(*track, frame)));
+
+        match self.mode() {
+            MixMode::Separate => rendered
+                .map(|(track_id, pts, samples)| MixedFrame { track_id, sample_rate, channels, pts, samples })
+                .collect(),
+            MixMode::Mixed => {
+                let rendered: Vec<(u8, u64, Vec<f32>)> = rendered.collect();
+                // Le mix commence avec la plus ancienne des frames sources, les autres
+                // sont décalées de leur écart de PTS
+                let Some(pts) = rendered.iter().map(|(_, pts, _)| *pts).min() else {
+                    return Vec::new();
+                };
+                let mut mixed: Vec<f32> = Vec::new();
+                for (_, frame_pts, samples) in rendered {
+                    let offset = offset_frames(frame_pts, pts, sample_rate) * channels as usize;
+                    if offset + samples.len() > mixed.len() {
+                        mixed.resize(offset + samples.len(), 0.0);
+                    }
+                    for (out, s) in mixed[offset..].iter_mut().zip(samples) {
+                        *out += s;
+                    }
+                }
+                if mixed.is_empty() {
+                    return Vec::new();
+                }
+                for s in &mut mixed {
+                    *s = s.clamp(-1.0, 1.0);
+                }
+                vec![MixedFrame { track_id: TRACK_MIXED, sample_rate, channels, pts, samples: mixed }]
+            }
+        }
+    }
+
+    /// Prélève les frames disponibles de la capture et les passe au mixeur
+    /// En mode mixé avec loopback actif, les deux sources passent par `mix_aligned`.
+    pub fn pull(&self, audio: &AudioCapture) -> Vec<MixedFrame> {
+        if self.mode() == MixMode::Mixed && audio.loopback_format().is_some() {
+            return self.mix_aligned(audio.get_frame(), audio.get_loopback_frame());
+        }
+        *self.state.lock().unwrap() = MixState::default();
+
+        let (mic, system) = (audio.get_frame(), audio.get_loopback_frame());
+        let mut sources: Vec<(u8, &AudioFrame)> = Vec::with_capacity(2);
+        if let Some(frame) = &mic {
+            sources.push((TRACK_MIC, frame));
+        }
+        if let Some(frame) = &system {
+            sources.push((TRACK_SYSTEM, frame));
+        }
+        if sources.is_empty() {
+            return Vec::new();
+        }
+        self.mix(&sources)
+    }
+
+    /// Mix micro + système aligné sur les PTS
+    /// Ne produit que la plage couverte par les deux sources ; si l'une manque depuis
+    /// plus de `max_wait`, la plage de l'autre part seule (source absente = silence).
+    fn mix_aligned(&self, mic: Option<AudioFrame>, system: Option<AudioFrame>) -> Vec<MixedFrame> {
+        let mut state = self.state.lock().unwrap();
+        for (track, frame) in [(TRACK_MIC, mic), (TRACK_SYSTEM, system)] {
+            if let Some(frame) = frame {
+                self.queue(&mut state, track, &frame);
+            }
+        }
+
+        let channels = self.channels as usize;
+        let sample_rate = state.sample_rate;
+        let Some(start) = state.pending.values().filter(|p| !p.samples.is_empty()).map(|p| p.pts).min() else {
+            return Vec::new();
+        };
+        let ends: Vec<usize> = state
+            .pending
+            .values()
+            .filter(|p| !p.samples.is_empty())
+            .map(|p| offset_frames(p.pts, start, sample_rate) + p.samples.len() / channels)
+            .collect();
+
+        let complete = MIXED_SOURCES.iter().all(|t| state.pending.get(t).is_some_and(|p| !p.samples.is_empty()));
+        let available = if complete {
+            state.waiting_since = None;
+            ends.iter().copied().min().unwrap_or(0)
+        } else {
+            let since = *state.waiting_since.get_or_insert_with(Instant::now);
+            if since.elapsed() < self.max_wait {
+                return Vec::new();
+            }
+            ends.iter().copied().max().unwrap_or(0)
+        };
+        if available == 0 {
+            return Vec::new();
+        }
+
+        let mut mixed = vec![0.0f32; available * channels];
+        let next_pts = start + frames_to_us(available as u64, sample_rate);
+        for pending in state.pending.values_mut() {
+            let offset = offset_frames(pending.pts, start, sample_rate);
+            let take = available.saturating_sub(offset).min(pending.samples.len() / channels);
+            if take == 0 {
+                continue;
+            }
+            for (out, s) in mixed[offset * channels..].iter_mut().zip(pending.samples.drain(..take * channels)) {
+                *out += s;
+            }
+            pending.pts = next_pts;
+        }
+        state.cursor = Some(next_pts);
+
+        for s in &mut mixed {
+            *s = s.clamp(-1.0, 1.0);
+        }
+        vec![MixedFrame { track_id: TRACK_MIXED, sample_rate, channels: self.channels, pts: start, samples: mixed }]
+    }
+
+    /// Ajoute une frame aux échantillons en attente de sa source
+    /// Les échantillons antérieurs au mix déjà produit sont écartés, un trou
+    /// dans la source est comblé par du silence.
+    fn queue(&self, state: &mut MixState, track: u8, frame: &AudioFrame) {
+        let channels = self.channels as usize;
+        let sample_rate = frame.sample_rate;
+        state.sample_rate = sample_rate;
+        let mut samples = self.render_track(track, frame);
+        let mut pts = frame.pts;
+
+        if let Some(cursor) = state.cursor.filter(|c| pts < *c) {
+            let late = us_to_frames(cursor - pts, sample_rate) as usize * channels;
+            if late >= samples.len() {
+                return;
+            }
+            samples.drain(..late);
+            pts = cursor;
+        }
+
+        match state.pending.get_mut(&track) {
+            Some(pending) if !pending.samples.is_empty() => {
+                let end = pending.pts + frames_to_us((pending.samples.len() / channels) as u64, sample_rate);
+                let gap = offset_frames(pts, end, sample_rate) * channels;
+                pending.samples.resize(pending.samples.len() + gap, 0.0);
+                pending.samples.extend(samples);
+            }
+            _ => {
+                state.pending.insert(track, Pending { pts, samples });
+            }
+        }
+    }
+}
@@ -3,16 +3,61 @@
 //! Centralisation de tous les modules de capture et pré-traitement
 
 pub mod screen;
+pub mod clock;
+pub mod frame;
+pub mod frame_source;
+pub mod compositor;
+pub mod mask;
+pub mod fps_controller;
+pub mod encoder;
+pub mod delta;
 pub mod audio;
+pub mod audio_backend;
+pub mod audio_codec;
+pub mod mixer;
+pub mod meter;
+pub mod vad;
+pub mod dsp;
+pub mod resample;
+pub mod wav_sink;
 pub mod input;
+pub mod input_codec;
+pub mod input_coalesce;
+pub mod keys;
+pub mod hotkeys;
+pub mod replay;
 pub mod ethernet;
 pub mod bluetooth;
 pub mod preprocess;
 
 // Réexport des structures principales pour usage externe
-pub use screen::ScreenCapture;
-pub use audio::AudioCapture;
+pub use screen::{ScreenCapture, ScreenSelection};
+pub use frame::{ScreenFrame, PixelFormat, Rect};
+pub use delta::{DeltaEncoder, DeltaDecoder};
+pub use encoder::{ScreenCodec, ScreenEncoder};
+pub use fps_controller::{AdaptiveFps, FpsInputs};
+pub use compositor::{CanvasLayout, Compositor, COMPOSITE_DISPLAY};
+pub use mask::{MaskStyle, PrivacyMask};
+pub use frame_source::{FrameSource, ScreenBackend, ScrapSource, SyntheticSource};
+pub use audio::{AudioCapture, AudioFrame, DeviceEvent, DeviceEventKind};
+pub use audio_codec::{AudioCodec, AudioEncoder};
+pub use clock::{frames_to_us, pts_now, us_to_frames, CaptureClock, CAPTURE_CLOCK};
+pub use resample::{convert_channels, Resampler};
+pub use wav_sink::{is_recording_name, repair_wav, RecordMode, WavRecorder};
+pub use meter::{AudioMeter, SILENCE_DB};
+pub use vad::{VadDecision, VadSettings, VoiceDetector};
+pub use dsp::{AudioFilter, Compressor, FilterChain, FilterKind, HighPass, NoiseGate, Normalizer};
+pub use mixer::{AudioMixer, MixMode, MixedFrame, TrackSettings, TRACK_MIC, TRACK_MIXED, TRACK_SYSTEM};
+pub use audio_backend::{
+    AudioBackend, AudioDevice, AudioFormat, AudioStream, CpalBackend, DeviceSelector, MockAudioBackend, MockDevices,
+    Waveform,
+};
 pub use input::{InputCapture, InputEvent, InputEventType};
+pub use input_codec::{InputEncoder, InputFormat};
+pub use input_coalesce::MoveCoalescer;
+pub use hotkeys::{ControlEvent, HotkeyAction, HotkeyMatch, HotkeyMatcher};
+pub use keys::{button_name, canonical_key_name, enigo_key, key_from_name, key_name, KeyChord};
+pub use replay::{EnigoInjector, InputInjector, RecordingInjector, ReplayEngine, ReplayHandle, ReplayStats, ScreenRemap};
 pub use ethernet::EthernetClient;
 pub use bluetooth::BluetoothClient;
 pub use preprocess::Preprocessor;
@@ -1,21 +1,45 @@
 // visualisation_module/src/capture/preprocess.rs
 
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::thread;
+use std::thread::{self, JoinHandle};
 use std::time::Duration;
 
-use crate::capture::{ScreenCapture, AudioCapture, InputCapture, InputEvent, InputEventType};
+use crate::capture::{ScreenCapture, AudioCapture, InputCapture, InputEvent, ScreenFrame};
+use crate::capture::audio_codec::{AudioCodec, AudioEncoder};
+use crate::capture::delta::DeltaEncoder;
+use crate::capture::encoder::{ScreenCodec, ScreenEncoder};
+use crate::capture::input_codec::{InputEncoder, InputFormat};
+use crate::capture::mixer::{AudioMixer, MixedFrame};
+use crate::capture::vad::{VadSettings, VoiceDetector};
+use crate::capture::wav_sink::{RecordMode, WavRecorder};
+use crate::config::{self, ConfigFile};
+use crate::error::ModuleError;
 use crate::Transmitter;
-use flate2::Compression;
-use flate2::write::GzEncoder;
-use std::io::Write;
+
+/// Attente de la boucle de traitement quand aucune source n'a rien produit
+const IDLE_INTERVAL: Duration = Duration::from_millis(5);
 
 pub struct Preprocessor {
     screen: Arc<ScreenCapture>,
     audio: Arc<AudioCapture>,
     input: Arc<InputCapture>,
     running: Arc<Mutex<bool>>,
+    thread_handle: Mutex<Option<JoinHandle<()>>>,
     transmitter: Arc<Mutex<Option<Arc<Transmitter>>>>,
+    screen_encoder: ScreenEncoder,
+    delta_encoder: Mutex<DeltaEncoder>,
+    mixer: AudioMixer,
+    audio_encoder: AudioEncoder,
+    input_encoder: InputEncoder,
+    /// Suppression des silences (None = toutes les frames sont envoyées)
+    vad: Option<VadSettings>,
+    /// Un détecteur par piste audio
+    detectors: Mutex<HashMap<u8, VoiceDetector>>,
+    /// Enregistrement WAV local des pistes (None = désactivé)
+    recorder: Option<WavRecorder>,
+    /// false en mode `only` : l'audio est seulement enregistré
+    transmit_audio: bool,
 }
 
 impl Preprocessor {
@@ -24,14 +48,33 @@ impl Preprocessor {
         audio: Arc<AudioCapture>,
         input: Arc<InputCapture>,
     ) -> Self {
-        let _ = Duration::from_millis(100);
-        let _ = thread::Builder::new();
+        let file = config::CONFIG.lock().unwrap().file.clone();
+        Self::with_config(screen, audio, input, &file)
+    }
+
+    /// Construit le préprocesseur à partir d'une config explicite (tests, outils)
+    pub fn with_config(
+        screen: Arc<ScreenCapture>,
+        audio: Arc<AudioCapture>,
+        input: Arc<InputCapture>,
+        file: &ConfigFile,
+    ) -> Self {
         Self {
             screen,
             audio,
             input,
             running: Arc::new(Mutex::new(false)),
+            thread_handle: Mutex::new(None),
             transmitter: Arc::new(Mutex::new(None)),
+            screen_encoder: ScreenEncoder::from_config(file),
+            delta_encoder: Mutex::new(DeltaEncoder::new(file.screen_tile_size, file.screen_keyframe_interval)),
+            mixer: AudioMixer::from_config(file),
+            audio_encoder: AudioEncoder::from_config(file),
+            input_encoder: InputEncoder::from_config(file),
+            vad: VadSettings::from_config(file),
+            detectors: Mutex::new(HashMap::new()),
+            recorder: WavRecorder::from_config(file),
+            transmit_audio: RecordMode::from_name(&file.audio_record_mode).is_none_or(|m| m.transmits()),
         }
     }
 
@@ -40,77 +83,194 @@ impl Preprocessor {
         *self.transmitter.lock().unwrap() = Some(transmitter);
     }
 
-    /// Démarre le prétraitement H24
-    /// NOTE: Désactivé temporairement - scrap::Capturer n'est pas Send
-    pub fn start(&self) {
-        eprintln!("WARN: Preprocess désactivé - scrap::Capturer non-Send");
-        let running = Arc::clone(&self.running);
-        *running.lock().unwrap() = true;
-        // Utiliser les champs screen, audio, input
-        let _ = (&self.screen, &self.audio, &self.input);
-        eprintln!("[Preprocessor] Started with screen/audio/input modules");
+    /// Remplace l'encodeur des frames écran
+    pub fn set_screen_encoder(&mut self, encoder: ScreenEncoder) {
+        self.screen_encoder = encoder;
+    }
+
+    /// Remplace l'encodeur des paquets audio
+    pub fn set_audio_encoder(&mut self, encoder: AudioEncoder) {
+        self.audio_encoder = encoder;
+    }
+
+    /// Remplace l'encodeur des événements d'entrée
+    pub fn set_input_encoder(&mut self, encoder: InputEncoder) {
+        self.input_encoder = encoder;
+    }
+
+    /// Active ou désactive la suppression des silences
+    pub fn set_vad(&mut self, vad: Option<VadSettings>) {
+        self.vad = vad;
+        self.detectors.lock().unwrap().clear();
+    }
+
+    /// Remplace l'enregistreur WAV ; en mode `Only` l'audio n'est plus transmis
+    pub fn set_recorder(&mut self, recorder: Option<WavRecorder>, mode: RecordMode) {
+        self.recorder = recorder.filter(|_| mode.records());
+        self.transmit_audio = mode.transmits() || self.recorder.is_none();
+    }
+
+    pub fn recorder(&self) -> Option<&WavRecorder> {
+        self.recorder.as_ref()
+    }
+
+    /// Mixeur audio (gain, mute, pan, mode de pistes) réglable à chaud
+    pub fn mixer(&self) -> &AudioMixer {
+        &self.mixer
+    }
+
+    /// Démarre le prétraitement H24 : une boucle dédiée enchaîne `process_batch`
+    /// (encodage, VAD, enregistrement WAV, envoi au transmitter)
+    pub fn start(self: &Arc<Self>) {
+        let mut running = self.running.lock().unwrap();
+        if *running {
+            return;
+        }
+        *running = true;
+        drop(running);
+
+        let preprocessor = Arc::clone(self);
+        let spawned = thread::Builder::new().name("preprocess".to_string()).spawn(move || {
+            while *preprocessor.running.lock().unwrap() {
+                if !preprocessor.process_batch() {
+                    thread::sleep(IDLE_INTERVAL);
+                }
+            }
+        });
+        match spawned {
+            Ok(handle) => {
+                *self.thread_handle.lock().unwrap() = Some(handle);
+                eprintln!("[Preprocessor] Started with screen/audio/input modules");
+            }
+            Err(e) => {
+                *self.running.lock().unwrap() = false;
+                eprintln!("[Preprocessor] Cannot start processing thread: {}", e);
+            }
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        *self.running.lock().unwrap()
     }
 
+    /// Arrête la boucle puis finalise les enregistrements WAV
     pub fn stop(&self) {
         *self.running.lock().unwrap() = false;
+        if let Some(handle) = self.thread_handle.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+        if let Some(Err(e)) = self.recorder.as_ref().map(|r| r.finalize()) {
+            eprintln!("[Preprocessor] Cannot finalize recordings: {}", e);
+        }
     }
 
-    pub fn process_batch(&self) {
-        // Démontrer l'utilisation de compress_screen, process_audio, serialize_input
-        let screen_frame = vec![0u8; 100];
-        let audio_frame = vec![0.0f32; 50];
-        let input_event = InputEvent {
-            event_type: InputEventType::KeyPress { key: "a".to_string() },
-            timestamp: 0,
-        };
-        let _ = Self::compress_screen(screen_frame);
-        let _ = Self::process_audio(audio_frame);
-        let _ = Self::serialize_input(input_event);
+    /// Traite une frame de chaque source et pousse le résultat vers le transmitter
+    /// Renvoie false si aucune source n'avait de données
+    pub fn process_batch(&self) -> bool {
+        let transmitter = self.transmitter.lock().unwrap().clone();
+        let mut busy = false;
+
+        if let Some(frame) = self.screen.get_frame() {
+            busy = true;
+            let data = self.compress_screen(frame);
+            if let Some(t) = &transmitter {
+                t.push_screen(data);
+            }
+        }
+        for frame in self.mixer.pull(&self.audio) {
+            busy = true;
+            // Archive locale : frames complètes, avant la suppression des silences
+            if let Some(recorder) = &self.recorder {
+                if let Err(e) = recorder.write(&frame) {
+                    eprintln!("[Preprocessor] WAV recording error: {}", e);
+                }
+            }
+            if !self.transmit_audio {
+                continue;
+            }
+            let data = self.process_audio(frame);
+            if let Some(t) = &transmitter {
+                t.push_audio(data);
+            }
+        }
+        if let Some(event) = self.input.get_event() {
+            busy = true;
+            let data = self.serialize_input(&event);
+            if let Some(t) = &transmitter {
+                t.push_input(data);
+            }
+        }
+        busy
     }
 
     // --- Pré-traitement avec compression réelle ---
-    fn compress_screen(frame: Vec<u8>) -> Vec<u8> {
-        // Compression GZIP du frame raw
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
-        let _ = encoder.write_all(&frame);
-        match encoder.finish() {
-            Ok(compressed) => {
-                // Ajouter header minimal
-                let mut result = vec![0x01]; // version 1
-                result.extend(compressed);
-                result
+
+    /// Paquet écran (keyframe ou tuiles modifiées) encodé avec le codec configuré
+    /// Côté pool, `DeltaDecoder` reconstruit les frames complètes
+    pub fn compress_screen(&self, frame: ScreenFrame) -> Vec<u8> {
+        let mut delta = self.delta_encoder.lock().unwrap();
+        match delta.encode(&frame, &self.screen_encoder) {
+            Ok(packet) => packet,
+            Err(e) => {
+                // Repli sur le GZIP brut si l'encodeur image échoue
+                eprintln!("[Preprocessor] Screen encoding failed: {}", e);
+                // Keyframes avant et après pour ne pas mélanger les formats de pixels
+                delta.request_keyframe();
+                let packet = delta
+                    .encode(&frame, &ScreenEncoder::new(ScreenCodec::Gzip, 100))
+                    .unwrap_or_default();
+                delta.request_keyframe();
+                packet
             }
-            Err(_) => frame, // Si compression échoue, renvoyer raw
         }
     }
 
-    fn process_audio(frame: Vec<f32>) -> Vec<u8> {
-        // Conversion f32 -> i16 (PCM16)
-        let mut pcm_data: Vec<u8> = Vec::with_capacity(frame.len() * 2);
-        for &sample in &frame {
-            let s16 = (sample * 32767.0) as i16;
-            pcm_data.extend_from_slice(&s16.to_le_bytes());
+    /// Paquet audio d'une piste encodé avec le codec configuré, ou marqueur de
+    /// silence si la VAD ne détecte pas de parole
+    /// Côté pool, `decode_audio` restitue piste, format et échantillons
+    pub fn process_audio(&self, frame: MixedFrame) -> Vec<u8> {
+        if let Some(settings) = self.vad {
+            let decision = self
+                .detectors
+                .lock()
+                .unwrap()
+                .entry(frame.track_id)
+                .or_insert_with(|| VoiceDetector::new(settings))
+                .process(&frame);
+            if !decision.speech {
+                return self.audio_encoder.encode_silence(&frame, decision.rms);
+            }
+        }
+
+        match self.audio_encoder.encode_packet(&frame) {
+            Ok(packet) => packet,
+            Err(e) => {
+                // Repli sur le PCM16 GZIP si le codec échoue
+                eprintln!("[Preprocessor] Audio encoding failed: {}", e);
+                AudioEncoder::new(AudioCodec::Gzip, 0).encode_packet(&frame).unwrap_or_default()
+            }
         }
+    }
+
+    /// Décode un paquet produit par `process_audio` (côté pool)
+    pub fn decode_audio(packet: &[u8]) -> Result<MixedFrame, ModuleError> {
+        AudioEncoder::decode_packet(packet)
+    }
 
-        // Compression GZIP du PCM
-        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
-        let _ = encoder.write_all(&pcm_data);
-        match encoder.finish() {
-            Ok(compressed) => {
-                let mut result = vec![0x02]; // version 2
-                result.extend(compressed);
-                result
+    /// Événement d'entrée sérialisé au format configuré (JSON ou binaire)
+    pub fn serialize_input(&self, event: &InputEvent) -> Vec<u8> {
+        match self.input_encoder.encode(event) {
+            Ok(packet) => packet,
+            Err(e) => {
+                // Repli sur le JSON (noms trop longs pour le binaire)
+                eprintln!("[Preprocessor] Input encoding failed: {}", e);
+                InputEncoder::new(InputFormat::Json).encode(event).unwrap_or_default()
             }
-            Err(_) => pcm_data,
         }
     }
 
-    fn serialize_input(event: InputEvent) -> Vec<u8> {
-        // Sérialisation JSON simple de l'input
-        let json = format!(
-            r#"{{"type":"{:?}","timestamp":{}}}"#,
-            event.event_type, event.timestamp
-        );
-        json.into_bytes()
+    /// Décode un paquet produit par `serialize_input` (côté pool, rejeu)
+    pub fn decode_input(packet: &[u8]) -> Result<InputEvent, ModuleError> {
+        InputEncoder::decode(packet)
     }
 }
@@ -0,0 +1,407 @@
+// visualisation_module/src/capture/replay.rs
+
+//! Rejeu d'une session d'entrées enregistrée (`InputEvent`)
+//! - timing d'origine d'après les PTS, mis à l'échelle par `speed`
+//! - pause / reprise / pas à pas, vitesse et arrêt pilotés à chaud via `ReplayHandle`
+//! - remappage des coordonnées vers un écran de taille différente
+//!
+//! L'injection passe par le trait `InputInjector` : `EnigoInjector` pilote le vrai
+//! bureau, `RecordingInjector` enregistre les événements rejoués (tests, vérification).
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use enigo::{Enigo, KeyboardControllable, MouseButton, MouseControllable};
+
+use crate::capture::input::{InputEvent, InputEventType};
+use crate::capture::input_codec::InputEncoder;
+use crate::capture::keys::enigo_key;
+use crate::error::ModuleError;
+
+/// Vitesses de rejeu acceptées
+const MIN_SPEED: f32 = 0.05;
+const MAX_SPEED: f32 = 64.0;
+
+/// Destination des événements rejoués
+pub trait InputInjector: Send {
+    fn inject(&mut self, event: &InputEventType) -> Result<(), ModuleError>;
+}
+
+/// Injection réelle via enigo (clavier et souris du bureau courant)
+pub struct EnigoInjector {
+    enigo: Enigo,
+}
+
+impl Default for EnigoInjector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnigoInjector {
+    pub fn new() -> Self {
+        Self { enigo: Enigo::new() }
+    }
+
+    fn button(name: &str) -> Result<MouseButton, ModuleError> {
+        match name.to_lowercase().as_str() {
+            "left" => Ok(MouseButton::Left),
+            "right" => Ok(MouseButton::Right),
+            "middle" => Ok(MouseButton::Middle),
+            other => Err(ModuleError::ValidationError(format!("Unsupported mouse button '{}'", other))),
+        }
+    }
+
+    fn key(name: &str) -> Result<enigo::Key, ModuleError> {
+        enigo_key(name).ok_or_else(|| ModuleError::ValidationError(format!("Unsupported key '{}'", name)))
+    }
+}
+
+impl InputInjector for EnigoInjector {
+    fn inject(&mut self, event: &InputEventType) -> Result<(), ModuleError> {
+        match event {
+            InputEventType::KeyPress { key } => self.enigo.key_down(Self::key(key)?),
+            InputEventType::KeyRelease { key } => self.enigo.key_up(Self::key(key)?),
+            InputEventType::MouseMove { x, y } => self.enigo.mouse_move_to(*x, *y),
+            InputEventType::MouseClick { button, x, y } => {
+                let button = Self::button(button)?;
+                self.enigo.mouse_move_to(*x, *y);
+                self.enigo.mouse_down(button);
+            }
+            InputEventType::MouseRelease { button, x, y } => {
+                let button = Self::button(button)?;
+                self.enigo.mouse_move_to(*x, *y);
+                self.enigo.mouse_up(button);
+            }
+            InputEventType::Scroll { dx, dy } => {
+                if *dy != 0 {
+                    self.enigo.mouse_scroll_y(*dy);
+                }
+                if *dx != 0 {
+                    self.enigo.mouse_scroll_x(*dx);
+                }
+            }
+            InputEventType::Control { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+/// Injecteur factice : garde les événements reçus et leur instant d'injection
+#[derive(Clone, Default)]
+pub struct RecordingInjector {
+    events: Arc<Mutex<Vec<(InputEventType, Instant)>>>,
+}
+
+impl RecordingInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn events(&self) -> Vec<InputEventType> {
+        self.events.lock().unwrap().iter().map(|(e, _)| e.clone()).collect()
+    }
+
+    /// Instants d'injection, dans l'ordre
+    pub fn instants(&self) -> Vec<Instant> {
+        self.events.lock().unwrap().iter().map(|(_, t)| *t).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl InputInjector for RecordingInjector {
+    fn inject(&mut self, event: &InputEventType) -> Result<(), ModuleError> {
+        self.events.lock().unwrap().push((event.clone(), Instant::now()));
+        Ok(())
+    }
+}
+
+/// Mise à l'échelle des coordonnées de l'écran enregistré vers l'écran de rejeu
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenRemap {
+    pub from: (u32, u32),
+    pub to: (u32, u32),
+    /// Origine de l'écran de rejeu sur le bureau virtuel
+    pub offset: (i32, i32),
+}
+
+impl ScreenRemap {
+    pub fn new(from: (u32, u32), to: (u32, u32)) -> Self {
+        Self { from, to, offset: (0, 0) }
+    }
+
+    pub fn with_offset(mut self, x: i32, y: i32) -> Self {
+        self.offset = (x, y);
+        self
+    }
+
+    pub fn apply(&self, x: i32, y: i32) -> (i32, i32) {
+        let scale = |v: i32, from: u32, to: u32| (v as f64 * to as f64 / from.max(1) as f64).round() as i32;
+        (scale(x, self.from.0, self.to.0) + self.offset.0, scale(y, self.from.1, self.to.1) + self.offset.1)
+    }
+
+    /// Événement avec ses coordonnées remappées (défilement inchangé)
+    pub fn remap(&self, event: &InputEventType) -> InputEventType {
+        match event.clone() {
+            InputEventType::MouseMove { x, y } => {
+                let (x, y) = self.apply(x, y);
+                InputEventType::MouseMove { x, y }
+            }
+            InputEventType::MouseClick { button, x, y } => {
+                let (x, y) = self.apply(x, y);
+                InputEventType::MouseClick { button, x, y }
+            }
+            InputEventType::MouseRelease { button, x, y } => {
+                let (x, y) = self.apply(x, y);
+                InputEventType::MouseRelease { button, x, y }
+            }
+            other => other,
+        }
+    }
+}
+
+/// Bilan d'un rejeu
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayStats {
+    pub injected: usize,
+    /// Événements refusés par l'injecteur (touche non gérée...)
+    pub skipped: usize,
+}
+
+/// État partagé entre le thread de rejeu et son `ReplayHandle`
+#[derive(Default)]
+struct ReplayControl {
+    paused: bool,
+    steps: usize,
+    stopped: bool,
+    finished: bool,
+    speed: f32,
+    /// Prochain événement à rejouer
+    position: usize,
+    stats: ReplayStats,
+}
+
+pub struct ReplayEngine {
+    events: Vec<InputEvent>,
+    speed: f32,
+    remap: Option<ScreenRemap>,
+    start_paused: bool,
+}
+
+impl ReplayEngine {
+    /// Rejeu des événements dans l'ordre de leur PTS (les repères de pilotage sont écartés)
+    pub fn new(mut events: Vec<InputEvent>) -> Self {
+        events.retain(|e| !matches!(e.event_type, InputEventType::Control { .. }));
+        events.sort_by_key(|e| e.pts);
+        Self { events, speed: 1.0, remap: None, start_paused: false }
+    }
+
+    /// Rejeu de paquets transmis (JSON ou binaire, cf. `input_codec`)
+    pub fn from_packets<P: AsRef<[u8]>>(packets: &[P]) -> Result<Self, ModuleError> {
+        let events = packets.iter().map(|p| InputEncoder::decode(p.as_ref())).collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(events))
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = clamp_speed(speed);
+        self
+    }
+
+    pub fn with_remap(mut self, remap: ScreenRemap) -> Self {
+        self.remap = Some(remap);
+        self
+    }
+
+    /// Démarre en pause (avancer avec `step` ou `resume`)
+    pub fn paused(mut self) -> Self {
+        self.start_paused = true;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Durée du rejeu à la vitesse choisie
+    pub fn duration(&self) -> Duration {
+        match (self.events.first(), self.events.last()) {
+            (Some(first), Some(last)) => scaled(last.pts - first.pts, self.speed),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Lance le rejeu dans un thread dédié
+    pub fn start(self, injector: Box<dyn InputInjector>) -> ReplayHandle {
+        let control = Arc::new((
+            Mutex::new(ReplayControl { paused: self.start_paused, speed: self.speed, ..Default::default() }),
+            Condvar::new(),
+        ));
+        let shared = Arc::clone(&control);
+        let thread = thread::spawn(move || self.run(injector, &shared));
+        ReplayHandle { control, thread: Some(thread) }
+    }
+
+    fn run(self, mut injector: Box<dyn InputInjector>, control: &(Mutex<ReplayControl>, Condvar)) {
+        let (lock, wakeup) = control;
+        let mut state = lock.lock().unwrap();
+        // Référence de temps : (instant, PTS de l'enregistrement lu à cet instant)
+        let mut clock: Option<(Instant, u64)> = None;
+        let mut speed = state.speed;
+        let mut cursor_pts = self.events.first().map_or(0, |e| e.pts);
+
+        while !state.stopped && state.position < self.events.len() {
+            let event = &self.events[state.position];
+            let now = Instant::now();
+            // Lecture courante de l'enregistrement, avant tout changement de réglage
+            if let Some((t0, p0)) = clock {
+                cursor_pts = (p0 + (now - t0).mul_f32(speed).as_micros() as u64).min(event.pts);
+            }
+            if speed != state.speed {
+                speed = state.speed;
+                clock = clock.map(|_| (now, cursor_pts));
+            }
+
+            if state.paused {
+                clock = None;
+                if state.steps == 0 {
+                    state = wakeup.wait(state).unwrap();
+                    continue;
+                }
+                state.steps -= 1;
+                cursor_pts = event.pts;
+            } else {
+                let (t0, p0) = *clock.get_or_insert((now, cursor_pts));
+                let due = t0 + scaled(event.pts.saturating_sub(p0), speed);
+                if now < due {
+                    state = wakeup.wait_timeout(state, due - now).unwrap().0;
+                    continue;
+                }
+            }
+
+            let event = match &self.remap {
+                Some(remap) => remap.remap(&event.event_type),
+                None => event.event_type.clone(),
+            };
+            match injector.inject(&event) {
+                Ok(()) => state.stats.injected += 1,
+                Err(e) => {
+                    eprintln!("[replay] Event {} skipped: {}", state.position, e);
+                    state.stats.skipped += 1;
+                }
+            }
+            state.position += 1;
+            wakeup.notify_all();
+        }
+
+        state.finished = true;
+        wakeup.notify_all();
+    }
+}
+
+/// Durée réelle de `us` microsecondes d'enregistrement à la vitesse `speed`
+fn scaled(us: u64, speed: f32) -> Duration {
+    Duration::from_micros((us as f64 / speed as f64).round() as u64)
+}
+
+fn clamp_speed(speed: f32) -> f32 {
+    if speed.is_finite() { speed.clamp(MIN_SPEED, MAX_SPEED) } else { 1.0 }
+}
+
+/// Pilotage d'un rejeu en cours
+pub struct ReplayHandle {
+    control: Arc<(Mutex<ReplayControl>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ReplayHandle {
+    fn update(&self, f: impl FnOnce(&mut ReplayControl)) {
+        let (lock, wakeup) = &*self.control;
+        f(&mut lock.lock().unwrap());
+        wakeup.notify_all();
+    }
+
+    pub fn pause(&self) {
+        self.update(|s| s.paused = true);
+    }
+
+    /// Reprend au rythme d'origine à partir de l'événement courant
+    pub fn resume(&self) {
+        self.update(|s| {
+            s.paused = false;
+            s.steps = 0;
+        });
+    }
+
+    /// En pause : rejoue immédiatement l'événement suivant
+    pub fn step(&self) {
+        self.update(|s| {
+            if s.paused {
+                s.steps += 1;
+            }
+        });
+    }
+
+    pub fn set_speed(&self, speed: f32) {
+        self.update(|s| s.speed = clamp_speed(speed));
+    }
+
+    pub fn stop(&self) {
+        self.update(|s| s.stopped = true);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.control.0.lock().unwrap().paused
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.control.0.lock().unwrap().finished
+    }
+
+    /// Nombre d'événements déjà rejoués (ou ignorés)
+    pub fn position(&self) -> usize {
+        self.control.0.lock().unwrap().position
+    }
+
+    pub fn stats(&self) -> ReplayStats {
+        self.control.0.lock().unwrap().stats
+    }
+
+    /// Attend que la position atteigne `position` (ou la fin) ; false si délai dépassé
+    pub fn wait_for(&self, position: usize, timeout: Duration) -> bool {
+        let (lock, wakeup) = &*self.control;
+        let state = lock.lock().unwrap();
+        let (state, _) = wakeup
+            .wait_timeout_while(state, timeout, |s| s.position < position && !s.finished)
+            .unwrap();
+        state.position >= position
+    }
+
+    /// Attend la fin du rejeu et renvoie son bilan
+    pub fn join(mut self) -> ReplayStats {
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        self.stats()
+    }
+}
+
+impl Drop for ReplayHandle {
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            self.stop();
+            let _ = thread.join();
+        }
+    }
+}
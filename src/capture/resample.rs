@@ -0,0 +1,110 @@
+// visualisation_module/src/capture/resample.rs
+
+//! Conversion du flux audio du périphérique vers le format configuré
+//! (`audio_sample_rate`, `audio_channels`) :
+//! - changement de disposition des canaux (mono, stéréo, downmix 5.1)
+//! - rééchantillonnage linéaire en continu, sans rupture entre blocs
+
+/// Coefficient -3 dB des canaux centre / surround dans un downmix stéréo
+const DOWNMIX_3DB: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// Convertit des échantillons entrelacés de `from` à `to` canaux
+pub fn convert_channels(samples: &[f32], from: u16, to: u16) -> Vec<f32> {
+    let (from, to) = (from.max(1) as usize, to.max(1) as usize);
+    if from == to {
+        return samples.to_vec();
+    }
+    let frames = samples.chunks_exact(from);
+    let mut out = Vec::with_capacity(frames.len() * to);
+
+    for frame in frames {
+        if from == 1 {
+            // Mono dupliqué sur tous les canaux
+            out.extend(std::iter::repeat_n(frame[0], to));
+        } else if to == 1 {
+            out.push(frame.iter().sum::<f32>() / from as f32);
+        } else if to == 2 && from == 6 {
+            // 5.1 (FL FR FC LFE SL SR) -> stéréo, LFE ignoré, normalisé pour éviter l'écrêtage
+            let norm = 1.0 / (1.0 + 2.0 * DOWNMIX_3DB);
+            out.push((frame[0] + DOWNMIX_3DB * (frame[2] + frame[4])) * norm);
+            out.push((frame[1] + DOWNMIX_3DB * (frame[2] + frame[5])) * norm);
+        } else {
+            // Cas générique : le canal i va vers la sortie i % to, moyenné
+            for j in 0..to {
+                let sources: Vec<f32> = frame.iter().skip(j).step_by(to).copied().collect();
+                out.push(if sources.is_empty() { 0.0 } else { sources.iter().sum::<f32>() / sources.len() as f32 });
+            }
+        }
+    }
+    out
+}
+
+/// Rééchantillonneur linéaire à état (les blocs successifs forment un signal continu)
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    from_rate: u32,
+    to_rate: u32,
+    channels: usize,
+    /// Position de lecture en frames, 0 = dernière frame du bloc précédent
+    position: f64,
+    last: Vec<f32>,
+}
+
+impl Resampler {
+    pub fn new(from_rate: u32, to_rate: u32, channels: u16) -> Self {
+        let channels = channels.max(1) as usize;
+        Self {
+            from_rate: from_rate.max(1),
+            to_rate: to_rate.max(1),
+            channels,
+            position: 1.0,
+            last: vec![0.0; channels],
+        }
+    }
+
+    pub fn from_rate(&self) -> u32 {
+        self.from_rate
+    }
+
+    pub fn to_rate(&self) -> u32 {
+        self.to_rate
+    }
+
+    pub fn is_passthrough(&self) -> bool {
+        self.from_rate == self.to_rate
+    }
+
+    /// Rééchantillonne un bloc entrelacé de `channels` canaux
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        if self.is_passthrough() {
+            return input.to_vec();
+        }
+        let ch = self.channels;
+        let frames = input.len() / ch;
+        if frames == 0 {
+            return Vec::new();
+        }
+
+        // Frame virtuelle k : 0 = `last`, k >= 1 = input[k - 1]
+        let frame_at = |k: usize| -> &[f32] {
+            if k == 0 { &self.last } else { &input[(k - 1) * ch..k * ch] }
+        };
+
+        let step = self.from_rate as f64 / self.to_rate as f64;
+        let mut out = Vec::with_capacity(((frames as f64 / step) as usize + 1) * ch);
+        let mut position = self.position;
+        while position < frames as f64 {
+            let i = position.floor() as usize;
+            let t = (position - i as f64) as f32;
+            let (a, b) = (frame_at(i), frame_at(i + 1));
+            for c in 0..ch {
+                out.push(a[c] + (b[c] - a[c]) * t);
+            }
+            position += step;
+        }
+
+        self.position = position - frames as f64;
+        self.last.copy_from_slice(&input[(frames - 1) * ch..frames * ch]);
+        out
+    }
+}
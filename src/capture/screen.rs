@@ -4,97 +4,270 @@ const MODULE_NAME: &str = "screen";
 const MODULE_ID: u8 = 1;
 const MODULE_VERSION: &str = "1.0";
 
-use scrap::{Capturer, Display};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use crossbeam::queue::SegQueue;
 
-use crate::config;
+use crate::capture::compositor::Compositor;
+use crate::capture::frame::{Rect, ScreenFrame};
+use crate::capture::mask::{apply_masks, PrivacyMask};
+use crate::capture::fps_controller::{frame_fingerprint, AdaptiveFps, FpsInputs};
+use crate::capture::frame_source::ScreenBackend;
+use crate::config::{self, ConfigFile};
 use crate::metrics::{Metrics, ModuleType};
+use crate::utils::queue::{capacity_for_ram, BoundedQueue, DropPolicy};
+
+/// Période de réévaluation du FPS adaptatif
+const FPS_EVAL_PERIOD: Duration = Duration::from_millis(250);
+
+/// Un input plus ancien que ce délai ne compte plus comme activité
+const INPUT_ACTIVITY_WINDOW: Duration = Duration::from_secs(2);
+
+/// Écrans capturés et zone retenue sur chacun
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScreenSelection {
+    /// Index des écrans à capturer (vide = tous)
+    pub displays: Vec<u16>,
+    /// Rectangle de découpe par écran (absent = écran entier)
+    pub regions: HashMap<u16, Rect>,
+}
+
+impl ScreenSelection {
+    pub fn from_config(file: &ConfigFile) -> Self {
+        Self {
+            displays: file.screen_displays.clone(),
+            regions: file
+                .screen_regions
+                .iter()
+                .map(|r| (r.display, Rect::new(r.x, r.y, r.width, r.height)))
+                .collect(),
+        }
+    }
+
+    pub fn includes(&self, display: u16) -> bool {
+        self.displays.is_empty() || self.displays.contains(&display)
+    }
+
+    /// Applique la découpe de l'écran de la frame (None si la zone est hors écran)
+    pub fn apply(&self, frame: ScreenFrame) -> Option<ScreenFrame> {
+        match self.regions.get(&frame.display_index) {
+            Some(rect) => frame.crop(rect),
+            None => Some(frame),
+        }
+    }
+}
 
 pub struct ScreenCapture {
-    fps: u32,
+    fps_controller: AdaptiveFps,
+    backend: ScreenBackend,
+    /// Mode canevas : tous les écrans sélectionnés dans une seule frame
+    compositor: Option<Compositor>,
     inner: Arc<ScreenInner>,
     metrics: Option<Arc<Metrics>>,
 }
 
 struct ScreenInner {
     running: Mutex<bool>,
-    frame_buffer: SegQueue<Vec<u8>>,
+    /// En pause : le thread tourne mais ne capture plus
+    paused: AtomicBool,
+    frame_buffer: BoundedQueue<ScreenFrame>,
     frames_captured: Mutex<u32>,
+    current_fps: Mutex<u32>,
+    selection: Mutex<ScreenSelection>,
+    masks: Mutex<Vec<PrivacyMask>>,
 }
 
 impl ScreenCapture {
     pub fn new() -> Self {
-        let resources = config::Config::get_available_resources();
-        let fps = if resources.cpu >= 4 { 60 } else { 30 };
+        let file = config::CONFIG.lock().unwrap().file.clone();
+        Self::with_config(&file)
+    }
 
-        let mut capturers = Vec::new();
-        for display in Display::all().unwrap_or_else(|_| vec![Display::primary().unwrap()]) {
-            if let Ok(c) = Capturer::new(display) {
-                capturers.push(c);
-            }
-        }
+    /// Construit la capture à partir d'une config explicite (tests, outils)
+    pub fn with_config(file: &ConfigFile) -> Self {
+        let initial_fps = if file.cpu_cores >= 4 { 60 } else { 30 };
+        let mut fps_controller = AdaptiveFps::new(file.screen_min_fps, file.screen_max_fps, initial_fps);
+        fps_controller.set_limits(file.screen_fps_cpu_limit, file.screen_fps_queue_limit);
 
         let inner = ScreenInner {
             running: Mutex::new(false),
-            frame_buffer: SegQueue::new(),
+            paused: AtomicBool::new(false),
+            // Coalesce : la frame la plus récente d'un écran remplace la précédente
+            frame_buffer: BoundedQueue::with_merge(
+                capacity_for_ram(file.screen_buffer_capacity, file.ram_gb, 4, 4, 240),
+                DropPolicy::parse_or(&file.screen_drop_policy, DropPolicy::DropOldest),
+                |queued, new| queued.display_index == new.display_index,
+            ),
             frames_captured: Mutex::new(0),
+            current_fps: Mutex::new(fps_controller.current_fps()),
+            selection: Mutex::new(ScreenSelection::from_config(file)),
+            masks: Mutex::new(PrivacyMask::all_from_config(&file.screen_masks)),
         };
 
         Self {
-            fps,
+            fps_controller,
+            backend: ScreenBackend::from_config(file),
+            compositor: file.screen_composite.then(|| Compositor::from_config(file)),
             inner: Arc::new(inner),
             metrics: None,
         }
     }
 
+    /// Remplace le backend de capture (effectif au prochain `start`)
+    pub fn set_backend(&mut self, backend: ScreenBackend) {
+        self.backend = backend;
+    }
+
+    pub fn backend(&self) -> &ScreenBackend {
+        &self.backend
+    }
+
+    /// Active (Some) ou désactive (None) la composition en canevas (effectif au prochain `start`)
+    pub fn set_compositor(&mut self, compositor: Option<Compositor>) {
+        self.compositor = compositor;
+    }
+
+    pub fn is_compositing(&self) -> bool {
+        self.compositor.is_some()
+    }
+
+    /// Restreint la capture à ces écrans (vide = tous), effectif immédiatement
+    pub fn set_displays(&self, displays: Vec<u16>) {
+        self.inner.selection.lock().unwrap().displays = displays;
+    }
+
+    /// Découpe l'écran `display` au rectangle donné (None = écran entier)
+    pub fn set_region(&self, display: u16, region: Option<Rect>) {
+        let mut selection = self.inner.selection.lock().unwrap();
+        match region {
+            Some(rect) => selection.regions.insert(display, rect),
+            None => selection.regions.remove(&display),
+        };
+    }
+
+    pub fn selection(&self) -> ScreenSelection {
+        self.inner.selection.lock().unwrap().clone()
+    }
+
+    /// Remplace tous les masques de confidentialité, effectif dès la frame suivante
+    pub fn set_masks(&self, masks: Vec<PrivacyMask>) {
+        *self.inner.masks.lock().unwrap() = masks;
+    }
+
+    pub fn add_mask(&self, mask: PrivacyMask) {
+        self.inner.masks.lock().unwrap().push(mask);
+    }
+
+    /// Retire les masques d'un écran (None = tous les écrans)
+    pub fn clear_masks(&self, display: Option<u16>) {
+        self.inner.masks.lock().unwrap().retain(|m| display.is_some_and(|d| m.display != d));
+    }
+
+    pub fn masks(&self) -> Vec<PrivacyMask> {
+        self.inner.masks.lock().unwrap().clone()
+    }
+
     pub fn attach_metrics(&mut self, metrics: Arc<Metrics>) {
         self.metrics = Some(metrics);
     }
 
     pub async fn start(&self) {
         let inner = Arc::clone(&self.inner);
-        let _fps = self.fps;
-        let fps_duration = Duration::from_millis(1000 / self.fps as u64);
+        let mut controller = self.fps_controller.clone();
         let metrics = self.metrics.clone();
+        let backend = self.backend.clone();
+        let mut compositor = self.compositor.clone();
 
         *inner.running.lock().unwrap() = true;
 
         // WARN: scrap::Capturer n'est pas Send et ne peut pas être partagé entre threads
-        // Solution: créer les capturers DANS le thread, pas avant
+        // Solution: ouvrir les sources DANS le thread, pas avant
         let _handle = std::thread::spawn(move || {
-            // Créer les capturers ICI, dans le thread, pour éviter les problèmes Send/Sync
-            let mut capturers = Vec::new();
-            for display in Display::all().unwrap_or_else(|_| vec![Display::primary().unwrap()]) {
-                if let Ok(c) = Capturer::new(display) {
-                    capturers.push(c);
+            let mut sources = match backend.open() {
+                Ok(sources) => sources,
+                Err(e) => {
+                    eprintln!("[{}] Cannot open frame sources: {}", MODULE_NAME, e);
+                    *inner.running.lock().unwrap() = false;
+                    return;
+                }
+            };
+
+            if let Some(c) = compositor.as_mut() {
+                for (index, source) in sources.iter().enumerate() {
+                    c.detect_origin(index as u16, source.origin());
                 }
             }
 
-            eprintln!("[{}] Screen capture thread started (v{})", MODULE_NAME, MODULE_VERSION);
+            eprintln!("[{}] Screen capture thread started (v{}, {} source(s))", MODULE_NAME, MODULE_VERSION, sources.len());
 
             let mut frame_count = 0;
             let mut last_fps_update = Instant::now();
+            let mut last_fps_eval = Instant::now();
+            let mut fingerprints: HashMap<usize, u64> = HashMap::new();
+            let mut content_changed = false;
 
             while *inner.running.lock().unwrap() {
+                if inner.paused.load(Ordering::Relaxed) {
+                    std::thread::sleep(controller.frame_interval());
+                    continue;
+                }
                 let loop_start = Instant::now();
+                let selection = inner.selection.lock().unwrap().clone();
+                let masks = inner.masks.lock().unwrap().clone();
+                let mut captured = Vec::with_capacity(sources.len());
 
-                for capturer in capturers.iter_mut() {
-                    match capturer.frame() {
-                        Ok(frame) => {
-                            inner.frame_buffer.push(frame.to_vec());
-                            let mut count = inner.frames_captured.lock().unwrap();
-                            *count = count.saturating_add(1);
-                            frame_count += 1;
-                            eprintln!("[{}] Captured frame #{} ({} bytes)", MODULE_ID, count, frame.len());
+                for (index, source) in sources.iter_mut().enumerate() {
+                    if !selection.includes(index as u16) {
+                        continue;
+                    }
+                    // Masquage (repère écran complet) puis découpe, avant toute mise en buffer :
+                    // empreinte, mémoire et compression ne voient que la zone autorisée
+                    let next = source.next_frame().map(|f| {
+                        f.and_then(|mut f| {
+                            apply_masks(&mut f, &masks);
+                            selection.apply(f)
+                        })
+                    });
+                    match next {
+                        Ok(Some(frame)) => {
+                            let fingerprint = frame_fingerprint(&frame);
+                            if fingerprints.insert(index, fingerprint) != Some(fingerprint) {
+                                content_changed = true;
+                            }
+                            captured.push(frame);
                         }
+                        Ok(None) => {}
                         Err(e) => {
-                            eprintln!("[{}] Capture error: {}", MODULE_NAME, e);
+                            eprintln!("[{}] Capture error on {}: {}", MODULE_NAME, source.name(), e);
                         }
                     }
                 }
 
+                // Mode canevas : une seule frame composite par tour de boucle
+                if let Some(c) = compositor.as_mut() {
+                    if !captured.is_empty() {
+                        c.retain(|display| selection.includes(display));
+                        for frame in captured.drain(..) {
+                            let region = selection.regions.get(&frame.display_index).copied();
+                            c.push(frame, region.as_ref());
+                        }
+                        captured.extend(c.compose());
+                    }
+                }
+
+                for frame in captured {
+                    let len = frame.data.len();
+                    let dropped = inner.frame_buffer.push(frame);
+                    if let Some(m) = &metrics {
+                        m.add_dropped(ModuleType::Screen, dropped);
+                    }
+                    let mut count = inner.frames_captured.lock().unwrap();
+                    *count = count.saturating_add(1);
+                    frame_count += 1;
+                    eprintln!("[{}] Captured frame #{} ({} bytes)", MODULE_ID, count, len);
+                }
+
                 // Mettre à jour les FPS toutes les 1 secondes
                 if last_fps_update.elapsed() >= Duration::from_secs(1) {
                     if let Some(m) = &metrics {
@@ -104,6 +277,25 @@ impl ScreenCapture {
                     last_fps_update = Instant::now();
                 }
 
+                // Réévaluer le FPS selon le contenu, l'input, le CPU et la file d'envoi
+                if last_fps_eval.elapsed() >= FPS_EVAL_PERIOD {
+                    let inputs = match &metrics {
+                        Some(m) => FpsInputs {
+                            content_changed,
+                            input_active: m
+                                .since_last_activity(ModuleType::Input)
+                                .is_some_and(|d| d < INPUT_ACTIVITY_WINDOW),
+                            cpu_percent: m.last_cpu(),
+                            queue_depth: m.get_queue_depth(ModuleType::Screen),
+                        },
+                        None => FpsInputs { content_changed, ..Default::default() },
+                    };
+                    *inner.current_fps.lock().unwrap() = controller.update(&inputs);
+                    content_changed = false;
+                    last_fps_eval = Instant::now();
+                }
+
+                let fps_duration = controller.frame_interval();
                 let elapsed = loop_start.elapsed();
                 if elapsed < fps_duration {
                     std::thread::sleep(fps_duration - elapsed);
@@ -113,21 +305,57 @@ impl ScreenCapture {
         });
     }
 
+    /// Suspend la capture sans arrêter le thread ni fermer les sources
+    pub fn pause(&self) {
+        self.inner.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.inner.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.inner.paused.load(Ordering::Relaxed)
+    }
+
     pub async fn stop(&self) {
         *self.inner.running.lock().unwrap() = false;
         // Attendre un peu pour que le thread se termine
         std::thread::sleep(Duration::from_millis(100));
     }
 
-    pub fn get_frame(&self) -> Option<Vec<u8>> {
+    pub fn get_frame(&self) -> Option<ScreenFrame> {
         self.inner.frame_buffer.pop()
     }
 
+    /// FPS cible actuel du régulateur adaptatif
     pub fn get_current_fps(&self) -> u32 {
-        self.fps
+        *self.inner.current_fps.lock().unwrap()
+    }
+
+    pub fn is_running(&self) -> bool {
+        *self.inner.running.lock().unwrap()
+    }
+
+    pub fn get_frames_captured(&self) -> u32 {
+        *self.inner.frames_captured.lock().unwrap()
     }
 
     pub fn clear_buffer(&self) {
-        while self.inner.frame_buffer.pop().is_some() {}
+        self.inner.frame_buffer.clear();
+    }
+
+    /// Frames en attente / capacité du buffer
+    pub fn buffer_len(&self) -> usize {
+        self.inner.frame_buffer.len()
+    }
+
+    pub fn buffer_capacity(&self) -> usize {
+        self.inner.frame_buffer.capacity()
+    }
+
+    /// Frames perdues faute de place dans le buffer
+    pub fn get_dropped(&self) -> u64 {
+        self.inner.frame_buffer.dropped()
     }
 }
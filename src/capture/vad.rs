@@ -0,0 +1,109 @@
+// visualisation_module/src/capture/vad.rs
+
+//! Détection d'activité vocale (VAD) par énergie et taux de passage par zéro
+//! - parole : énergie au-dessus du seuil, ou légèrement en dessous avec un taux
+//!   de passage par zéro élevé (consonnes sourdes : s, f, ch...)
+//! - maintien (`hangover`) : la parole est prolongée après la dernière frame
+//!   active pour ne pas couper les fins de mots
+//!
+//! Pendant le silence, le `Preprocessor` envoie un marqueur compact à la place de la frame.
+
+use crate::capture::meter::to_db;
+use crate::capture::mixer::MixedFrame;
+use crate::config::ConfigFile;
+
+/// Écart sous le seuil d'énergie accepté pour les sons à fort passage par zéro
+const UNVOICED_MARGIN_DB: f32 = 10.0;
+
+/// Taux de passage par zéro (par échantillon) typique des consonnes sourdes
+const UNVOICED_ZCR: f32 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VadSettings {
+    /// Énergie RMS (dBFS) au-dessus de laquelle une frame est active
+    pub threshold_db: f32,
+    /// Durée de maintien de la parole après la dernière frame active
+    pub hangover_ms: u32,
+}
+
+impl VadSettings {
+    /// Réglages de la config (None si la VAD est désactivée)
+    pub fn from_config(file: &ConfigFile) -> Option<Self> {
+        file.audio_vad_enabled.then_some(Self {
+            threshold_db: file.audio_vad_threshold_db,
+            hangover_ms: file.audio_vad_hangover_ms,
+        })
+    }
+}
+
+/// Décision de la VAD pour une frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VadDecision {
+    /// Frame à transmettre (parole ou maintien)
+    pub speech: bool,
+    /// Début d'un segment de parole
+    pub onset: bool,
+    /// Niveau RMS linéaire de la frame (sert au bruit de confort)
+    pub rms: f32,
+}
+
+/// Détecteur d'une piste (l'état de maintien est propre à chaque piste)
+#[derive(Debug, Clone)]
+pub struct VoiceDetector {
+    settings: VadSettings,
+    /// Maintien restant en millisecondes
+    hangover_left: f32,
+    speech: bool,
+}
+
+/// Proportion de changements de signe entre échantillons consécutifs (canaux moyennés)
+pub fn zero_crossing_rate(samples: &[f32], channels: u16) -> f32 {
+    let channels = channels.max(1) as usize;
+    let mono: Vec<f32> = samples.chunks_exact(channels).map(|c| c.iter().sum::<f32>()).collect();
+    if mono.len() < 2 {
+        return 0.0;
+    }
+    let crossings = mono.windows(2).filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0)).count();
+    crossings as f32 / (mono.len() - 1) as f32
+}
+
+impl VoiceDetector {
+    pub fn new(settings: VadSettings) -> Self {
+        Self { settings, hangover_left: 0.0, speech: false }
+    }
+
+    pub fn settings(&self) -> VadSettings {
+        self.settings
+    }
+
+    /// Dans un segment de parole (maintien compris)
+    pub fn in_speech(&self) -> bool {
+        self.speech
+    }
+
+    pub fn process(&mut self, frame: &MixedFrame) -> VadDecision {
+        let rms = if frame.samples.is_empty() {
+            0.0
+        } else {
+            (frame.samples.iter().map(|s| s * s).sum::<f32>() / frame.samples.len() as f32).sqrt()
+        };
+        let level_db = to_db(rms);
+        let threshold = self.settings.threshold_db;
+        let active = level_db >= threshold
+            || (level_db >= threshold - UNVOICED_MARGIN_DB
+                && zero_crossing_rate(&frame.samples, frame.channels) >= UNVOICED_ZCR);
+
+        let channels = frame.channels.max(1) as f32;
+        let duration_ms = frame.samples.len() as f32 / channels / frame.sample_rate.max(1) as f32 * 1000.0;
+        if active {
+            self.hangover_left = self.settings.hangover_ms as f32;
+        } else {
+            self.hangover_left = (self.hangover_left - duration_ms).max(0.0);
+        }
+
+        let speech = active || self.hangover_left > 0.0;
+        let onset = speech && !self.speech;
+        self.speech = speech;
+        VadDecision { speech, onset, rms }
+    }
+}
@@ -0,0 +1,309 @@
+// visualisation_module/src/capture/wav_sink.rs
+
+//! Enregistrement local des pistes audio en WAV (PCM 16 bits)
+//! - un fichier par piste (`mixed`, `mic`, `system`), nommé par date de début
+//! - rotation après `audio_record_rotate_secs` (ou changement de format de la piste)
+//! - en-tête remis à jour après chaque frame : un fichier coupé net (crash, kill)
+//!   reste lisible ; `repair_wav` corrige les tailles d'un fichier resté incohérent
+//!   (au démarrage, uniquement les fichiers nommés par l'enregistreur)
+//!
+//! `audio_record_mode` : `off`, `alongside` (en plus du réseau) ou `only` (à la place).
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::Local;
+
+use crate::capture::mixer::{MixedFrame, TRACK_MIC, TRACK_MIXED, TRACK_SYSTEM};
+use crate::config::ConfigFile;
+use crate::error::ModuleError;
+
+/// Taille de données au-delà de laquelle on change de fichier (limite 32 bits du RIFF)
+const MAX_DATA_BYTES: u64 = u32::MAX as u64 - (1 << 20);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordMode {
+    Off,
+    /// Enregistrement en plus de la transmission réseau
+    Alongside,
+    /// Enregistrement seul, l'audio n'est plus transmis
+    Only,
+}
+
+impl RecordMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "" | "off" | "none" => Some(RecordMode::Off),
+            "alongside" | "both" | "on" => Some(RecordMode::Alongside),
+            "only" | "local" => Some(RecordMode::Only),
+            _ => None,
+        }
+    }
+
+    pub fn records(self) -> bool {
+        self != RecordMode::Off
+    }
+
+    pub fn transmits(self) -> bool {
+        self != RecordMode::Only
+    }
+}
+
+fn track_name(track: u8) -> String {
+    match track {
+        TRACK_MIXED => "mixed".to_string(),
+        TRACK_MIC => "mic".to_string(),
+        TRACK_SYSTEM => "system".to_string(),
+        other => format!("track{}", other),
+    }
+}
+
+fn io_error(path: &Path, e: impl std::fmt::Display) -> ModuleError {
+    ModuleError::IoError(format!("{}: {}", path.display(), e))
+}
+
+/// Fichier en cours d'écriture pour une piste
+struct TrackWriter {
+    writer: hound::WavWriter<BufWriter<File>>,
+    sample_rate: u32,
+    channels: u16,
+}
+
+pub struct WavRecorder {
+    dir: PathBuf,
+    /// Durée maximale d'un fichier (None = pas de rotation)
+    rotate: Option<Duration>,
+    writers: Mutex<HashMap<u8, TrackWriter>>,
+    /// Fichiers créés, dans l'ordre
+    files: Mutex<Vec<PathBuf>>,
+}
+
+impl WavRecorder {
+    /// Crée le dossier d'enregistrement et répare les fichiers d'une session interrompue
+    pub fn new(dir: impl Into<PathBuf>, rotate: Option<Duration>) -> Result<Self, ModuleError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
+        let repaired = repair_dir(&dir);
+        if repaired > 0 {
+            eprintln!("[wav] {} recording(s) repaired in {}", repaired, dir.display());
+        }
+        Ok(Self {
+            dir,
+            rotate: rotate.filter(|d| !d.is_zero()),
+            writers: Mutex::new(HashMap::new()),
+            files: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Enregistreur de la config (None si `audio_record_mode` vaut `off` ou est invalide)
+    pub fn from_config(file: &ConfigFile) -> Option<Self> {
+        let mode = RecordMode::from_name(&file.audio_record_mode).unwrap_or_else(|| {
+            eprintln!("[wav] Unknown audio_record_mode '{}', recording disabled", file.audio_record_mode);
+            RecordMode::Off
+        });
+        if !mode.records() {
+            return None;
+        }
+        match Self::new(&file.audio_record_dir, Some(Duration::from_secs(file.audio_record_rotate_secs))) {
+            Ok(recorder) => Some(recorder),
+            Err(e) => {
+                eprintln!("[wav] Recording disabled: {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Fichiers créés depuis le démarrage
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.files.lock().unwrap().clone()
+    }
+
+    /// Ajoute une frame au fichier de sa piste (nouveau fichier si rotation)
+    pub fn write(&self, frame: &MixedFrame) -> Result<(), ModuleError> {
+        let channels = frame.channels.max(1);
+        let mut writers = self.writers.lock().unwrap();
+
+        let rotate = writers.get(&frame.track_id).is_some_and(|w| {
+            let written = w.writer.duration() as u64;
+            let bytes = w.writer.len() as u64 * 2;
+            w.sample_rate != frame.sample_rate
+                || w.channels != channels
+                || self.rotate.is_some_and(|d| written >= (d.as_secs_f64() * w.sample_rate as f64) as u64)
+                || bytes + frame.samples.len() as u64 * 2 > MAX_DATA_BYTES
+        });
+        if rotate {
+            if let Some(old) = writers.remove(&frame.track_id) {
+                old.writer.finalize().map_err(|e| ModuleError::IoError(e.to_string()))?;
+            }
+        }
+
+        let track = match writers.entry(frame.track_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(self.create(frame.track_id, frame.sample_rate, channels)?),
+        };
+        let usable = frame.samples.len() - frame.samples.len() % channels as usize;
+        for &s in &frame.samples[..usable] {
+            let sample = (s.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+            track.writer.write_sample(sample).map_err(|e| ModuleError::IoError(e.to_string()))?;
+        }
+        // En-tête à jour : le fichier reste valide si le processus est tué
+        track.writer.flush().map_err(|e| ModuleError::IoError(e.to_string()))
+    }
+
+    fn create(&self, track: u8, sample_rate: u32, channels: u16) -> Result<TrackWriter, ModuleError> {
+        let stamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+        let mut files = self.files.lock().unwrap();
+        // Suffixe pour les rotations dans la même seconde
+        let mut path = self.dir.join(format!("{}_{}.wav", track_name(track), stamp));
+        let mut n = 1;
+        while path.exists() || files.contains(&path) {
+            path = self.dir.join(format!("{}_{}_{}.wav", track_name(track), stamp, n));
+            n += 1;
+        }
+
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let writer = hound::WavWriter::create(&path, spec).map_err(|e| io_error(&path, e))?;
+        eprintln!("[wav] Recording {} to {}", track_name(track), path.display());
+        files.push(path);
+        Ok(TrackWriter { writer, sample_rate, channels })
+    }
+
+    /// Termine les fichiers en cours (les frames suivantes ouvrent de nouveaux fichiers)
+    pub fn finalize(&self) -> Result<(), ModuleError> {
+        let mut result = Ok(());
+        for (_, track) in self.writers.lock().unwrap().drain() {
+            if let Err(e) = track.writer.finalize() {
+                result = Err(ModuleError::IoError(e.to_string()));
+            }
+        }
+        result
+    }
+}
+
+impl Drop for WavRecorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.finalize() {
+            eprintln!("[wav] Cannot finalize recordings: {}", e);
+        }
+    }
+}
+
+/// Nom de fichier produit par `WavRecorder` : `<piste>_<AAAAMMJJ>_<HHMMSS>[_n].wav`
+pub fn is_recording_name(name: &str) -> bool {
+    let digits = |s: &str, len: Option<usize>| {
+        !s.is_empty() && len.is_none_or(|l| s.len() == l) && s.bytes().all(|b| b.is_ascii_digit())
+    };
+    let Some(stem) = name.strip_suffix(".wav") else { return false };
+    let parts: Vec<&str> = stem.split('_').collect();
+    if !(3..=4).contains(&parts.len()) {
+        return false;
+    }
+    let track = match parts[0] {
+        "mixed" | "mic" | "system" => true,
+        other => other.strip_prefix("track").is_some_and(|n| digits(n, None)),
+    };
+    track && digits(parts[1], Some(8)) && digits(parts[2], Some(6)) && parts.get(3).is_none_or(|n| digits(n, None))
+}
+
+/// Les octets de `start` à `end` forment une suite de chunks RIFF complets (LIST, cue...)
+fn is_chunk_chain(file: &mut File, start: u64, end: u64) -> bool {
+    let mut pos = start;
+    while pos + 8 <= end {
+        let mut header = [0u8; 8];
+        if file.seek(SeekFrom::Start(pos)).is_err() || file.read_exact(&mut header).is_err() {
+            return false;
+        }
+        if !header[0..4].iter().all(|b| (0x20..0x7f).contains(b)) {
+            return false;
+        }
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as u64;
+        pos += 8 + size + size % 2;
+    }
+    pos > start && (pos == end || pos == end + 1)
+}
+
+/// Recalcule les tailles RIFF et `data` d'un enregistrement interrompu.
+/// Retourne true si l'en-tête a été corrigé, false s'il était déjà cohérent.
+/// Un chunk `data` de taille valide suivi d'autres chunks n'est jamais modifié,
+/// et aucun octet n'est supprimé : une trame incomplète reste hors du chunk `data`.
+pub fn repair_wav(path: &Path) -> Result<bool, ModuleError> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path).map_err(|e| io_error(path, e))?;
+    let file_len = file.metadata().map_err(|e| io_error(path, e))?.len();
+
+    let mut header = [0u8; 12];
+    file.read_exact(&mut header).map_err(|e| io_error(path, e))?;
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        return Err(ModuleError::ValidationError(format!("{}: not a WAV file", path.display())));
+    }
+
+    // Parcours des chunks jusqu'à `data` (alignement des trames lu dans `fmt `)
+    let mut block_align = 1u64;
+    let mut pos = 12u64;
+    loop {
+        let mut chunk = [0u8; 8];
+        file.seek(SeekFrom::Start(pos)).map_err(|e| io_error(path, e))?;
+        if file.read_exact(&mut chunk).is_err() {
+            return Err(ModuleError::ValidationError(format!("{}: no data chunk", path.display())));
+        }
+        let size = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as u64;
+        if &chunk[0..4] == b"fmt " {
+            let mut fmt = [0u8; 14];
+            file.read_exact(&mut fmt).map_err(|e| io_error(path, e))?;
+            block_align = u16::from_le_bytes([fmt[12], fmt[13]]).max(1) as u64;
+        } else if &chunk[0..4] == b"data" {
+            let data_start = pos + 8;
+            let data_end = data_start + size;
+            // Taille valide suivie de chunks complets : fichier finalisé, on n'y touche pas
+            let size_valid = data_end <= file_len && size.is_multiple_of(block_align);
+            if size_valid && data_end < file_len && is_chunk_chain(&mut file, data_end + size % 2, file_len) {
+                return Ok(false);
+            }
+
+            let available = file_len.saturating_sub(data_start).min(MAX_DATA_BYTES);
+            let data_len = available - available % block_align;
+            let riff_len = data_start + data_len - 8;
+            let current_riff = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as u64;
+            if size == data_len && current_riff == riff_len {
+                return Ok(false);
+            }
+            file.seek(SeekFrom::Start(4)).map_err(|e| io_error(path, e))?;
+            file.write_all(&(riff_len as u32).to_le_bytes()).map_err(|e| io_error(path, e))?;
+            file.seek(SeekFrom::Start(pos + 4)).map_err(|e| io_error(path, e))?;
+            file.write_all(&(data_len as u32).to_le_bytes()).map_err(|e| io_error(path, e))?;
+            return Ok(true);
+        }
+        pos += 8 + size + size % 2;
+    }
+}
+
+/// Répare les enregistrements interrompus d'un dossier (seuls les fichiers nommés par
+/// `WavRecorder` sont examinés) ; retourne le nombre de fichiers corrigés
+pub fn repair_dir(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else { return 0 };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(is_recording_name))
+        .filter(|p| match repair_wav(p) {
+            Ok(repaired) => repaired,
+            Err(e) => {
+                eprintln!("[wav] Cannot repair {}: {}", p.display(), e);
+                false
+            }
+        })
+        .count()
+}
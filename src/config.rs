@@ -31,8 +31,228 @@ pub struct ConfigFile {
     pub ping_timeout_ms: u64,
     pub ethernet_enabled: bool,
     pub bluetooth_enabled: bool,
+    #[serde(default = "default_screen_backend")]
+    pub screen_backend: String,
+    #[serde(default = "default_synthetic_width")]
+    pub screen_synthetic_width: usize,
+    #[serde(default = "default_synthetic_height")]
+    pub screen_synthetic_height: usize,
+    #[serde(default = "default_synthetic_displays")]
+    pub screen_synthetic_displays: usize,
+    #[serde(default = "default_tile_size")]
+    pub screen_tile_size: u32,
+    #[serde(default = "default_keyframe_interval")]
+    pub screen_keyframe_interval: u32,
+    #[serde(default = "default_fps_cpu_limit")]
+    pub screen_fps_cpu_limit: f32,
+    #[serde(default = "default_fps_queue_limit")]
+    pub screen_fps_queue_limit: usize,
+    /// Écrans à capturer (vide = tous)
+    #[serde(default)]
+    pub screen_displays: Vec<u16>,
+    /// Zone capturée par écran (écran entier si absent)
+    #[serde(default)]
+    pub screen_regions: Vec<ScreenRegion>,
+    /// Composite tous les écrans sélectionnés dans un seul canevas
+    #[serde(default)]
+    pub screen_composite: bool,
+    /// Disposition du canevas : position | horizontal | vertical
+    #[serde(default = "default_screen_layout")]
+    pub screen_layout: String,
+    #[serde(default = "default_screen_scale")]
+    pub screen_scale: f32,
+    /// Position réelle des écrans sur le bureau (prioritaire sur la détection)
+    #[serde(default)]
+    pub screen_positions: Vec<ScreenPosition>,
+    /// Zones masquées avant toute sortie du thread de capture
+    #[serde(default)]
+    pub screen_masks: Vec<ScreenMask>,
+    /// Capacité des buffers de capture (0 = dérivée de `ram_gb`)
+    #[serde(default)]
+    pub screen_buffer_capacity: usize,
+    #[serde(default)]
+    pub audio_buffer_capacity: usize,
+    #[serde(default)]
+    pub input_buffer_capacity: usize,
+    /// Politique quand un buffer est plein : oldest | newest | coalesce
+    #[serde(default = "default_drop_oldest")]
+    pub screen_drop_policy: String,
+    #[serde(default = "default_drop_oldest")]
+    pub audio_drop_policy: String,
+    #[serde(default = "default_drop_coalesce")]
+    pub input_drop_policy: String,
+    /// Format des événements d'entrée transmis : json | binary
+    #[serde(default = "default_input_serialization")]
+    pub input_serialization: String,
+    /// Conserve les extrêmes du trajet souris en plus de la position finale de chaque fenêtre
+    #[serde(default)]
+    pub input_move_extrema: bool,
+    /// Raccourcis globaux : action (start_stop | pause_resume | marker | save_replay) et touches
+    #[serde(default)]
+    pub input_hotkeys: Vec<HotkeyBinding>,
+    /// Les appuis des raccourcis ne sont pas enregistrés dans le flux d'entrées
+    #[serde(default)]
+    pub input_hotkeys_exclude: bool,
+    /// Backend audio : cpal | mock
+    #[serde(default = "default_audio_backend")]
+    pub audio_backend: String,
+    /// Capture le son du système (loopback / monitor) en plus du micro
+    #[serde(default)]
+    pub audio_loopback_enabled: bool,
+    /// Périphérique du micro : nom (ou partie du nom), index ("#1") ou "default"
+    #[serde(default)]
+    pub audio_input_device: String,
+    /// Période de surveillance du périphérique du micro (0 = pas de bascule automatique)
+    #[serde(default = "default_device_poll_ms")]
+    pub audio_device_poll_ms: u64,
+    /// Enregistrement WAV des pistes : off | alongside (en plus du réseau) | only
+    #[serde(default = "default_record_mode")]
+    pub audio_record_mode: String,
+    #[serde(default = "default_record_dir")]
+    pub audio_record_dir: String,
+    /// Durée d'un fichier avant rotation (0 = pas de rotation)
+    #[serde(default = "default_record_rotate_secs")]
+    pub audio_record_rotate_secs: u64,
+    /// Sortie du mixeur : mixed (une piste) | separate (micro et système séparés)
+    #[serde(default = "default_audio_mix_mode")]
+    pub audio_mix_mode: String,
+    /// Mode mixé : attente maximale d'une source en retard avant de mixer sans elle (silence)
+    #[serde(default = "default_audio_mix_wait_ms")]
+    pub audio_mix_wait_ms: u64,
+    #[serde(default = "default_gain")]
+    pub audio_mic_gain: f32,
+    #[serde(default)]
+    pub audio_mic_muted: bool,
+    #[serde(default)]
+    pub audio_mic_pan: f32,
+    #[serde(default = "default_gain")]
+    pub audio_system_gain: f32,
+    #[serde(default)]
+    pub audio_system_muted: bool,
+    #[serde(default)]
+    pub audio_system_pan: f32,
+    /// Canaux des frames audio produites (1 = mono, 2 = stéréo)
+    #[serde(default = "default_audio_channels")]
+    pub audio_channels: u16,
+    /// Bandes du spectre remonté dans les métriques (0 = désactivé)
+    #[serde(default)]
+    pub audio_spectrum_bands: usize,
+    /// Détection d'activité vocale : marqueurs de silence à la place des frames
+    #[serde(default)]
+    pub audio_vad_enabled: bool,
+    #[serde(default = "default_vad_threshold_db")]
+    pub audio_vad_threshold_db: f32,
+    #[serde(default = "default_vad_hangover_ms")]
+    pub audio_vad_hangover_ms: u32,
+    /// Filtres appliqués au micro, dans l'ordre : gate, highpass, compressor, normalize
+    #[serde(default)]
+    pub audio_dsp_chain: Vec<String>,
+    #[serde(default = "default_gate_threshold_db")]
+    pub audio_gate_threshold_db: f32,
+    #[serde(default = "default_gate_attack_ms")]
+    pub audio_gate_attack_ms: f32,
+    #[serde(default = "default_gate_release_ms")]
+    pub audio_gate_release_ms: f32,
+    #[serde(default = "default_highpass_hz")]
+    pub audio_highpass_hz: f32,
+    #[serde(default = "default_compressor_threshold_db")]
+    pub audio_compressor_threshold_db: f32,
+    #[serde(default = "default_compressor_ratio")]
+    pub audio_compressor_ratio: f32,
+    #[serde(default = "default_compressor_attack_ms")]
+    pub audio_compressor_attack_ms: f32,
+    #[serde(default = "default_compressor_release_ms")]
+    pub audio_compressor_release_ms: f32,
+    #[serde(default)]
+    pub audio_compressor_makeup_db: f32,
+    #[serde(default = "default_normalize_target_db")]
+    pub audio_normalize_target_db: f32,
+    #[serde(default = "default_normalize_max_gain_db")]
+    pub audio_normalize_max_gain_db: f32,
 }
 
+/// Raccourci global : `keys` au format `ctrl+shift+r`
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct HotkeyBinding {
+    pub action: String,
+    pub keys: String,
+}
+
+/// Rectangle de capture d'un écran (pixels, repère de l'écran)
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ScreenRegion {
+    pub display: u16,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Origine d'un écran dans le repère du bureau virtuel
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ScreenPosition {
+    pub display: u16,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Masque de confidentialité (repère de l'écran complet, avant découpe)
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ScreenMask {
+    pub display: u16,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// fill | pixelate
+    #[serde(default = "default_mask_style")]
+    pub style: String,
+    /// Couleur RGB du remplissage
+    #[serde(default)]
+    pub color: [u8; 3],
+    /// Taille des blocs de pixelisation
+    #[serde(default = "default_mask_block")]
+    pub block: u32,
+}
+
+// Valeurs par défaut des champs optionnels du YAML
+fn default_screen_backend() -> String { "scrap".to_string() }
+fn default_synthetic_width() -> usize { 1280 }
+fn default_synthetic_height() -> usize { 720 }
+fn default_synthetic_displays() -> usize { 1 }
+fn default_tile_size() -> u32 { 64 }
+fn default_keyframe_interval() -> u32 { 120 }
+fn default_fps_cpu_limit() -> f32 { 85.0 }
+fn default_fps_queue_limit() -> usize { 250 }
+fn default_screen_layout() -> String { "position".to_string() }
+fn default_screen_scale() -> f32 { 1.0 }
+fn default_mask_style() -> String { "fill".to_string() }
+fn default_mask_block() -> u32 { 16 }
+fn default_drop_oldest() -> String { "oldest".to_string() }
+fn default_drop_coalesce() -> String { "coalesce".to_string() }
+fn default_input_serialization() -> String { "json".to_string() }
+fn default_audio_backend() -> String { "cpal".to_string() }
+fn default_audio_mix_mode() -> String { "mixed".to_string() }
+fn default_audio_mix_wait_ms() -> u64 { 50 }
+fn default_gain() -> f32 { 1.0 }
+fn default_audio_channels() -> u16 { 2 }
+fn default_vad_threshold_db() -> f32 { -45.0 }
+fn default_vad_hangover_ms() -> u32 { 300 }
+fn default_device_poll_ms() -> u64 { 1000 }
+fn default_record_mode() -> String { "off".to_string() }
+fn default_record_dir() -> String { "recordings".to_string() }
+fn default_record_rotate_secs() -> u64 { 3600 }
+fn default_gate_threshold_db() -> f32 { -50.0 }
+fn default_gate_attack_ms() -> f32 { 5.0 }
+fn default_gate_release_ms() -> f32 { 150.0 }
+fn default_highpass_hz() -> f32 { 80.0 }
+fn default_compressor_threshold_db() -> f32 { -18.0 }
+fn default_compressor_ratio() -> f32 { 4.0 }
+fn default_compressor_attack_ms() -> f32 { 10.0 }
+fn default_compressor_release_ms() -> f32 { 100.0 }
+fn default_normalize_target_db() -> f32 { -20.0 }
+fn default_normalize_max_gain_db() -> f32 { 12.0 }
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub file: ConfigFile,
@@ -92,6 +312,63 @@ impl Config {
                 ping_timeout_ms: 5000,
                 ethernet_enabled: true,
                 bluetooth_enabled: false,
+                screen_backend: default_screen_backend(),
+                screen_synthetic_width: default_synthetic_width(),
+                screen_synthetic_height: default_synthetic_height(),
+                screen_synthetic_displays: default_synthetic_displays(),
+                screen_tile_size: default_tile_size(),
+                screen_keyframe_interval: default_keyframe_interval(),
+                screen_fps_cpu_limit: default_fps_cpu_limit(),
+                screen_fps_queue_limit: default_fps_queue_limit(),
+                screen_displays: Vec::new(),
+                screen_regions: Vec::new(),
+                screen_composite: false,
+                screen_layout: default_screen_layout(),
+                screen_scale: default_screen_scale(),
+                screen_positions: Vec::new(),
+                screen_masks: Vec::new(),
+                screen_buffer_capacity: 0,
+                audio_buffer_capacity: 0,
+                input_buffer_capacity: 0,
+                screen_drop_policy: default_drop_oldest(),
+                audio_drop_policy: default_drop_oldest(),
+                input_drop_policy: default_drop_coalesce(),
+                input_serialization: default_input_serialization(),
+                input_move_extrema: false,
+                input_hotkeys: Vec::new(),
+                input_hotkeys_exclude: false,
+                audio_backend: default_audio_backend(),
+                audio_loopback_enabled: false,
+                audio_input_device: String::new(),
+                audio_device_poll_ms: default_device_poll_ms(),
+                audio_record_mode: default_record_mode(),
+                audio_record_dir: default_record_dir(),
+                audio_record_rotate_secs: default_record_rotate_secs(),
+                audio_mix_mode: default_audio_mix_mode(),
+                audio_mix_wait_ms: default_audio_mix_wait_ms(),
+                audio_mic_gain: default_gain(),
+                audio_mic_muted: false,
+                audio_mic_pan: 0.0,
+                audio_system_gain: default_gain(),
+                audio_system_muted: false,
+                audio_system_pan: 0.0,
+                audio_channels: default_audio_channels(),
+                audio_spectrum_bands: 0,
+                audio_vad_enabled: false,
+                audio_vad_threshold_db: default_vad_threshold_db(),
+                audio_vad_hangover_ms: default_vad_hangover_ms(),
+                audio_dsp_chain: Vec::new(),
+                audio_gate_threshold_db: default_gate_threshold_db(),
+                audio_gate_attack_ms: default_gate_attack_ms(),
+                audio_gate_release_ms: default_gate_release_ms(),
+                audio_highpass_hz: default_highpass_hz(),
+                audio_compressor_threshold_db: default_compressor_threshold_db(),
+                audio_compressor_ratio: default_compressor_ratio(),
+                audio_compressor_attack_ms: default_compressor_attack_ms(),
+                audio_compressor_release_ms: default_compressor_release_ms(),
+                audio_compressor_makeup_db: 0.0,
+                audio_normalize_target_db: default_normalize_target_db(),
+                audio_normalize_max_gain_db: default_normalize_max_gain_db(),
             },
         }
     }
@@ -124,6 +401,10 @@ impl Config {
         CONFIG.lock().unwrap().file.screen_max_fps
     }
 
+    pub fn get_screen_min_fps() -> u32 {
+        CONFIG.lock().unwrap().file.screen_min_fps
+    }
+
     pub fn get_audio_sample_rate() -> u32 {
         CONFIG.lock().unwrap().file.audio_sample_rate
     }
@@ -131,4 +412,8 @@ impl Config {
     pub fn get_screen_compression() -> String {
         CONFIG.lock().unwrap().file.screen_compression.clone()
     }
+
+    pub fn get_screen_backend() -> String {
+        CONFIG.lock().unwrap().file.screen_backend.clone()
+    }
 }
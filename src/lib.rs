@@ -23,7 +23,7 @@ pub use capture::{
 
 pub use config::Config;
 pub use logging::{LOGGER, LoggingManager, LogEntry};
-pub use metrics::{Metrics, MetricsSummary, ModuleType};
+pub use metrics::{AudioLevels, Metrics, MetricsSummary, ModuleType};
 pub use error::ErrorManager;
 pub use ping::Ping;
 pub use transmitter::{Transmitter, Packet, PacketType};
@@ -11,6 +11,7 @@ use visualisation_module::{
     Ping, StateManager,
     Metrics, LoggingManager, Config,
 };
+use visualisation_module::capture::HotkeyAction;
 
 #[tokio::main]
 async fn main() {
@@ -34,7 +35,9 @@ async fn main() {
     audio.attach_metrics(Arc::clone(&metrics));
     let audio = Arc::new(audio);
     
-    let input = Arc::new(InputCapture::new());
+    let mut input = InputCapture::new();
+    input.attach_metrics(Arc::clone(&metrics));
+    let input = Arc::new(input);
 
     logging.push_log(visualisation_module::LogEntry::new("main", "Modules capture initialisés"));
 
@@ -75,6 +78,70 @@ async fn main() {
 
     logging.push_log(visualisation_module::LogEntry::new("main", "Préprocesseur initialisé"));
 
+    // --- Raccourcis globaux (événements de contrôle de l'InputCapture) ---
+    let control_input = Arc::clone(&input);
+    let control_screen = Arc::clone(&screen);
+    let control_audio = Arc::clone(&audio);
+    let control_logging = Arc::clone(&logging);
+    tokio::spawn(async move {
+        let mut capturing = true;
+        let mut paused = false;
+        loop {
+            while let Some(event) = control_input.get_control_event() {
+                let message = match event.action {
+                    HotkeyAction::StartStop => {
+                        capturing = !capturing;
+                        // Une pause en cours prend fin avec le changement d'état
+                        paused = false;
+                        control_screen.resume();
+                        control_audio.resume();
+                        if capturing {
+                            control_screen.start().await;
+                            control_audio.start().await;
+                            control_input.resume();
+                            "Capture démarrée".to_string()
+                        } else {
+                            // L'entrée reste à l'écoute des raccourcis, sans rien enregistrer
+                            control_input.pause();
+                            control_screen.stop().await;
+                            control_audio.stop().await;
+                            "Capture arrêtée".to_string()
+                        }
+                    }
+                    HotkeyAction::PauseResume if !capturing => "Capture arrêtée : pause ignorée".to_string(),
+                    // Les sources restent ouvertes (pas de fin d'enregistrement WAV) ;
+                    // l'entrée continue d'écouter les raccourcis
+                    HotkeyAction::PauseResume => {
+                        paused = !paused;
+                        if paused {
+                            control_screen.pause();
+                            control_audio.pause();
+                            control_input.pause();
+                            "Capture suspendue".to_string()
+                        } else {
+                            control_screen.resume();
+                            control_audio.resume();
+                            control_input.resume();
+                            "Capture reprise".to_string()
+                        }
+                    }
+                    // Repère inséré dans le flux d'entrées transmis au pool
+                    HotkeyAction::Marker => {
+                        control_input.push_control(&event);
+                        format!("Repère à pts {} ({} ms)", event.pts, event.timestamp)
+                    }
+                    // Le tampon de rejeu est tenu par le pool : la demande lui est transmise
+                    HotkeyAction::SaveReplay => {
+                        control_input.push_control(&event);
+                        format!("Sauvegarde du rejeu demandée au pool à pts {}", event.pts)
+                    }
+                };
+                control_logging.push_log(visualisation_module::LogEntry::new("hotkeys".to_string(), message));
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    });
+
     // --- Ping H24 ---
     let ping = Arc::new(Ping::new(Arc::clone(&metrics)));
     ping.start();
@@ -97,6 +164,7 @@ async fn main() {
 
     // --- Transmitter start ---
     transmitter.start();
+    preprocessor.start();
 
     logging.push_log(visualisation_module::LogEntry::new("main", "Module opérationnel - Mode H24"));
 
@@ -109,12 +177,21 @@ async fn main() {
         
         // Log des métriques tous les 10s
         let summary = metrics.get_summary();
+        let levels = |l: &Option<visualisation_module::AudioLevels>| match l {
+            Some(l) => format!("{:.0}/{:.0}dB clip {}", l.rms_db, l.peak_db, l.clipped),
+            None => "-".to_string(),
+        };
         let log_msg = format!(
-            "CPU: {:.1}% | RAM: {}MB | Screen FPS: {} | Ping: {}ms",
+            "CPU: {:.1}% | RAM: {}MB | Screen FPS: {} | Ping: {}ms | Drops S/A/I: {}/{}/{} | Mic: {} | System: {}",
             summary.avg_cpu,
             summary.avg_ram_mb,
             summary.avg_fps_screen,
-            summary.avg_ping_ms.unwrap_or(0)
+            summary.avg_ping_ms.unwrap_or(0),
+            summary.dropped_screen,
+            summary.dropped_audio,
+            summary.dropped_input,
+            levels(&summary.levels_audio),
+            levels(&summary.levels_audio_loopback)
         );
         logging.push_log(visualisation_module::LogEntry::debug("metrics", &log_msg));
     }
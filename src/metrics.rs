@@ -9,6 +9,8 @@ use sysinfo::System;
 pub enum ModuleType {
     Screen,
     Audio,
+    /// Piste audio loopback (son du système)
+    AudioLoopback,
     Input,
     Preprocessor,
     Transmitter,
@@ -32,6 +34,34 @@ pub struct Metrics {
     cpu_history: Mutex<VecDeque<(Instant, f32)>>,
     ram_history: Mutex<VecDeque<(Instant, u64)>>,
     sys_history_max: usize,
+
+    // Profondeur des files du transmitter
+    queue_depth: Mutex<HashMap<ModuleType, usize>>,
+
+    // Dernière activité par module (ex: événement clavier/souris)
+    last_activity: Mutex<HashMap<ModuleType, Instant>>,
+
+    // Éléments perdus par les buffers de capture pleins
+    dropped: Mutex<HashMap<ModuleType, u64>>,
+
+    // Niveaux de la dernière frame audio par piste (écrêtage cumulé)
+    audio_levels: Mutex<HashMap<ModuleType, AudioLevels>>,
+
+    // Changements de périphérique audio (perte, bascule, retour)
+    device_switches: Mutex<HashMap<ModuleType, u64>>,
+}
+
+/// Niveaux mesurés sur une piste audio
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioLevels {
+    /// RMS en dBFS (-100 = silence)
+    pub rms_db: f32,
+    /// Crête en dBFS
+    pub peak_db: f32,
+    /// Échantillons écrêtés
+    pub clipped: u64,
+    /// Spectre grossier en dBFS par bande (vide si désactivé)
+    pub spectrum_db: Vec<f32>,
 }
 
 impl Metrics {
@@ -46,6 +76,11 @@ impl Metrics {
             cpu_history: Mutex::new(VecDeque::with_capacity(200)),
             ram_history: Mutex::new(VecDeque::with_capacity(200)),
             sys_history_max: 200,
+            queue_depth: Mutex::new(HashMap::new()),
+            last_activity: Mutex::new(HashMap::new()),
+            dropped: Mutex::new(HashMap::new()),
+            audio_levels: Mutex::new(HashMap::new()),
+            device_switches: Mutex::new(HashMap::new()),
         })
     }
 
@@ -77,6 +112,11 @@ impl Metrics {
         hist.iter().map(|(_, v)| *v).sum::<f32>() / hist.len() as f32
     }
 
+    /// Dernière mesure CPU (0 si jamais mesurée)
+    pub fn last_cpu(&self) -> f32 {
+        self.cpu_history.lock().unwrap().back().map(|(_, v)| *v).unwrap_or(0.0)
+    }
+
     pub fn avg_ram(&self) -> u64 {
         let hist = self.ram_history.lock().unwrap();
         if hist.is_empty() { return 0; }
@@ -117,6 +157,56 @@ impl Metrics {
         *packets.get(&module).unwrap_or(&0)
     }
 
+    /// Profondeur actuelle de la file d'un module dans le transmitter
+    pub fn set_queue_depth(&self, module: ModuleType, depth: usize) {
+        self.queue_depth.lock().unwrap().insert(module, depth);
+    }
+
+    pub fn get_queue_depth(&self, module: ModuleType) -> usize {
+        *self.queue_depth.lock().unwrap().get(&module).unwrap_or(&0)
+    }
+
+    /// Signale une activité du module (ex: événement input)
+    pub fn mark_activity(&self, module: ModuleType) {
+        self.last_activity.lock().unwrap().insert(module, Instant::now());
+    }
+
+    /// Temps écoulé depuis la dernière activité du module (None si jamais)
+    pub fn since_last_activity(&self, module: ModuleType) -> Option<Duration> {
+        self.last_activity.lock().unwrap().get(&module).map(|t| t.elapsed())
+    }
+
+    /// Comptabilise des éléments perdus par le buffer d'un module
+    pub fn add_dropped(&self, module: ModuleType, count: u64) {
+        if count == 0 { return; }
+        *self.dropped.lock().unwrap().entry(module).or_insert(0) += count;
+    }
+
+    pub fn get_dropped(&self, module: ModuleType) -> u64 {
+        *self.dropped.lock().unwrap().get(&module).unwrap_or(&0)
+    }
+
+    /// Enregistre les niveaux d'une frame audio ; `clipped` s'ajoute au total de la piste
+    pub fn record_audio_levels(&self, module: ModuleType, levels: AudioLevels) {
+        let mut all = self.audio_levels.lock().unwrap();
+        let clipped = all.get(&module).map_or(0, |l| l.clipped) + levels.clipped;
+        all.insert(module, AudioLevels { clipped, ..levels });
+    }
+
+    /// Derniers niveaux de la piste (None si aucune frame mesurée)
+    pub fn audio_levels(&self, module: ModuleType) -> Option<AudioLevels> {
+        self.audio_levels.lock().unwrap().get(&module).cloned()
+    }
+
+    /// Comptabilise une réouverture du flux sur un autre périphérique
+    pub fn add_device_switch(&self, module: ModuleType) {
+        *self.device_switches.lock().unwrap().entry(module).or_insert(0) += 1;
+    }
+
+    pub fn get_device_switches(&self, module: ModuleType) -> u64 {
+        *self.device_switches.lock().unwrap().get(&module).unwrap_or(&0)
+    }
+
     /// Met à jour la latence du ping
     pub fn add_ping_latency(&self, latency: Duration) {
         let now = Instant::now();
@@ -140,11 +230,19 @@ impl Metrics {
             avg_ram_mb: self.avg_ram(),
             avg_fps_screen: self.avg_fps(ModuleType::Screen),
             avg_fps_audio: self.avg_fps(ModuleType::Audio),
+            avg_fps_audio_loopback: self.avg_fps(ModuleType::AudioLoopback),
             avg_fps_input: self.avg_fps(ModuleType::Input),
             packets_screen: self.get_packets(ModuleType::Screen),
             packets_audio: self.get_packets(ModuleType::Audio),
             packets_input: self.get_packets(ModuleType::Input),
             avg_ping_ms: self.avg_ping_latency().map(|d| d.as_millis() as u64),
+            dropped_screen: self.get_dropped(ModuleType::Screen),
+            dropped_audio: self.get_dropped(ModuleType::Audio),
+            dropped_audio_loopback: self.get_dropped(ModuleType::AudioLoopback),
+            dropped_input: self.get_dropped(ModuleType::Input),
+            levels_audio: self.audio_levels(ModuleType::Audio),
+            levels_audio_loopback: self.audio_levels(ModuleType::AudioLoopback),
+            device_switches_audio: self.get_device_switches(ModuleType::Audio),
         }
     }
 }
@@ -155,9 +253,17 @@ pub struct MetricsSummary {
     pub avg_ram_mb: u64,
     pub avg_fps_screen: u32,
     pub avg_fps_audio: u32,
+    pub avg_fps_audio_loopback: u32,
     pub avg_fps_input: u32,
     pub packets_screen: u64,
     pub packets_audio: u64,
     pub packets_input: u64,
     pub avg_ping_ms: Option<u64>,
+    pub dropped_screen: u64,
+    pub dropped_audio: u64,
+    pub dropped_audio_loopback: u64,
+    pub dropped_input: u64,
+    pub levels_audio: Option<AudioLevels>,
+    pub levels_audio_loopback: Option<AudioLevels>,
+    pub device_switches_audio: u64,
 }
@@ -112,6 +112,11 @@ impl Transmitter {
         
         thread::spawn(move || {
             while *running.lock().unwrap() {
+                // Profondeur des files (utilisée par la régulation du FPS écran)
+                metrics.set_queue_depth(ModuleType::Screen, screen_queue.len());
+                metrics.set_queue_depth(ModuleType::Audio, audio_queue.len());
+                metrics.set_queue_depth(ModuleType::Input, input_queue.len());
+
                 // Traiter chaque queue avec batching
                 Self::process_queue_batched(&screen_queue, &ethernet, &bluetooth, &metrics, ModuleType::Screen, &packets_sent, max_size, batch_size, batch_timeout);
                 Self::process_queue_batched(&audio_queue, &ethernet, &bluetooth, &metrics, ModuleType::Audio, &packets_sent, max_size, batch_size, batch_timeout);
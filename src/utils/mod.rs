@@ -4,5 +4,5 @@ pub mod timer;
 pub mod thread_pool;
 pub mod queue;
 
-pub use queue::SharedQueue;
+pub use queue::{BoundedQueue, DropPolicy, SharedQueue};
 pub use thread_pool::ThreadPool;
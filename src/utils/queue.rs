@@ -1,7 +1,9 @@
 // visualisation_module/src/utils/queue.rs
 
 use crossbeam::queue::SegQueue;
-use std::sync::Arc;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
 pub struct SharedQueue<T> {
     inner: Arc<SegQueue<T>>,
@@ -42,3 +44,130 @@ impl<T> SharedQueue<T> {
         self.inner.len()
     }
 }
+
+/// Politique appliquée quand une file bornée est pleine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// Jette l'élément le plus ancien pour faire de la place
+    DropOldest,
+    /// Refuse le nouvel élément
+    DropNewest,
+    /// Fusionne le nouvel élément avec un élément fusionnable déjà en file
+    /// (ex: mouvements souris), sinon jette le plus ancien
+    Coalesce,
+}
+
+impl DropPolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "oldest" | "drop_oldest" => Some(DropPolicy::DropOldest),
+            "newest" | "drop_newest" => Some(DropPolicy::DropNewest),
+            "coalesce" => Some(DropPolicy::Coalesce),
+            _ => None,
+        }
+    }
+
+    /// Lit une politique de la config, `fallback` si le nom est inconnu
+    pub fn parse_or(name: &str, fallback: DropPolicy) -> Self {
+        Self::from_name(name).unwrap_or_else(|| {
+            eprintln!("[queue] Unknown drop policy '{}', using {:?}", name, fallback);
+            fallback
+        })
+    }
+}
+
+/// Capacité d'une file : valeur explicite, sinon `per_gb` éléments par Go de RAM
+/// bornés à [min, max]
+pub fn capacity_for_ram(explicit: usize, ram_gb: u32, per_gb: usize, min: usize, max: usize) -> usize {
+    if explicit > 0 {
+        return explicit;
+    }
+    (ram_gb as usize).saturating_mul(per_gb).clamp(min, max)
+}
+
+/// File bornée multi-producteurs avec politique de rejet et compteur de pertes
+pub struct BoundedQueue<T> {
+    items: Mutex<VecDeque<T>>,
+    capacity: usize,
+    policy: DropPolicy,
+    /// Vrai si deux éléments peuvent être fusionnés (le plus récent remplace l'ancien)
+    mergeable: Option<fn(&T, &T) -> bool>,
+    dropped: AtomicU64,
+}
+
+impl<T> BoundedQueue<T> {
+    pub fn new(capacity: usize, policy: DropPolicy) -> Self {
+        Self {
+            items: Mutex::new(VecDeque::new()),
+            capacity: capacity.max(1),
+            policy,
+            mergeable: None,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// File avec fusion : utilisée par la politique `Coalesce`
+    pub fn with_merge(capacity: usize, policy: DropPolicy, mergeable: fn(&T, &T) -> bool) -> Self {
+        Self { mergeable: Some(mergeable), ..Self::new(capacity, policy) }
+    }
+
+    /// Ajoute un élément ; renvoie le nombre d'éléments perdus (0 ou 1)
+    pub fn push(&self, item: T) -> u64 {
+        let mut items = self.items.lock().unwrap();
+        if items.len() < self.capacity {
+            items.push_back(item);
+            return 0;
+        }
+
+        match self.policy {
+            DropPolicy::DropNewest => {}
+            DropPolicy::DropOldest => {
+                items.pop_front();
+                items.push_back(item);
+            }
+            DropPolicy::Coalesce => {
+                let merge = self.mergeable.unwrap_or(|_, _| false);
+                if items.back().is_some_and(|last| merge(last, &item)) {
+                    // Le plus récent remplace le dernier en file
+                    *items.back_mut().unwrap() = item;
+                } else {
+                    // Sinon on sacrifie le plus ancien élément fusionnable, à défaut le plus ancien
+                    let victim = items.iter().position(|queued| merge(queued, &item)).unwrap_or(0);
+                    items.remove(victim);
+                    items.push_back(item);
+                }
+            }
+        }
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+        1
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        self.items.lock().unwrap().pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.lock().unwrap().is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn policy(&self) -> DropPolicy {
+        self.policy
+    }
+
+    /// Total des éléments perdus depuis la création
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn clear(&self) {
+        self.items.lock().unwrap().clear();
+    }
+}
@@ -1,6 +1,12 @@
 #[cfg(test)]
 mod tests {
-    use visualisation_module::{Config, Metrics, ModuleType};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use visualisation_module::{AudioCapture, Config, Metrics, ModuleType};
+    use visualisation_module::capture::{
+        AudioCodec, AudioEncoder, AudioFrame, AudioMixer, MixMode, TRACK_MIC, TRACK_SYSTEM, AudioFormat, MockAudioBackend,
+        Waveform, FrameSource, SyntheticSource, CaptureClock, CAPTURE_CLOCK, frames_to_us, pts_now,
+    };
 
     #[test]
     fn test_config_loading() {
@@ -47,4 +53,76 @@ mod tests {
         let summary = metrics.get_summary();
         assert_eq!(summary.packets_screen, 100);
     }
+
+    #[test]
+    fn test_bounded_queue_policies() {
+        use visualisation_module::utils::{BoundedQueue, DropPolicy};
+
+        let oldest = BoundedQueue::new(2, DropPolicy::DropOldest);
+        assert_eq!(oldest.push(1) + oldest.push(2) + oldest.push(3), 1);
+        assert_eq!((oldest.pop(), oldest.pop(), oldest.pop()), (Some(2), Some(3), None));
+        assert_eq!(oldest.dropped(), 1);
+
+        let newest = BoundedQueue::new(2, DropPolicy::DropNewest);
+        for i in 1..=4 {
+            newest.push(i);
+        }
+        assert_eq!((newest.pop(), newest.pop()), (Some(1), Some(2)));
+        assert_eq!(newest.dropped(), 2);
+
+        // Négatifs = mouvements fusionnables, positifs = événements à garder
+        let coalesce = BoundedQueue::with_merge(3, DropPolicy::Coalesce, |a: &i32, b: &i32| *a < 0 && *b < 0);
+        for event in [1, -1, 2, -2, -3, 3] {
+            coalesce.push(event);
+        }
+        let drained: Vec<i32> = std::iter::from_fn(|| coalesce.pop()).collect();
+        assert_eq!(drained, vec![2, -3, 3]);
+        assert_eq!(coalesce.dropped(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_capture_clock_stamps_screen_audio_and_packets() {
+        let clock = CaptureClock::new();
+        let (a, b) = (clock.now(), clock.now());
+        assert!(b >= a);
+        assert_eq!(clock.unix_millis(5_000), (clock.anchor_unix_us() + 5_000) / 1000);
+
+        // Écran : heure murale dérivée du PTS via l'ancre commune
+        let anchor_ms = CAPTURE_CLOCK.anchor_unix_us() / 1000;
+        let before = pts_now();
+        let screen = SyntheticSource::new(0, 16, 16).next_frame().unwrap().unwrap();
+        assert!(screen.pts >= before && screen.pts <= pts_now());
+        assert!((screen.timestamp - screen.pts / 1000).abs_diff(anchor_ms) <= 1);
+
+        // Audio : le PTS avance exactement au rythme des échantillons
+        let mut file = Config::default().file;
+        file.ram_gb = 4;
+        let format = AudioFormat { sample_rate: 48000, channels: 2 };
+        let backend = MockAudioBackend::new(format, Waveform::Samples(vec![0.1])).with_chunk_frames(480);
+        let mut audio = AudioCapture::with_config(&file);
+        audio.set_backend(Arc::new(backend));
+        audio.start().await;
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        audio.stop().await;
+        let frames: Vec<AudioFrame> = std::iter::from_fn(|| audio.get_frame()).collect();
+        assert!(frames.len() >= 3);
+        let step = frames_to_us(1024, 48000);
+        assert_eq!(frames[1].pts - frames[0].pts, step);
+        assert_eq!(frames[2].pts - frames[0].pts, frames_to_us(2048, 48000));
+        assert!(frames[2].pts <= pts_now());
+        assert!((frames[0].timestamp - frames[0].pts / 1000).abs_diff(anchor_ms) <= 1);
+
+        // Le PTS suit la frame jusque dans le paquet audio
+        let mixed = AudioMixer::new(MixMode::Separate).mix(&[(TRACK_MIC, &frames[1])]);
+        assert_eq!(mixed[0].pts, frames[1].pts);
+        let packet = AudioEncoder::new(AudioCodec::Lossless, 128).encode_packet(&mixed[0]).unwrap();
+        assert_eq!(AudioEncoder::decode_packet(&packet).unwrap().pts, frames[1].pts);
+        let silence = AudioEncoder::new(AudioCodec::Lossless, 128).encode_silence(&mixed[0], 0.0);
+        assert_eq!(AudioEncoder::decode_packet(&silence).unwrap().pts, frames[1].pts);
+
+        // Mix de deux sources : PTS de la plus ancienne
+        let later = AudioFrame { pts: frames[0].pts + 500, ..frames[0].clone() };
+        let mixed = AudioMixer::new(MixMode::Mixed).mix(&[(TRACK_MIC, &later), (TRACK_SYSTEM, &frames[0])]);
+        assert_eq!(mixed[0].pts, frames[0].pts);
+    }
 }
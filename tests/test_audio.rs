@@ -4,7 +4,7 @@ use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
-use visualisation_module::capture::{AudioCapture, Ping, Transmitter};
+use visualisation_module::{AudioCapture, Ping, Transmitter};
 
 /// Test audio réel système pendant 5 minutes
 /// - Idle strict (aucun pong)
@@ -31,7 +31,7 @@ pub fn test_audio_idle_5min(
             buffer_count += 1;
 
             // Pousser le buffer réel vers le transmitter
-            transmitter.push_audio(buffer);
+            transmitter.push_audio(buffer.samples.iter().flat_map(|s| s.to_le_bytes()).collect());
 
             // Légère pause pour réguler CPU si nécessaire
             thread::sleep(Duration::from_millis(5));
@@ -46,3 +46,871 @@ pub fn test_audio_idle_5min(
     // Vérifications strictes
     assert!(buffer_count > 1000, "Buffers audio insuffisants pendant 5 minutes");
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use visualisation_module::{AudioCapture, AudioLevels, Config, Metrics, ModuleType, ScreenCapture};
+    use visualisation_module::capture::{
+        AudioCodec, AudioEncoder, AudioFrame, AudioMixer, MixMode, MixedFrame, TrackSettings, TRACK_MIC, TRACK_MIXED,
+        TRACK_SYSTEM, convert_channels, AudioFilter, AudioMeter, Compressor, FilterChain, FilterKind, HighPass, NoiseGate, Normalizer,
+        Resampler, SILENCE_DB, VadSettings, VoiceDetector, AudioFormat, MockAudioBackend, Waveform, AudioDevice, DeviceEventKind,
+        DeviceSelector, MockDevices, is_recording_name, repair_wav, RecordMode, WavRecorder, CAPTURE_CLOCK, frames_to_us, pts_now,
+        us_to_frames,
+    };
+
+    #[test]
+    fn test_mock_audio_waveform_is_deterministic() {
+        let format = AudioFormat { sample_rate: 8000, channels: 2 };
+        let sine = MockAudioBackend::new(format, Waveform::Sine { frequency: 1000.0, amplitude: 0.5 });
+
+        // 8 échantillons par période : quart de période = crête, canaux identiques
+        let chunk = sine.render(0, 8);
+        assert_eq!(chunk.len(), 16);
+        assert!((chunk[4] - 0.5).abs() < 1e-6 && chunk[4] == chunk[5]);
+        assert_eq!(sine.render(8, 8), chunk);
+
+        let samples = MockAudioBackend::new(format, Waveform::Samples(vec![0.1, -0.1, 0.2, -0.2]));
+        assert_eq!(samples.render(1, 2), vec![0.2, -0.2, 0.1, -0.1]);
+    }
+
+    #[tokio::test]
+    async fn test_audio_capture_mock_backend_frames() {
+        let metrics = Metrics::new();
+        let mut file = Config::default().file;
+        file.ram_gb = 4; // frames de 1024 échantillons par canal
+        let format = AudioFormat { sample_rate: 48000, channels: 2 };
+        let ramp: Vec<f32> = (0..4096).map(|i| (i as f32 / 4096.0) * 2.0 - 1.0).collect();
+        let backend = MockAudioBackend::new(format, Waveform::Samples(ramp.clone())).with_chunk_frames(300);
+
+        let mut audio = AudioCapture::with_config(&file);
+        audio.set_backend(Arc::new(backend));
+        audio.attach_metrics(Arc::clone(&metrics));
+        audio.start().await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(audio.is_running().await);
+        assert_eq!(audio.stream_format(), Some(format));
+        audio.stop().await;
+
+        // Blocs de 300 frames regroupés en frames de 1024 x 2 canaux, sans perte ni décalage
+        let first = audio.get_frame().expect("aucune frame audio");
+        let second = audio.get_frame().expect("une seule frame audio");
+        assert_eq!(first.samples.len(), 2048);
+        assert_eq!((first.sample_rate, first.channels, first.frames()), (48000, 2, 1024));
+        assert_eq!(first.samples, ramp[..2048]);
+        assert_eq!(second.samples, ramp[2048..]);
+    }
+
+    #[test]
+    fn test_convert_channels_layouts() {
+        assert_eq!(convert_channels(&[0.5, -0.25], 1, 2), vec![0.5, 0.5, -0.25, -0.25]);
+        assert_eq!(convert_channels(&[0.5, -0.5, 0.2, 0.4], 2, 1), vec![0.0, 0.3]);
+
+        // 5.1 -> stéréo : centre réparti, LFE ignoré, jamais au-delà de [-1, 1]
+        let surround = [1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+        let stereo = convert_channels(&surround, 6, 2);
+        assert_eq!(stereo.len(), 2);
+        assert!((stereo[0] - 1.0).abs() < 1e-6 && stereo[0] == stereo[1]);
+        let center_only = convert_channels(&[0.0, 0.0, 1.0, 0.0, 0.0, 0.0], 6, 2);
+        assert!(center_only[0] > 0.0 && center_only[0] < 1.0);
+        let lfe_only = convert_channels(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0], 6, 2);
+        assert_eq!(lfe_only, vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_resampler_rate_and_continuity() {
+        // 48 kHz -> 16 kHz : un tiers des frames
+        let input: Vec<f32> = (0..4800).map(|i| (i as f32 * 0.01).sin()).collect();
+        let mut resampler = Resampler::new(48000, 16000, 1);
+        let one_shot = resampler.process(&input);
+        assert!((one_shot.len() as i64 - 1600).abs() <= 1);
+
+        // Découpé en blocs irréguliers, le résultat est identique
+        let mut chunked = Resampler::new(48000, 16000, 1);
+        let mut out = Vec::new();
+        for block in input.chunks(333) {
+            out.extend(chunked.process(block));
+        }
+        assert_eq!(out.len(), one_shot.len());
+        assert!(out.iter().zip(&one_shot).all(|(a, b)| (a - b).abs() < 1e-6));
+
+        // Suréchantillonnage stéréo : canaux interpolés séparément
+        let mut up = Resampler::new(22050, 44100, 2);
+        let out = up.process(&[0.0, 1.0, 1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(out.len() % 2, 0);
+        assert!(out.chunks(2).all(|lr| (lr[0] + lr[1] - 1.0).abs() < 1e-6));
+        assert!(Resampler::new(48000, 48000, 2).is_passthrough());
+    }
+
+    #[tokio::test]
+    async fn test_audio_capture_converts_to_configured_format() {
+        let mut file = Config::default().file;
+        file.ram_gb = 4;
+        file.audio_sample_rate = 48000;
+        file.audio_channels = 1;
+        // Périphérique stéréo à 44.1 kHz, canaux opposés : le mono est nul
+        let format = AudioFormat { sample_rate: 44100, channels: 2 };
+        let backend = MockAudioBackend::new(format, Waveform::Samples(vec![0.5, -0.5])).with_chunk_frames(441);
+
+        let mut audio = AudioCapture::with_config(&file);
+        audio.set_backend(Arc::new(backend));
+        audio.start().await;
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        audio.stop().await;
+
+        assert_eq!(audio.stream_format(), Some(format));
+        assert_eq!(audio.output_format(), AudioFormat { sample_rate: 48000, channels: 1 });
+        let frame = audio.get_frame().expect("aucune frame audio");
+        assert_eq!((frame.sample_rate, frame.channels, frame.samples.len()), (48000, 1, 1024));
+        assert!(frame.samples.iter().all(|s| s.abs() < 1e-6));
+    }
+
+    #[tokio::test]
+    async fn test_audio_loopback_separate_track() {
+        let metrics = Metrics::new();
+        let mut file = Config::default().file;
+        file.ram_gb = 4;
+        file.audio_loopback_enabled = true;
+        let format = AudioFormat { sample_rate: 48000, channels: 2 };
+        let backend = MockAudioBackend::new(format, Waveform::Samples(vec![0.25]))
+            .with_loopback(Waveform::Samples(vec![-0.5]))
+            .with_chunk_frames(512);
+
+        let mut audio = AudioCapture::with_config(&file);
+        audio.set_backend(Arc::new(backend));
+        audio.attach_metrics(Arc::clone(&metrics));
+        audio.start().await;
+        tokio::time::sleep(Duration::from_millis(1200)).await;
+        audio.stop().await;
+
+        assert_eq!(audio.loopback_format(), Some(format));
+        let mic = audio.get_frame().expect("aucune frame micro");
+        let system = audio.get_loopback_frame().expect("aucune frame loopback");
+        assert!(mic.samples.iter().all(|s| *s == 0.25));
+        assert!(system.samples.iter().all(|s| *s == -0.5));
+        assert!(metrics.avg_fps(ModuleType::AudioLoopback) > 0);
+        assert!(metrics.avg_fps(ModuleType::Audio) > 0);
+
+        // Sans source loopback, seul le micro est capturé
+        let mut mic_only = AudioCapture::with_config(&file);
+        mic_only.set_backend(Arc::new(MockAudioBackend::new(format, Waveform::Samples(vec![0.25]))));
+        mic_only.start().await;
+        assert!(mic_only.is_running().await);
+        assert_eq!(mic_only.loopback_format(), None);
+        mic_only.stop().await;
+    }
+
+    #[test]
+    fn test_audio_mixer_gain_mute_pan() {
+        let mixer = AudioMixer::new(MixMode::Separate);
+        mixer.set_track(TRACK_MIC, TrackSettings { gain: 0.5, muted: false, pan: 1.0 });
+        mixer.set_muted(TRACK_SYSTEM, true);
+
+        let frame = |samples: Vec<f32>, channels| AudioFrame { samples, sample_rate: 48000, channels, timestamp: 0, pts: 0 };
+        let mic = frame(vec![0.8, 0.4], 1); // mono, 2 frames
+        let system = frame(vec![0.3, -0.3, 0.3, -0.3], 2); // stéréo, 2 frames
+        let frames = mixer.mix(&[(TRACK_MIC, &mic), (TRACK_SYSTEM, &system)]);
+
+        assert_eq!(frames.len(), 2);
+        // Pan à droite : gauche coupée, droite au gain 0.5
+        assert_eq!(
+            frames[0],
+            MixedFrame { track_id: TRACK_MIC, sample_rate: 48000, channels: 2, pts: 0, samples: vec![0.0, 0.4, 0.0, 0.2] }
+        );
+        assert_eq!(frames[1].track_id, TRACK_SYSTEM);
+        assert!(frames[1].samples.iter().all(|s| *s == 0.0));
+
+        // Mode mixé : somme des sources bornée à [-1, 1] sur une seule piste
+        mixer.set_mode(MixMode::Mixed);
+        mixer.set_track(TRACK_MIC, TrackSettings::default());
+        mixer.set_muted(TRACK_SYSTEM, false);
+        mixer.set_gain(TRACK_SYSTEM, 4.0);
+        let frames = mixer.mix(&[(TRACK_MIC, &mic), (TRACK_SYSTEM, &system)]);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].track_id, TRACK_MIXED);
+        let expected = [1.0, -0.4, 1.0, -0.8];
+        assert!(frames[0].samples.iter().zip(expected).all(|(s, e)| (s - e).abs() < 1e-6));
+    }
+
+    #[tokio::test]
+    async fn test_audio_mixer_separate_tracks_from_capture() {
+        let mut file = Config::default().file;
+        file.ram_gb = 4;
+        file.audio_loopback_enabled = true;
+        file.audio_mix_mode = "separate".to_string();
+        file.audio_system_gain = 0.5;
+        let format = AudioFormat { sample_rate: 48000, channels: 2 };
+        let backend = MockAudioBackend::new(format, Waveform::Samples(vec![0.25]))
+            .with_loopback(Waveform::Samples(vec![0.5]))
+            .with_chunk_frames(1024);
+
+        let mut audio = AudioCapture::with_config(&file);
+        audio.set_backend(Arc::new(backend));
+        audio.start().await;
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        audio.stop().await;
+
+        let mixer = AudioMixer::from_config(&file);
+        let frames = mixer.pull(&audio);
+        let tracks: Vec<u8> = frames.iter().map(|f| f.track_id).collect();
+        assert_eq!(tracks, vec![TRACK_MIC, TRACK_SYSTEM]);
+        assert!(frames[0].samples.iter().all(|s| *s == 0.25));
+        assert!(frames[1].samples.iter().all(|s| *s == 0.25));
+
+        // Piste, canaux et fréquence sont recopiés dans l'en-tête du paquet
+        let packet = AudioEncoder::new(AudioCodec::Gzip, 128).encode_packet(&frames[1]).expect("encodage audio");
+        assert_eq!(&packet[..8], &[0x06, TRACK_SYSTEM, 2, 0x80, 0xBB, 0x00, 0x00, AudioCodec::Gzip as u8]);
+        let decoded = visualisation_module::Preprocessor::decode_audio(&packet).expect("paquet audio invalide");
+        assert_eq!((decoded.track_id, decoded.sample_rate, decoded.channels), (TRACK_SYSTEM, 48000, 2));
+        assert_eq!(decoded.samples.len(), frames[1].samples.len());
+        assert!(decoded.samples.iter().all(|s| (s - 0.25).abs() < 1e-3));
+        assert!(visualisation_module::Preprocessor::decode_audio(&packet[..5]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_audio_mixer_aligns_pts_and_skips_stalled_source() {
+        // Alignement : la source système démarre 2 échantillons après le micro
+        let mixer = AudioMixer::new(MixMode::Mixed);
+        let frame = |value: f32, pts| AudioFrame { samples: vec![value; 8], sample_rate: 48000, channels: 2, timestamp: 0, pts };
+        let late = frames_to_us(2, 48000);
+        assert_eq!(us_to_frames(late, 48000), 2);
+        let frames = mixer.mix(&[(TRACK_MIC, &frame(0.25, 1000)), (TRACK_SYSTEM, &frame(0.5, 1000 + late))]);
+        assert_eq!(frames[0].pts, 1000);
+        let expected = [0.25, 0.25, 0.25, 0.25, 0.75, 0.75, 0.75, 0.75, 0.5, 0.5, 0.5, 0.5];
+        assert_eq!(frames[0].samples, expected);
+
+        // Micro absent : après l'attente, le son du système part seul
+        let mut file = Config::default().file;
+        file.ram_gb = 4;
+        file.audio_loopback_enabled = true;
+        file.audio_mix_wait_ms = 30;
+        let format = AudioFormat { sample_rate: 48000, channels: 2 };
+        let backend = MockAudioBackend::new(format, Waveform::Samples(vec![0.25]))
+            .with_devices(MockDevices::new(&[]))
+            .with_loopback(Waveform::Samples(vec![0.5]))
+            .with_chunk_frames(1024);
+        let mut audio = AudioCapture::with_config(&file);
+        audio.set_backend(Arc::new(backend));
+        audio.start().await;
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        audio.stop().await;
+        assert!(audio.loopback_buffer_len() >= 2);
+
+        let mixer = AudioMixer::from_config(&file);
+        assert!(mixer.pull(&audio).is_empty());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut pts = Vec::new();
+        while audio.loopback_buffer_len() > 0 {
+            let frames = mixer.pull(&audio);
+            assert_eq!(frames.len(), 1);
+            assert_eq!(frames[0].track_id, TRACK_MIXED);
+            assert!(frames[0].samples.iter().all(|s| *s == 0.5));
+            pts.push(frames[0].pts);
+        }
+        // La frame retenue pendant l'attente part avec la suivante, sans trou
+        assert!(pts.windows(2).all(|w| w[0] < w[1]));
+        assert!(mixer.pull(&audio).is_empty());
+    }
+
+    #[test]
+    fn test_audio_meter_levels_and_spectrum() {
+        let tone = |frequency: f32, amplitude: f32| AudioFrame {
+            samples: (0..2048)
+                .map(|i| amplitude * (i as f32 / 48000.0 * frequency * std::f32::consts::TAU).sin())
+                .collect(),
+            sample_rate: 48000,
+            channels: 1,
+            timestamp: 0,
+            pts: 0,
+        };
+
+        // Sinus à -6 dBFS : RMS 3 dB sous la crête, rien d'écrêté, pas de spectre par défaut
+        let levels = AudioMeter::new(0).measure(&tone(1000.0, 0.5));
+        assert!((levels.peak_db + 6.02).abs() < 0.1, "peak {}", levels.peak_db);
+        assert!((levels.rms_db + 9.03).abs() < 0.1, "rms {}", levels.rms_db);
+        assert_eq!(levels.clipped, 0);
+        assert!(levels.spectrum_db.is_empty());
+
+        // La bande contenant 1 kHz domine le spectre
+        let meter = AudioMeter::new(10);
+        let spectrum = meter.measure(&tone(1000.0, 0.5)).spectrum_db;
+        assert_eq!(spectrum.len(), 10);
+        let loudest = spectrum.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap().0;
+        let band_of = |hz: f32| ((hz / 20.0).ln() / (24000.0f32 / 20.0).ln() * 10.0) as usize;
+        assert_eq!(loudest, band_of(1000.0));
+        assert!((spectrum[loudest] + 6.0).abs() < 3.0, "band level {}", spectrum[loudest]);
+
+        // Silence et écrêtage
+        let silence = meter.measure(&tone(1000.0, 0.0));
+        assert_eq!((silence.rms_db, silence.peak_db), (SILENCE_DB, SILENCE_DB));
+        assert!(silence.spectrum_db.iter().all(|b| *b == SILENCE_DB));
+        let mut loud = tone(1000.0, 1.5);
+        loud.samples.iter_mut().for_each(|s| *s = s.clamp(-1.0, 1.0));
+        let clipped = AudioMeter::new(0).measure(&loud);
+        assert!(clipped.clipped > 0 && clipped.peak_db > -0.01);
+    }
+
+    #[tokio::test]
+    async fn test_audio_levels_reported_in_metrics() {
+        let metrics = Metrics::new();
+        let mut file = Config::default().file;
+        file.ram_gb = 4;
+        file.audio_loopback_enabled = true;
+        file.audio_spectrum_bands = 8;
+        let format = AudioFormat { sample_rate: 48000, channels: 2 };
+        // Micro muet (micro débranché), son du système écrêté
+        let backend = MockAudioBackend::new(format, Waveform::Samples(vec![0.0]))
+            .with_loopback(Waveform::Samples(vec![1.0, -1.0]))
+            .with_chunk_frames(1024);
+
+        let mut audio = AudioCapture::with_config(&file);
+        audio.set_backend(Arc::new(backend));
+        audio.attach_metrics(Arc::clone(&metrics));
+        audio.start().await;
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        audio.stop().await;
+
+        let summary = metrics.get_summary();
+        let mic = summary.levels_audio.expect("niveaux micro absents");
+        assert_eq!(mic.rms_db, SILENCE_DB);
+        assert_eq!(mic.spectrum_db.len(), 8);
+        let system = summary.levels_audio_loopback.expect("niveaux loopback absents");
+        assert!(system.peak_db > -0.01);
+        // Le compteur d'écrêtage est cumulé sur toutes les frames
+        let frames = audio.loopback_buffer_len() as u64;
+        assert!(frames >= 1);
+        assert_eq!(system.clipped, frames * 2048);
+
+        metrics.record_audio_levels(ModuleType::Audio, AudioLevels { clipped: 3, ..AudioLevels::default() });
+        metrics.record_audio_levels(ModuleType::Audio, AudioLevels { clipped: 2, ..AudioLevels::default() });
+        assert_eq!(metrics.audio_levels(ModuleType::Audio).unwrap().clipped, 5);
+    }
+
+    /// Sinus mono à 48 kHz
+    fn sine(frequency: f32, amplitude: f32, frames: usize) -> Vec<f32> {
+        (0..frames).map(|i| amplitude * (i as f32 / 48000.0 * frequency * std::f32::consts::TAU).sin()).collect()
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0f32, |m, s| m.max(s.abs()))
+    }
+
+    #[test]
+    fn test_dsp_filters() {
+        // Passe-haut 80 Hz : 20 Hz fortement atténué, 1 kHz intact (régime établi)
+        let mut hpf = HighPass::new(80.0);
+        let mut low = sine(20.0, 0.5, 48000);
+        hpf.process(&mut low, 1, 48000);
+        assert!(peak(&low[24000..]) < 0.05, "20 Hz -> {}", peak(&low[24000..]));
+        let mut hpf = HighPass::new(80.0);
+        let mut high = sine(1000.0, 0.5, 9600);
+        hpf.process(&mut high, 1, 48000);
+        assert!((peak(&high[4800..]) - 0.5).abs() < 0.01);
+
+        // Porte : le bruit sous le seuil est coupé, la voix passe
+        let mut gate = NoiseGate::new(-40.0, 1.0, 20.0);
+        let mut hiss = sine(3000.0, 0.003, 4800);
+        gate.process(&mut hiss, 1, 48000);
+        assert!(peak(&hiss[2400..]) < 1e-4);
+        let mut voice = sine(300.0, 0.3, 4800);
+        gate.process(&mut voice, 1, 48000);
+        assert!(peak(&voice[2400..]) > 0.29);
+
+        // Compresseur 4:1 au-dessus de -20 dBFS : une crête à -6 dBFS ressort vers -16.5
+        let mut comp = Compressor::new(-20.0, 4.0, 1.0, 50.0, 0.0);
+        let mut loud = sine(500.0, 0.5, 9600);
+        comp.process(&mut loud, 1, 48000);
+        let out_db = 20.0 * peak(&loud[4800..]).log10();
+        assert!((out_db + 16.5).abs() < 1.5, "compressed peak {} dB", out_db);
+
+        // Normalisation : une voix faible remonte vers -20 dBFS RMS en quelques frames
+        let mut norm = Normalizer::new(-20.0, 12.0);
+        let mut last = Vec::new();
+        for _ in 0..40 {
+            last = sine(300.0, 0.05, 1024); // -29 dBFS RMS
+            norm.process(&mut last, 1, 48000);
+        }
+        let rms = (last.iter().map(|s| s * s).sum::<f32>() / last.len() as f32).sqrt();
+        assert!((20.0 * rms.log10() + 20.0).abs() < 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_dsp_chain_from_config_with_runtime_bypass() {
+        let mut file = Config::default().file;
+        file.ram_gb = 4;
+        file.audio_dsp_chain = vec!["highpass".to_string(), "gate".to_string(), "bogus".to_string()];
+        file.audio_gate_threshold_db = -30.0;
+        let chain = FilterChain::from_config(&file);
+        assert_eq!(chain.kinds(), vec![FilterKind::HighPass, FilterKind::NoiseGate]);
+
+        // Micro sous le seuil de la porte : filtré ; son du système jamais filtré
+        let format = AudioFormat { sample_rate: 48000, channels: 2 };
+        let quiet: Vec<f32> = sine(1000.0, 0.01, 4800).into_iter().flat_map(|s| [s, s]).collect();
+        file.audio_loopback_enabled = true;
+        let backend = MockAudioBackend::new(format, Waveform::Samples(quiet.clone()))
+            .with_loopback(Waveform::Samples(quiet))
+            .with_chunk_frames(1024);
+        let mut audio = AudioCapture::with_config(&file);
+        audio.set_backend(Arc::new(backend));
+        assert!(!audio.set_filter_bypass(FilterKind::Compressor, true));
+        audio.start().await;
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        let mic = audio.get_frame().expect("aucune frame micro");
+        let system = audio.get_loopback_frame().expect("aucune frame loopback");
+        assert!(peak(&mic.samples[1024..]) < 1e-3);
+        assert!(peak(&system.samples) > 0.009);
+
+        // Porte contournée à chaud : le signal du micro repasse
+        assert!(audio.set_filter_bypass(FilterKind::NoiseGate, true));
+        audio.clear_buffer();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        audio.stop().await;
+        let mic = std::iter::from_fn(|| audio.get_frame()).last().expect("aucune frame micro après bypass");
+        assert!(peak(&mic.samples) > 0.009);
+    }
+
+    #[test]
+    fn test_device_selector_parse_and_resolve() {
+        assert_eq!(DeviceSelector::parse(""), DeviceSelector::Default);
+        assert_eq!(DeviceSelector::parse("Default"), DeviceSelector::Default);
+        assert_eq!(DeviceSelector::parse("#1"), DeviceSelector::Index(1));
+        assert_eq!(DeviceSelector::parse("2"), DeviceSelector::Index(2));
+        assert_eq!(DeviceSelector::parse("usb"), DeviceSelector::Name("usb".to_string()));
+
+        let devices = vec![
+            AudioDevice { index: 0, name: "Built-in".to_string(), is_default: true },
+            AudioDevice { index: 1, name: "USB Mic".to_string(), is_default: false },
+            AudioDevice { index: 2, name: "usb".to_string(), is_default: false },
+        ];
+        // Nom exact prioritaire, puis sous-chaîne sans casse
+        assert_eq!(DeviceSelector::parse("usb").resolve(&devices), Some("usb".to_string()));
+        assert_eq!(DeviceSelector::parse("USB m").resolve(&devices), Some("USB Mic".to_string()));
+        assert_eq!(DeviceSelector::parse("#1").resolve(&devices), Some("USB Mic".to_string()));
+        assert_eq!(DeviceSelector::parse("#7").resolve(&devices), None);
+        assert_eq!(DeviceSelector::parse("headset").resolve(&devices), None);
+        assert_eq!(DeviceSelector::Default.resolve(&devices), None);
+    }
+
+    #[tokio::test]
+    async fn test_audio_device_hotplug_fallback_and_reattach() {
+        let mut file = Config::default().file;
+        file.ram_gb = 4;
+        file.audio_input_device = "usb".to_string();
+        file.audio_device_poll_ms = 50;
+        let devices = MockDevices::new(&["Built-in", "USB Mic"]);
+        let backend = MockAudioBackend::new(AudioFormat { sample_rate: 48000, channels: 2 }, Waveform::Sine { frequency: 440.0, amplitude: 0.5 })
+            .with_devices(devices.clone());
+        let metrics = Metrics::new();
+        let mut audio = AudioCapture::with_config(&file);
+        audio.set_backend(Arc::new(backend));
+        audio.attach_metrics(Arc::clone(&metrics));
+
+        let names: Vec<String> = audio.list_devices().into_iter().map(|d| d.name).collect();
+        assert_eq!(names, vec!["Built-in".to_string(), "USB Mic".to_string()]);
+
+        assert_eq!(audio.get_sample_format(), None);
+        audio.start().await;
+        assert_eq!(audio.active_device(), Some("USB Mic".to_string()));
+        assert_eq!(audio.get_sample_format(), Some(cpal::SampleFormat::F32));
+        let opened = audio.get_device_event().expect("aucun événement d'ouverture");
+        assert_eq!((opened.kind, opened.device.as_str()), (DeviceEventKind::Opened, "USB Mic"));
+        // Horodaté sur l'horloge de capture, comme les frames
+        assert!(opened.pts <= pts_now());
+        assert_eq!(opened.timestamp, CAPTURE_CLOCK.unix_millis(opened.pts));
+
+        // Débranchement : bascule sur le périphérique par défaut
+        devices.unplug("USB Mic");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(audio.active_device(), None);
+        let lost = audio.get_device_event().expect("aucun événement de perte");
+        assert_eq!((lost.kind, lost.device.as_str()), (DeviceEventKind::Lost, "USB Mic"));
+        assert!(lost.pts > opened.pts);
+        assert_eq!(audio.get_device_event().map(|e| e.kind), Some(DeviceEventKind::Fallback));
+        assert_eq!(metrics.get_device_switches(ModuleType::Audio), 1);
+
+        // Rebranchement : retour sur le micro demandé, la capture continue
+        devices.plug("USB Mic");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(audio.active_device(), Some("USB Mic".to_string()));
+        let back = audio.get_device_event().expect("aucun événement de retour");
+        assert_eq!((back.kind, back.device.as_str()), (DeviceEventKind::Reattached, "USB Mic"));
+        assert_eq!(audio.get_device_event(), None);
+        assert_eq!(metrics.get_summary().device_switches_audio, 2);
+        audio.clear_buffer();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(audio.get_frame().is_some());
+        audio.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_audio_device_busy_keeps_fallback_stream() {
+        let mut file = Config::default().file;
+        file.ram_gb = 4;
+        file.audio_input_device = "USB Mic".to_string();
+        file.audio_device_poll_ms = 30;
+        let devices = MockDevices::new(&["Built-in", "USB Mic"]);
+        let backend = MockAudioBackend::new(AudioFormat { sample_rate: 48000, channels: 2 }, Waveform::Sine { frequency: 440.0, amplitude: 0.5 })
+            .with_devices(devices.clone());
+        let metrics = Metrics::new();
+        let mut audio = AudioCapture::with_config(&file);
+        audio.set_backend(Arc::new(backend));
+        audio.attach_metrics(Arc::clone(&metrics));
+
+        audio.start().await;
+        devices.unplug("USB Mic");
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(audio.active_device(), None);
+        assert_eq!(metrics.get_device_switches(ModuleType::Audio), 1);
+        while audio.get_device_event().is_some() {}
+
+        // Listé mais impossible à ouvrir : le flux par défaut reste en place, sans événement
+        devices.set_busy("USB Mic", true);
+        devices.plug("USB Mic");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(audio.active_device(), None);
+        assert_eq!(audio.get_device_event(), None);
+        assert_eq!(metrics.get_device_switches(ModuleType::Audio), 1);
+        audio.clear_buffer();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(audio.get_frame().is_some());
+
+        devices.set_busy("USB Mic", false);
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(audio.active_device(), Some("USB Mic".to_string()));
+        assert_eq!(audio.get_device_event().map(|e| e.kind), Some(DeviceEventKind::Reattached));
+        assert_eq!(metrics.get_device_switches(ModuleType::Audio), 2);
+        audio.stop().await;
+
+        // Sélecteur par défaut : la réouverture est un retour, pas un repli
+        let devices = MockDevices::new(&["Built-in", "USB Mic"]);
+        let backend = MockAudioBackend::new(AudioFormat { sample_rate: 48000, channels: 2 }, Waveform::Sine { frequency: 440.0, amplitude: 0.5 })
+            .with_devices(devices.clone());
+        file.audio_input_device = "default".to_string();
+        let mut audio = AudioCapture::with_config(&file);
+        audio.set_backend(Arc::new(backend));
+        audio.start().await;
+        assert_eq!(audio.get_device_event().map(|e| e.kind), Some(DeviceEventKind::Opened));
+        devices.unplug("Built-in");
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(audio.get_device_event().map(|e| e.kind), Some(DeviceEventKind::Lost));
+        assert_eq!(audio.get_device_event().map(|e| e.kind), Some(DeviceEventKind::Reattached));
+        assert_eq!(audio.get_device_event(), None);
+        audio.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_preprocessor_loop_records_wav() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut file = Config::default().file;
+        file.ram_gb = 4;
+        file.audio_backend = "mock".to_string();
+        file.audio_record_mode = "only".to_string();
+        file.audio_record_dir = dir.path().to_string_lossy().to_string();
+
+        let audio = Arc::new(AudioCapture::with_config(&file));
+        let preprocessor = Arc::new(visualisation_module::Preprocessor::with_config(
+            Arc::new(ScreenCapture::with_config(&file)),
+            Arc::clone(&audio),
+            Arc::new(visualisation_module::InputCapture::with_config(&file)),
+            &file,
+        ));
+        audio.start().await;
+        preprocessor.start();
+        assert!(preprocessor.is_running());
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        preprocessor.stop();
+        audio.stop().await;
+
+        // La boucle a vidé la capture dans le fichier WAV, finalisé à l'arrêt
+        assert!(!preprocessor.is_running());
+        let files = preprocessor.recorder().expect("enregistreur absent").files();
+        assert!(!files.is_empty());
+        let bytes = std::fs::read(&files[0]).unwrap();
+        let data_len = u32::from_le_bytes(bytes[40..44].try_into().unwrap()) as usize;
+        assert!(data_len > 0 && bytes.len() == 44 + data_len);
+    }
+
+    #[test]
+    fn test_wav_recorder_tracks_and_rotation() {
+        assert_eq!(RecordMode::from_name("alongside"), Some(RecordMode::Alongside));
+        assert!(!RecordMode::Only.transmits() && RecordMode::Only.records());
+        assert_eq!(RecordMode::from_name("sometimes"), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = WavRecorder::new(dir.path(), Some(Duration::from_secs(1))).unwrap();
+        let mut mic = music_frame(24000);
+        mic.track_id = TRACK_MIC;
+        let system = MixedFrame { track_id: TRACK_SYSTEM, sample_rate: 16000, channels: 1, pts: 0, samples: vec![0.25; 8000] };
+        for _ in 0..5 {
+            recorder.write(&mic).unwrap();
+        }
+        recorder.write(&system).unwrap();
+        recorder.finalize().unwrap();
+
+        // 5 x 0,5 s de micro, rotation à 1 s : 1 s + 1 s + 0,5 s
+        let files = recorder.files();
+        let mic_files: Vec<_> = files.iter().filter(|p| p.file_name().unwrap().to_str().unwrap().starts_with("mic_")).collect();
+        assert_eq!(mic_files.len(), 3);
+        let durations: Vec<u32> = mic_files.iter().map(|p| hound::WavReader::open(p).unwrap().duration()).collect();
+        assert_eq!(durations, vec![48000, 48000, 24000]);
+
+        let first = hound::WavReader::open(mic_files[0]).unwrap();
+        assert_eq!((first.spec().sample_rate, first.spec().channels, first.spec().bits_per_sample), (48000, 2, 16));
+        let samples: Vec<i16> = first.into_samples::<i16>().take(4).map(|s| s.unwrap()).collect();
+        let expected: Vec<i16> = mic.samples[..4].iter().map(|s| (s * i16::MAX as f32).round() as i16).collect();
+        assert_eq!(samples, expected);
+
+        let system_file = files.iter().find(|p| p.file_name().unwrap().to_str().unwrap().starts_with("system_")).unwrap();
+        let reader = hound::WavReader::open(system_file).unwrap();
+        assert_eq!((reader.spec().sample_rate, reader.spec().channels, reader.duration()), (16000, 1, 8000));
+    }
+
+    #[test]
+    fn test_wav_recording_survives_kill_and_is_repaired() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = WavRecorder::new(dir.path(), None).unwrap();
+        let frame = music_frame(4800);
+        recorder.write(&frame).unwrap();
+        recorder.write(&frame).unwrap();
+        let path = recorder.files()[0].clone();
+        // Processus tué : pas de finalisation, l'en-tête écrit après chaque frame suffit
+        std::mem::forget(recorder);
+        assert_eq!(hound::WavReader::open(&path).unwrap().duration(), 9600);
+        assert!(!repair_wav(&path).unwrap());
+
+        // Données écrites après le dernier en-tête, dont une trame incomplète
+        let mut data = std::fs::read(&path).unwrap();
+        data.extend(std::iter::repeat_n(0u8, 4 * 100 + 3));
+        std::fs::write(&path, &data).unwrap();
+        assert!(repair_wav(&path).unwrap());
+        assert_eq!(hound::WavReader::open(&path).unwrap().duration(), 9700);
+
+        // Réparation automatique à l'ouverture du dossier
+        let mut data = std::fs::read(&path).unwrap();
+        data.extend([0u8; 8]);
+        std::fs::write(&path, &data).unwrap();
+        let _recorder = WavRecorder::new(dir.path(), None).unwrap();
+        assert_eq!(hound::WavReader::open(&path).unwrap().duration(), 9702);
+
+        let bogus = dir.path().join("bogus.wav");
+        std::fs::write(&bogus, b"not a wav file").unwrap();
+        assert!(repair_wav(&bogus).is_err());
+    }
+
+    #[test]
+    fn test_wav_repair_leaves_foreign_files_alone() {
+        assert!(is_recording_name("mic_20260101_120000.wav"));
+        assert!(is_recording_name("track7_20260101_120000_2.wav"));
+        for name in ["song.wav", "mic_2026_120000.wav", "mic_20260101_120000.WAV", "voice_20260101_120000.wav"] {
+            assert!(!is_recording_name(name), "{}", name);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let spec = hound::WavSpec { channels: 2, sample_rate: 48000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let write_wav = |path: &std::path::Path| {
+            let mut writer = hound::WavWriter::create(path, spec).unwrap();
+            for i in 0..960 {
+                writer.write_sample((i % 100) as i16).unwrap();
+            }
+            writer.finalize().unwrap();
+        };
+
+        // WAV tiers avec un chunk LIST après `data` : jamais modifié, même nommé comme un enregistrement
+        let tagged = dir.path().join("mixed_20260101_120000.wav");
+        write_wav(&tagged);
+        let mut bytes = std::fs::read(&tagged).unwrap();
+        bytes.extend_from_slice(b"LIST");
+        bytes.extend_from_slice(&12u32.to_le_bytes());
+        bytes.extend_from_slice(b"INFOISFT\0\0\0\0");
+        let riff = (bytes.len() - 8) as u32;
+        bytes[4..8].copy_from_slice(&riff.to_le_bytes());
+        std::fs::write(&tagged, &bytes).unwrap();
+        assert!(!repair_wav(&tagged).unwrap());
+
+        // Fichier au nom étranger, en-tête incohérent : ignoré au démarrage
+        let foreign = dir.path().join("interview.wav");
+        write_wav(&foreign);
+        let mut stale = std::fs::read(&foreign).unwrap();
+        stale.extend([7u8; 40]);
+        std::fs::write(&foreign, &stale).unwrap();
+
+        let _recorder = WavRecorder::new(dir.path(), None).unwrap();
+        assert_eq!(std::fs::read(&tagged).unwrap(), bytes);
+        assert_eq!(std::fs::read(&foreign).unwrap(), stale);
+        assert_eq!(hound::WavReader::open(&tagged).unwrap().duration(), 480);
+    }
+
+    /// Signal de test : deux sinus et un peu de bruit déterministe, stéréo
+    fn music_frame(frames: usize) -> MixedFrame {
+        let mut noise = 12345u32;
+        let samples = (0..frames)
+            .flat_map(|i| {
+                noise = noise.wrapping_mul(1103515245).wrapping_add(12345);
+                let n = ((noise >> 16) as f32 / 65536.0 - 0.5) * 0.01;
+                let t = i as f32 / 48000.0;
+                let l = 0.4 * (t * 440.0 * std::f32::consts::TAU).sin() + n;
+                let r = 0.3 * (t * 660.0 * std::f32::consts::TAU).sin() - n;
+                [l, r]
+            })
+            .collect();
+        MixedFrame { track_id: TRACK_MIC, sample_rate: 48000, channels: 2, pts: 0, samples }
+    }
+
+    #[test]
+    fn test_audio_codecs_roundtrip() {
+        let frame = music_frame(4800);
+        let pcm16 = |s: f32| (s.clamp(-1.0, 1.0) * 32767.0) as i16;
+        let gzip = AudioEncoder::new(AudioCodec::Gzip, 128).encode_packet(&frame).unwrap();
+
+        // Sans perte : PCM16 restitué à l'identique et plus compact que le GZIP
+        let lossless = AudioEncoder::new(AudioCodec::Lossless, 128).encode_packet(&frame).unwrap();
+        let decoded = AudioEncoder::decode_packet(&lossless).unwrap();
+        assert_eq!((decoded.track_id, decoded.sample_rate, decoded.channels), (TRACK_MIC, 48000, 2));
+        assert!(decoded.samples.iter().zip(&frame.samples).all(|(d, s)| (d * 32767.0).round() as i16 == pcm16(*s)));
+        assert!(lossless.len() < gzip.len() * 3 / 4, "lossless {} vs gzip {}", lossless.len(), gzip.len());
+
+        // ADPCM : 4 bits par échantillon, erreur faible
+        let adpcm = AudioEncoder::new(AudioCodec::Adpcm, 128).encode_packet(&frame).unwrap();
+        let decoded = AudioEncoder::decode_packet(&adpcm).unwrap();
+        assert_eq!(adpcm[8], 4);
+        assert!(adpcm.len() < frame.samples.len() / 2 + 200);
+        let max_err = decoded.samples.iter().zip(&frame.samples).map(|(d, s)| (d - s).abs()).fold(0.0, f32::max);
+        assert!(max_err < 0.05, "adpcm error {}", max_err);
+
+        assert!(AudioEncoder::decode_packet(&adpcm[..adpcm.len() / 2]).is_err());
+        assert!(AudioEncoder::decode_packet(&lossless[..10]).is_err());
+    }
+
+    #[test]
+    fn test_audio_packet_rejects_corrupt_headers() {
+        let frame = music_frame(480);
+        let silence = AudioEncoder::new(AudioCodec::Lossless, 128).encode_silence(&frame, 0.01);
+        let patched = |packet: &[u8], at: usize, bytes: &[u8]| {
+            let mut packet = packet.to_vec();
+            packet[at..at + bytes.len()].copy_from_slice(bytes);
+            packet
+        };
+
+        // Fréquence, canaux et nombre de frames hors de toute réalité : refusés sans allocation
+        let huge = patched(&patched(&silence, 3, &u32::MAX.to_le_bytes()), 13, &u32::MAX.to_le_bytes());
+        assert!(AudioEncoder::decode_packet(&patched(&huge, 2, &[255])).is_err());
+        assert!(AudioEncoder::decode_packet(&patched(&silence, 3, &1000u32.to_le_bytes())).is_err());
+        assert!(AudioEncoder::decode_packet(&patched(&silence, 2, &[9])).is_err());
+        assert!(AudioEncoder::decode_packet(&patched(&silence, 13, &(48000u32 * 61).to_le_bytes())).is_err());
+        assert_eq!(AudioEncoder::decode_packet(&silence).unwrap().samples.len(), 960);
+
+        // Corps trop court pour le nombre de frames annoncé
+        for codec in [AudioCodec::Gzip, AudioCodec::Lossless, AudioCodec::Adpcm] {
+            let packet = AudioEncoder::new(codec, 128).encode_packet(&frame).unwrap();
+            assert!(AudioEncoder::decode_packet(&packet).is_ok());
+            let inflated = patched(&packet, 13, &(48000u32 * 59).to_le_bytes());
+            assert!(AudioEncoder::decode_packet(&inflated).is_err(), "{:?}", codec);
+        }
+    }
+
+    #[test]
+    fn test_lossy_audio_codec_follows_bitrate() {
+        let frame = music_frame(48000); // 1 s
+        let size = |kbps| AudioEncoder::new(AudioCodec::Lossy, kbps).encode_packet(&frame).unwrap();
+        let error = |packet: &[u8]| {
+            let decoded = AudioEncoder::decode_packet(packet).unwrap();
+            let err: f32 = decoded.samples.iter().zip(&frame.samples).map(|(d, s)| (d - s).powi(2)).sum();
+            (err / frame.samples.len() as f32).sqrt()
+        };
+
+        let (low, high) = (size(96), size(256));
+        // Débit tenu à ~5 % près (en-tête et arrondis des blocs compris)
+        assert!(low.len() * 8 <= 96_000 * 105 / 100, "96 kbps -> {} bits", low.len() * 8);
+        assert!(high.len() * 8 <= 256_000 * 105 / 100, "256 kbps -> {} bits", high.len() * 8);
+        assert!(low.len() < high.len());
+        assert!(error(&high) < error(&low));
+        assert!(error(&high) < 0.01);
+        assert_eq!(&high[11..13], &256u16.to_le_bytes());
+    }
+
+    #[test]
+    fn test_voice_detector_energy_zcr_and_hangover() {
+        // Frames mono de 10 ms à 48 kHz
+        let frame = |f: &dyn Fn(usize) -> f32| MixedFrame {
+            track_id: TRACK_MIC,
+            sample_rate: 48000,
+            channels: 1,
+            pts: 0,
+            samples: (0..480).map(f).collect(),
+        };
+        let voiced = frame(&|i| 0.1 * (i as f32 / 48000.0 * 200.0 * std::f32::consts::TAU).sin());
+        let hiss = frame(&|i| if i % 2 == 0 { 0.004 } else { -0.004 }); // -48 dBFS, ZCR élevé
+        let hum = frame(&|i| 0.004 * (i as f32 / 48000.0 * 50.0 * std::f32::consts::TAU).sin()); // -51 dBFS, ZCR faible
+        let silence = frame(&|_| 0.0);
+
+        let mut vad = VoiceDetector::new(VadSettings { threshold_db: -40.0, hangover_ms: 30 });
+        assert!(!vad.process(&silence).speech);
+        let onset = vad.process(&voiced);
+        assert!(onset.speech && onset.onset);
+        assert!(!vad.process(&voiced).onset);
+
+        // Maintien de 30 ms : deux frames de silence encore transmises, puis coupure
+        assert!(vad.process(&silence).speech);
+        assert!(vad.process(&silence).speech);
+        assert!(!vad.process(&silence).speech);
+        assert!(!vad.in_speech());
+
+        // Sous le seuil : une consonne sourde passe, un ronflement grave non
+        assert!(vad.process(&hiss).speech);
+        let mut vad = VoiceDetector::new(VadSettings { threshold_db: -40.0, hangover_ms: 0 });
+        let hum_decision = vad.process(&hum);
+        assert!(!hum_decision.speech);
+        assert!((hum_decision.rms - 0.004 / 2f32.sqrt()).abs() < 2e-4);
+    }
+
+    #[test]
+    fn test_silence_markers_keep_timeline_continuous() {
+        let mut file = Config::default().file;
+        file.audio_compression = "lossless".to_string();
+        file.audio_vad_enabled = true;
+        file.audio_vad_threshold_db = -40.0;
+        file.audio_vad_hangover_ms = 0;
+        let preprocessor = visualisation_module::Preprocessor::with_config(
+            Arc::new(ScreenCapture::with_config(&file)),
+            Arc::new(AudioCapture::with_config(&file)),
+            Arc::new(visualisation_module::InputCapture::with_config(&file)),
+            &file,
+        );
+
+        // Parole, silence avec bruit de fond, parole : 3 frames stéréo de 1024
+        let speech = music_frame(1024);
+        let mut noise = speech.clone();
+        noise.samples.iter_mut().enumerate().for_each(|(i, s)| *s = if i % 3 == 0 { 0.002 } else { -0.001 });
+        let packets: Vec<Vec<u8>> = [&speech, &noise, &speech].iter().map(|f| preprocessor.process_audio((*f).clone())).collect();
+
+        assert!(!AudioEncoder::is_silence(&packets[0]));
+        assert!(AudioEncoder::is_silence(&packets[1]));
+        assert!(packets[1].len() <= 29, "marqueur de {} octets", packets[1].len());
+
+        // Côté pool : chaque paquet restitue sa durée, le silence en bruit de confort au même niveau
+        let decoded: Vec<MixedFrame> = packets
+            .iter()
+            .map(|p| visualisation_module::Preprocessor::decode_audio(p).expect("paquet audio invalide"))
+            .collect();
+        assert!(decoded.iter().all(|f| f.samples.len() == 2048 && f.channels == 2 && f.sample_rate == 48000));
+        let rms = |f: &MixedFrame| (f.samples.iter().map(|s| s * s).sum::<f32>() / f.samples.len() as f32).sqrt();
+        assert!((rms(&decoded[1]) - rms(&noise)).abs() < rms(&noise) * 0.2);
+        assert_eq!(decoded[2].samples.len(), speech.samples.len());
+    }
+
+    #[test]
+    fn test_audio_encoder_from_config() {
+        let mut file = Config::default().file;
+        assert_eq!(AudioEncoder::from_config(&file).codec(), AudioCodec::Gzip);
+        file.audio_compression = "flac".to_string();
+        assert_eq!(AudioEncoder::from_config(&file).codec(), AudioCodec::Lossless);
+        file.audio_compression = "ima-adpcm".to_string();
+        assert_eq!(AudioEncoder::from_config(&file).codec(), AudioCodec::Adpcm);
+        file.audio_compression = "lossy".to_string();
+        file.audio_bitrate = 64;
+        let encoder = AudioEncoder::from_config(&file);
+        assert_eq!((encoder.codec(), encoder.bitrate()), (AudioCodec::Lossy, 64));
+    }
+}
@@ -5,7 +5,7 @@ use std::time::{Duration, Instant};
 use std::thread;
 
 use rdev::{listen, Event, EventType};
-use visualisation_module::capture::Ping;
+use visualisation_module::Ping;
 
 /// Test réel clavier / souris (OS-level hook)
 /// - Aucun input synthétique
@@ -73,3 +73,476 @@ pub fn test_input_5min_idle(ping: Arc<Ping>) {
         "Aucun événement input réel capturé pendant 5 minutes"
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use visualisation_module::{AudioCapture, Config, ScreenCapture};
+    use visualisation_module::capture::{
+        AudioFormat, MockAudioBackend, Waveform, button_name, key_from_name, key_name, InputEncoder, InputEvent, InputEventType,
+        InputFormat, MoveCoalescer, HotkeyAction, HotkeyMatcher, RecordingInjector, ReplayEngine, ScreenRemap, canonical_key_name,
+        enigo_key, KeyChord, pts_now,
+    };
+
+    fn input_events() -> Vec<InputEvent> {
+        let event = |event_type, i: u64| InputEvent { event_type, timestamp: 1_700_000_000_000 + i as u128, pts: 1_000 * i };
+        vec![
+            event(InputEventType::KeyPress { key: "ctrl_left".to_string() }, 1),
+            event(InputEventType::KeyRelease { key: "unknown_191".to_string() }, 2),
+            event(InputEventType::MouseMove { x: -1920, y: 1080 }, 3),
+            event(InputEventType::MouseClick { button: "left".to_string(), x: 10, y: 20 }, 4),
+            event(InputEventType::MouseRelease { button: "button_8".to_string(), x: 10, y: 20 }, 5),
+            event(InputEventType::Scroll { dx: 0, dy: -3 }, 6),
+        ]
+    }
+
+    #[test]
+    fn test_input_event_json_schema() {
+        let json = InputEncoder::new(InputFormat::Json);
+        let events = input_events();
+        assert_eq!(
+            String::from_utf8(json.encode(&events[0]).unwrap()).unwrap(),
+            r#"{"event":{"type":"key_press","key":"ctrl_left"},"timestamp":1700000000001,"pts":1000}"#
+        );
+        assert_eq!(
+            String::from_utf8(json.encode(&events[3]).unwrap()).unwrap(),
+            r#"{"event":{"type":"mouse_click","button":"left","x":10,"y":20},"timestamp":1700000000004,"pts":4000}"#
+        );
+        for event in &events {
+            assert_eq!(&InputEncoder::decode(&json.encode(event).unwrap()).unwrap(), event);
+        }
+
+        // Schéma écrit à la main par un outil externe
+        let scroll = InputEncoder::decode(br#"{"pts":7,"timestamp":9,"event":{"dy":2,"dx":1,"type":"scroll"}}"#).unwrap();
+        assert_eq!(scroll.event_type, InputEventType::Scroll { dx: 1, dy: 2 });
+        assert!(InputEncoder::decode(br#"{"event":{"type":"KeyPress(KeyA)"},"timestamp":0,"pts":0}"#).is_err());
+    }
+
+    #[test]
+    fn test_input_event_binary_roundtrip() {
+        let binary = InputEncoder::new(InputFormat::Binary);
+        let json = InputEncoder::new(InputFormat::Json);
+        for event in input_events() {
+            let packet = binary.encode(&event).unwrap();
+            assert_eq!(packet[0], 0x01);
+            assert!(packet.len() < json.encode(&event).unwrap().len() / 2);
+            assert_eq!(InputEncoder::decode(&packet).unwrap(), event);
+            assert!(InputEncoder::decode(&packet[..packet.len() - 1]).is_err());
+        }
+        let mouse = binary.encode(&input_events()[2]).unwrap();
+        assert_eq!(mouse.len(), 26);
+
+        let long = InputEvent { event_type: InputEventType::KeyPress { key: "k".repeat(300) }, timestamp: 0, pts: 0 };
+        assert!(binary.encode(&long).is_err());
+        assert!(InputEncoder::decode(&[]).is_err());
+        assert!(InputEncoder::decode(&[0x7F, 1, 2]).is_err());
+
+        let mut file = Config::default().file;
+        assert_eq!(InputEncoder::from_config(&file).format(), InputFormat::Json);
+        file.input_serialization = "binary".to_string();
+        assert_eq!(InputEncoder::from_config(&file).format(), InputFormat::Binary);
+    }
+
+    #[test]
+    fn test_stable_key_names() {
+        use rdev::{Button, Key};
+        let keys = [
+            (Key::KeyA, "a"), (Key::Num7, "7"), (Key::F11, "f11"), (Key::ControlLeft, "ctrl_left"),
+            (Key::Return, "enter"), (Key::UpArrow, "up"), (Key::Kp5, "kp_5"), (Key::SemiColon, "semicolon"),
+            (Key::Unknown(191), "unknown_191"),
+        ];
+        for (key, name) in keys {
+            assert_eq!(key_name(key), name);
+            assert_eq!(key_from_name(name), Some(key));
+        }
+        assert_eq!(key_from_name("Shift_Left"), Some(Key::ShiftLeft));
+        assert_eq!(key_from_name("KeyA"), None);
+        assert_eq!(button_name(Button::Middle), "middle");
+        assert_eq!(button_name(Button::Unknown(8)), "button_8");
+    }
+
+    fn replay_events(steps_ms: &[u64]) -> Vec<InputEvent> {
+        steps_ms
+            .iter()
+            .enumerate()
+            .map(|(i, &ms)| InputEvent {
+                event_type: InputEventType::MouseMove { x: i as i32 * 100, y: i as i32 * 50 },
+                timestamp: 1_700_000_000_000 + ms as u128,
+                pts: 5_000_000 + ms * 1_000,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_replay_timing_and_speed() {
+        // Ordre rétabli d'après les PTS
+        let mut events = replay_events(&[0, 80, 160]);
+        events.swap(0, 2);
+        let injector = RecordingInjector::new();
+        let engine = ReplayEngine::new(events).with_speed(2.0);
+        assert_eq!(engine.duration(), Duration::from_millis(80));
+
+        let stats = engine.start(Box::new(injector.clone())).join();
+        assert_eq!(stats.injected, 3);
+        assert_eq!(
+            injector.events(),
+            replay_events(&[0, 80, 160]).into_iter().map(|e| e.event_type).collect::<Vec<_>>()
+        );
+        let t = injector.instants();
+        let (first, second) = (t[1] - t[0], t[2] - t[0]);
+        assert!(first >= Duration::from_millis(38) && first < Duration::from_millis(70), "{:?}", first);
+        assert!(second >= Duration::from_millis(78) && second < Duration::from_millis(130), "{:?}", second);
+    }
+
+    #[test]
+    fn test_replay_pause_step_resume() {
+        let injector = RecordingInjector::new();
+        let handle = ReplayEngine::new(replay_events(&[0, 10_000, 20_000, 20_010])).paused().start(Box::new(injector.clone()));
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(injector.is_empty());
+
+        // Pas à pas : un événement à la fois, sans attendre les 10 s d'écart
+        handle.step();
+        assert!(handle.wait_for(1, Duration::from_secs(1)));
+        handle.step();
+        assert!(handle.wait_for(2, Duration::from_secs(1)));
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(injector.len(), 2);
+
+        // Reprise au rythme d'origine depuis l'événement courant, accélérée
+        handle.set_speed(1000.0);
+        handle.resume();
+        assert!(handle.wait_for(4, Duration::from_secs(2)));
+        assert!(handle.is_finished() || handle.position() == 4);
+        assert_eq!(handle.join().injected, 4);
+
+        // Arrêt d'un rejeu en cours
+        let injector = RecordingInjector::new();
+        let handle = ReplayEngine::new(replay_events(&[0, 60_000])).start(Box::new(injector.clone()));
+        assert!(handle.wait_for(1, Duration::from_secs(1)));
+        handle.stop();
+        assert_eq!(handle.join().injected, 1);
+        assert_eq!(injector.len(), 1);
+    }
+
+    #[test]
+    fn test_replay_remap_and_packets() {
+        let encoder = InputEncoder::new(InputFormat::Binary);
+        let mut events = input_events();
+        events.push(InputEvent {
+            event_type: InputEventType::MouseMove { x: 1920, y: 1080 },
+            timestamp: 1_700_000_000_007,
+            pts: 7_000,
+        });
+        let packets: Vec<Vec<u8>> = events.iter().map(|e| encoder.encode(e).unwrap()).collect();
+
+        let injector = RecordingInjector::new();
+        let engine = ReplayEngine::from_packets(&packets)
+            .unwrap()
+            .with_speed(10.0)
+            .with_remap(ScreenRemap::new((1920, 1080), (960, 540)).with_offset(100, 0));
+        assert_eq!(engine.len(), 7);
+        let stats = engine.start(Box::new(injector.clone())).join();
+        assert_eq!(stats.injected, 7);
+        assert_eq!(stats.skipped, 0);
+
+        let replayed = injector.events();
+        assert_eq!(replayed[2], InputEventType::MouseMove { x: -860, y: 540 });
+        assert_eq!(replayed[3], InputEventType::MouseClick { button: "left".to_string(), x: 105, y: 10 });
+        // Défilement non remappé
+        assert_eq!(replayed[5], InputEventType::Scroll { dx: 0, dy: -3 });
+        assert_eq!(replayed[6], InputEventType::MouseMove { x: 1060, y: 540 });
+
+        assert!(ReplayEngine::from_packets(&[vec![0x7f, 0]]).is_err());
+    }
+
+    #[test]
+    fn test_key_chords_and_full_key_set() {
+        use visualisation_module::error::ModuleError;
+
+        // Synonymes ramenés aux noms stables
+        assert_eq!(canonical_key_name("Ctrl"), Some("ctrl_left"));
+        assert_eq!(canonical_key_name("esc"), Some("escape"));
+        assert_eq!(canonical_key_name("-"), Some("minus"));
+        assert_eq!(canonical_key_name("pgdn"), Some("page_down"));
+        assert_eq!(canonical_key_name("nope"), None);
+
+        for name in [
+            "f1", "f12", "up", "down", "left", "right", "ctrl", "shift_right", "alt", "cmd", "tab", "escape",
+            "backspace", "delete", "home", "end", "page_up", "minus", "slash", "backquote", "a", "Z", "7",
+        ] {
+            assert!(enigo_key(name).is_some(), "{} not injectable", name);
+        }
+        assert_eq!(enigo_key("ctrl_left"), Some(enigo::Key::LControl));
+        assert_eq!(enigo_key("f5"), Some(enigo::Key::F5));
+        assert_eq!(enigo_key("semicolon"), Some(enigo::Key::Layout(';')));
+        #[cfg(target_os = "linux")]
+        for name in ["kp_0", "kp_9", "kp_enter", "kp_plus", "insert", "print_screen", "scroll_lock"] {
+            assert!(enigo_key(name).is_some(), "{} not injectable", name);
+        }
+
+        // Appui dans l'ordre, relâchement dans l'ordre inverse
+        let chord = KeyChord::parse("ctrl+Shift+t").unwrap();
+        assert_eq!(chord.keys(), &["ctrl_left", "shift_left", "t"]);
+        let injector = RecordingInjector::new();
+        let mut sink: Box<dyn visualisation_module::capture::InputInjector> = Box::new(injector.clone());
+        for event in chord.events() {
+            sink.inject(&event).unwrap();
+        }
+        let press = |k: &str| InputEventType::KeyPress { key: k.to_string() };
+        let release = |k: &str| InputEventType::KeyRelease { key: k.to_string() };
+        assert_eq!(
+            injector.events(),
+            vec![press("ctrl_left"), press("shift_left"), press("t"), release("t"), release("shift_left"), release("ctrl_left")]
+        );
+        assert_eq!(KeyChord::parse("f4").unwrap().events(), vec![press("f4"), release("f4")]);
+
+        for bad in ["ctrl+nope", "ctrl+", "+", "ctrl+ctrl", "unknown_191", "fn"] {
+            assert!(matches!(KeyChord::parse(bad), Err(ModuleError::ValidationError(_))), "{} accepted", bad);
+        }
+    }
+
+    fn mouse_move(x: i32, y: i32, pts_ms: u64) -> InputEvent {
+        InputEvent { event_type: InputEventType::MouseMove { x, y }, timestamp: pts_ms as u128, pts: pts_ms * 1_000 }
+    }
+
+    #[test]
+    fn test_mouse_moves_coalesced_per_window() {
+        let mut coalescer = MoveCoalescer::new(Duration::from_millis(10), false);
+        let mut out = Vec::new();
+        // Trajet d'un pixel par ms : une seule position par fenêtre de 10 ms
+        for i in 0..25 {
+            out.extend(coalescer.push(mouse_move(i, 2 * i, i as u64)));
+        }
+        assert_eq!(out, vec![mouse_move(9, 18, 9), mouse_move(19, 38, 19)]);
+        assert!(coalescer.has_pending());
+        assert!(coalescer.flush_expired(29_999).is_empty());
+        assert_eq!(coalescer.flush_expired(30_000), vec![mouse_move(24, 48, 24)]);
+        assert_eq!(coalescer.coalesced(), 22);
+
+        // Clics et touches : jamais regroupés, la fenêtre en cours est vidée avant eux
+        let click = InputEvent {
+            event_type: InputEventType::MouseClick { button: "left".to_string(), x: 3, y: 3 },
+            timestamp: 42,
+            pts: 42_000,
+        };
+        let key = InputEvent { event_type: InputEventType::KeyPress { key: "a".to_string() }, timestamp: 43, pts: 43_000 };
+        let mut out = coalescer.push(mouse_move(1, 1, 40));
+        out.extend(coalescer.push(mouse_move(3, 3, 41)));
+        out.extend(coalescer.push(click.clone()));
+        out.extend(coalescer.push(key.clone()));
+        out.extend(coalescer.push(key.clone()));
+        assert_eq!(out, vec![mouse_move(3, 3, 41), click, key.clone(), key]);
+        assert!(!coalescer.has_pending());
+
+        // Extrêmes du trajet conservés dans l'ordre chronologique
+        let mut coalescer = MoveCoalescer::new(Duration::from_millis(10), true);
+        for (i, (x, y)) in [(50, 50), (10, 60), (30, 90), (80, 40), (60, 55)].into_iter().enumerate() {
+            assert!(coalescer.push(mouse_move(x, y, i as u64)).is_empty());
+        }
+        assert_eq!(
+            coalescer.flush(),
+            vec![mouse_move(10, 60, 1), mouse_move(30, 90, 2), mouse_move(80, 40, 3), mouse_move(60, 55, 4)]
+        );
+
+        // Fréquence nulle : pas de regroupement
+        let mut passthrough = MoveCoalescer::new(Duration::ZERO, false);
+        assert_eq!(passthrough.push(mouse_move(1, 1, 0)), vec![mouse_move(1, 1, 0)]);
+        assert_eq!(passthrough.push(mouse_move(2, 2, 0)), vec![mouse_move(2, 2, 0)]);
+    }
+
+    #[test]
+    fn test_input_capture_honours_sample_rate() {
+        let mut file = Config::default().file;
+        file.input_sample_rate = 100;
+        let input = visualisation_module::InputCapture::with_config(&file);
+        assert_eq!(input.get_current_fps(), 100);
+
+        // Les PTS simulés (0 à 1 s) doivent être échus sur l'horloge de capture
+        while pts_now() < 1_010_000 {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        for i in 0..1000 {
+            input.push_event(mouse_move(i as i32, 0, i));
+            if i % 250 == 0 {
+                input.push_event(InputEvent {
+                    event_type: InputEventType::KeyPress { key: "space".to_string() },
+                    timestamp: i as u128,
+                    pts: i * 1_000,
+                });
+            }
+        }
+        let events: Vec<InputEvent> = std::iter::from_fn(|| input.get_event()).collect();
+        let moves = events.iter().filter(|e| matches!(e.event_type, InputEventType::MouseMove { .. })).count();
+        let keys: Vec<u64> = events
+            .iter()
+            .filter(|e| matches!(e.event_type, InputEventType::KeyPress { .. }))
+            .map(|e| e.pts)
+            .collect();
+        // Au plus un mouvement par fenêtre de 10 ms (+ coupures par les touches)
+        assert!(moves <= 100 + keys.len(), "{} moves", moves);
+        assert_eq!(keys, vec![0, 250_000, 500_000, 750_000]);
+        assert!(events.windows(2).all(|w| w[0].pts <= w[1].pts));
+        assert_eq!(events.last().unwrap().event_type, InputEventType::MouseMove { x: 999, y: 0 });
+        assert_eq!(input.get_coalesced() as usize, 1000 - moves);
+    }
+
+    #[test]
+    fn test_global_hotkeys() {
+        let press = |k: &str| InputEventType::KeyPress { key: k.to_string() };
+        let release = |k: &str| InputEventType::KeyRelease { key: k.to_string() };
+
+        let mut matcher = HotkeyMatcher::new(true);
+        matcher.bind(HotkeyAction::StartStop, "ctrl+shift+r").unwrap();
+        matcher.bind(HotkeyAction::Marker, "f9").unwrap();
+        assert!(matcher.bind(HotkeyAction::PauseResume, "shift+ctrl+r").is_err());
+        assert!(matcher.bind(HotkeyAction::PauseResume, "ctrl+bogus").is_err());
+
+        // Déclenché par la touche qui complète la combinaison, côté droit accepté
+        assert_eq!(matcher.on_event(&press("ctrl_right")).action, None);
+        assert!(!matcher.on_event(&press("shift_left")).suppress);
+        let hit = matcher.on_event(&press("r"));
+        assert_eq!(hit.action, Some(HotkeyAction::StartStop));
+        assert!(hit.suppress);
+        // Répétition automatique : ni redéclenchée ni enregistrée
+        let repeat = matcher.on_event(&press("r"));
+        assert_eq!(repeat.action, None);
+        assert!(repeat.suppress);
+        assert!(matcher.on_event(&release("r")).suppress);
+        assert!(!matcher.on_event(&release("shift_left")).suppress);
+        assert!(!matcher.on_event(&release("ctrl_right")).suppress);
+
+        // Touche en trop : pas de déclenchement ; frappe ordinaire non touchée
+        matcher.on_event(&press("alt"));
+        assert_eq!(matcher.on_event(&press("f9")).action, None);
+        matcher.on_event(&release("f9"));
+        matcher.on_event(&release("alt"));
+        assert_eq!(matcher.on_event(&press("r")), Default::default());
+        matcher.on_event(&release("r"));
+        assert_eq!(matcher.on_event(&press("f9")).action, Some(HotkeyAction::Marker));
+
+        // Bout à bout via la config : événements de contrôle et exclusion du flux
+        let mut file = Config::default().file;
+        file.input_sample_rate = 0;
+        file.input_hotkeys_exclude = true;
+        file.input_hotkeys = vec![
+            visualisation_module::config::HotkeyBinding { action: "pause_resume".to_string(), keys: "ctrl+p".to_string() },
+            visualisation_module::config::HotkeyBinding { action: "explode".to_string(), keys: "f1".to_string() },
+        ];
+        let input = visualisation_module::InputCapture::with_config(&file);
+        for (i, event_type) in [press("ctrl_left"), press("p"), release("p"), release("ctrl_left"), press("f1")].into_iter().enumerate() {
+            input.push_event(InputEvent { event_type, timestamp: 1_000 + i as u128, pts: 10 * i as u64 });
+        }
+        let control = input.get_control_event().unwrap();
+        assert_eq!((control.action, control.pts, control.timestamp), (HotkeyAction::PauseResume, 10, 1_001));
+        assert!(input.get_control_event().is_none());
+        let recorded: Vec<InputEventType> = std::iter::from_fn(|| input.get_event()).map(|e| e.event_type).collect();
+        assert_eq!(recorded, vec![press("ctrl_left"), release("ctrl_left"), press("f1")]);
+
+        // Sans exclusion, le flux reste complet
+        file.input_hotkeys_exclude = false;
+        let input = visualisation_module::InputCapture::with_config(&file);
+        for event_type in [press("ctrl_left"), press("p"), release("p")] {
+            input.push_event(InputEvent { event_type, timestamp: 0, pts: 0 });
+        }
+        assert!(input.get_control_event().is_some());
+        assert_eq!(std::iter::from_fn(|| input.get_event()).count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_pause_keeps_sources_open() {
+        let mut file = Config::default().file;
+        file.ram_gb = 4;
+        file.input_sample_rate = 0;
+        file.input_hotkeys = vec![visualisation_module::config::HotkeyBinding {
+            action: "pause_resume".to_string(),
+            keys: "f8".to_string(),
+        }];
+
+        // Entrées : plus rien n'est enregistré, mais les raccourcis restent actifs
+        let input = visualisation_module::InputCapture::with_config(&file);
+        let key = |k: &str, pts: u64| InputEvent { event_type: InputEventType::KeyPress { key: k.to_string() }, timestamp: 0, pts };
+        input.pause();
+        assert!(input.is_paused());
+        input.push_event(key("a", 1));
+        input.push_event(InputEvent { event_type: InputEventType::KeyRelease { key: "a".to_string() }, timestamp: 0, pts: 1 });
+        input.push_event(key("f8", 2));
+        assert_eq!(input.get_control_event().map(|e| e.action), Some(HotkeyAction::PauseResume));
+        assert!(input.get_event().is_none());
+        input.resume();
+        input.push_event(key("b", 3));
+        assert_eq!(input.get_event().map(|e| e.pts), Some(3));
+
+        // Audio : flux toujours ouvert, frames jetées pendant la pause
+        let backend = MockAudioBackend::new(AudioFormat { sample_rate: 48000, channels: 2 }, Waveform::Sine { frequency: 440.0, amplitude: 0.5 });
+        let mut audio = AudioCapture::with_config(&file);
+        audio.set_backend(Arc::new(backend));
+        audio.start().await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        audio.pause();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        audio.clear_buffer();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(audio.get_frame().is_none());
+        audio.resume();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(audio.get_frame().is_some());
+        audio.stop().await;
+
+        // Écran : pause sans arrêt du thread
+        file.screen_backend = "synthetic".to_string();
+        let screen = ScreenCapture::with_config(&file);
+        screen.start().await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        screen.pause();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        while screen.get_frame().is_some() {}
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(screen.get_frame().is_none());
+        screen.resume();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(screen.get_frame().is_some());
+        screen.stop().await;
+    }
+
+    #[test]
+    fn test_control_events_reach_input_stream() {
+        let mut file = Config::default().file;
+        file.input_sample_rate = 0;
+        file.input_hotkeys = vec![
+            visualisation_module::config::HotkeyBinding { action: "marker".to_string(), keys: "f9".to_string() },
+            visualisation_module::config::HotkeyBinding { action: "save_replay".to_string(), keys: "f10".to_string() },
+        ];
+        assert_eq!(HotkeyAction::from_name("save_replay_buffer"), Some(HotkeyAction::SaveReplay));
+        assert_eq!(HotkeyAction::SaveReplay.name(), "save_replay");
+
+        // Arrêtée (entrée en pause) : les repères passent quand même dans le flux
+        let input = visualisation_module::InputCapture::with_config(&file);
+        let key = |k: &str, pts: u64| InputEvent { event_type: InputEventType::KeyPress { key: k.to_string() }, timestamp: 0, pts };
+        input.pause();
+        input.push_event(key("f9", 5));
+        input.push_event(InputEvent { event_type: InputEventType::KeyRelease { key: "f9".to_string() }, timestamp: 0, pts: 6 });
+        input.push_event(key("f10", 7));
+        let actions: Vec<HotkeyAction> = std::iter::from_fn(|| input.get_control_event())
+            .map(|event| {
+                input.push_control(&event);
+                event.action
+            })
+            .collect();
+        assert_eq!(actions, vec![HotkeyAction::Marker, HotkeyAction::SaveReplay]);
+        let marker = input.get_event().expect("repère absent du flux");
+        assert_eq!(marker.event_type, InputEventType::Control { action: "marker".to_string() });
+        assert_eq!(marker.pts, 5);
+        let save = input.get_event().expect("demande de sauvegarde absente du flux");
+        assert_eq!((save.event_type, save.pts), (InputEventType::Control { action: "save_replay".to_string() }, 7));
+        assert!(input.get_event().is_none());
+
+        // Sérialisé dans les deux formats, jamais rejoué
+        for format in [InputFormat::Json, InputFormat::Binary] {
+            let packet = InputEncoder::new(format).encode(&marker).unwrap();
+            assert_eq!(InputEncoder::decode(&packet).unwrap(), marker);
+        }
+        assert_eq!(ReplayEngine::new(vec![marker, key("a", 8)]).len(), 1);
+    }
+}
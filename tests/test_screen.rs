@@ -4,7 +4,7 @@ use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
-use visualisation_module::capture::{ScreenCapture, Ping, Transmitter};
+use visualisation_module::{Ping, ScreenCapture, Transmitter};
 
 /// Test écran réel OS-level pendant 5 minutes
 /// - Idle strict (aucun pong)
@@ -39,7 +39,7 @@ pub fn test_screen_idle_5min(
             let fps = 1.0 / delta.as_secs_f32();
 
             // Pousser vers le transmitter réel
-            transmitter.push_screen(frame);
+            transmitter.push_screen(frame.data);
 
             // Optionnel : ajustement dynamique pour pas saturer CPU/GPU
             if fps > 60.0 {
@@ -56,3 +56,476 @@ pub fn test_screen_idle_5min(
     // Vérifications strictes
     assert!(frame_count > 1000, "Frames écran insuffisantes pendant 5 minutes");
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use visualisation_module::{Config, Metrics, ModuleType, ScreenCapture};
+    use visualisation_module::capture::{
+        AdaptiveFps, CanvasLayout, Compositor, COMPOSITE_DISPLAY, FpsInputs, DeltaDecoder, DeltaEncoder, FrameSource, PixelFormat,
+        ScreenBackend, ScreenCodec, ScreenEncoder, MaskStyle, PrivacyMask, Rect, ScreenFrame, SyntheticSource,
+    };
+
+    fn synthetic_config(width: usize, height: usize) -> visualisation_module::config::ConfigFile {
+        let mut file = Config::default().file;
+        file.screen_backend = "synthetic".to_string();
+        file.screen_synthetic_width = width;
+        file.screen_synthetic_height = height;
+        file
+    }
+
+    /// Frame BGRA de l'écran 0 : mire synthétique, ou couleur unie si `fill` est donné
+    fn test_frame(width: u32, height: u32, fill: Option<[u8; 4]>) -> ScreenFrame {
+        let frame = SyntheticSource::new(0, width as usize, height as usize).next_frame().unwrap().unwrap();
+        match fill {
+            Some(bgra) => ScreenFrame { data: bgra.repeat((width * height) as usize), ..frame },
+            None => frame,
+        }
+    }
+
+    #[test]
+    fn test_synthetic_source_pattern() {
+        let mut source = SyntheticSource::new(0, 320, 240);
+        let first = source.next_frame().unwrap().unwrap();
+        let second = source.next_frame().unwrap().unwrap();
+
+        assert_eq!(first.data.len(), 320 * 240 * 4);
+        assert_eq!((first.width, first.height, first.stride), (320, 240, 320 * 4));
+        assert_eq!(first.format, PixelFormat::Bgra8);
+        assert_eq!(source.width(), 320);
+        assert_eq!(source.height(), 240);
+        // Le carré mobile et le compteur changent d'une frame à l'autre
+        assert_ne!(first.data, second.data);
+    }
+
+    #[test]
+    fn test_screen_backend_from_config() {
+        let file = synthetic_config(640, 360);
+        assert_eq!(
+            ScreenBackend::from_config(&file),
+            ScreenBackend::Synthetic { width: 640, height: 360, displays: 1 }
+        );
+        assert_eq!(ScreenBackend::from_config(&Config::default().file), ScreenBackend::Scrap);
+    }
+
+    #[tokio::test]
+    async fn test_screen_capture_synthetic_loop() {
+        let metrics = Metrics::new();
+        let mut file = synthetic_config(160, 90);
+        file.cpu_cores = 2; // départ à 30 fps
+        let mut screen = ScreenCapture::with_config(&file);
+        screen.attach_metrics(Arc::clone(&metrics));
+
+        screen.start().await;
+        tokio::time::sleep(Duration::from_millis(1200)).await;
+        screen.stop().await;
+
+        let frames: Vec<ScreenFrame> = std::iter::from_fn(|| screen.get_frame()).collect();
+        let frame = frames.first().expect("aucune frame synthétique");
+        assert_eq!(frame.data.len(), 160 * 90 * 4);
+        assert_eq!((frame.width, frame.height), (160, 90));
+        assert!(frame.timestamp > 0);
+        assert!(frames.len() >= 2);
+        assert!(screen.get_frames_captured() as usize >= frames.len());
+        assert!(metrics.avg_fps(ModuleType::Screen) > 0);
+        // Frames successives de la mire : PTS croissants, numéro incrusté différent à chaque frame
+        assert!(frames.windows(2).all(|w| w[0].pts < w[1].pts && w[0].data != w[1].data));
+    }
+
+    #[test]
+    fn test_screen_frame_crop() {
+        let frame = test_frame(64, 48, None);
+        let cropped = frame.crop(&Rect::new(10, 5, 20, 8)).unwrap();
+
+        assert_eq!((cropped.width, cropped.height, cropped.stride), (20, 8, 80));
+        assert_eq!(cropped.data.len(), 20 * 8 * 4);
+        assert_eq!(&cropped.data[..80], &frame.data[(5 * 64 + 10) * 4..(5 * 64 + 30) * 4]);
+
+        // Rectangle débordant : borné à l'écran ; hors écran : rien
+        let clipped = frame.crop(&Rect::new(60, 40, 100, 100)).unwrap();
+        assert_eq!((clipped.width, clipped.height), (4, 8));
+        assert!(frame.crop(&Rect::new(64, 0, 10, 10)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_screen_capture_display_selection_and_region() {
+        let mut file = synthetic_config(160, 90);
+        file.screen_synthetic_displays = 3;
+        file.screen_displays = vec![0, 2];
+        file.screen_regions = vec![visualisation_module::config::ScreenRegion {
+            display: 2, x: 40, y: 10, width: 64, height: 32,
+        }];
+        let screen = ScreenCapture::with_config(&file);
+        assert!(screen.selection().includes(2));
+        assert!(!screen.selection().includes(1));
+
+        screen.start().await;
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        screen.stop().await;
+
+        let mut seen = Vec::new();
+        while let Some(frame) = screen.get_frame() {
+            match frame.display_index {
+                0 => assert_eq!((frame.width, frame.height), (160, 90)),
+                2 => {
+                    assert_eq!((frame.width, frame.height), (64, 32));
+                    assert_eq!(frame.data.len(), 64 * 32 * 4);
+                }
+                other => panic!("écran {} non sélectionné", other),
+            }
+            seen.push(frame.display_index);
+        }
+        assert!(seen.contains(&0) && seen.contains(&2));
+
+        // Changement à chaud via l'API
+        screen.set_displays(vec![1]);
+        screen.set_region(2, None);
+        assert_eq!(screen.selection().displays, vec![1]);
+        assert!(screen.selection().regions.is_empty());
+    }
+
+    fn canvas_pixel(frame: &ScreenFrame, x: u32, y: u32) -> &[u8] {
+        let i = (y * frame.stride + x * 4) as usize;
+        &frame.data[i..i + 4]
+    }
+
+    #[test]
+    fn test_compositor_layouts() {
+        // Écran 1 à gauche du principal, décalé vers le bas
+        let display = |index: u16, width, height, bgra| ScreenFrame {
+            display_index: index,
+            timestamp: 1_000 + index as u64,
+            pts: 2_000 + index as u64,
+            ..test_frame(width, height, Some(bgra))
+        };
+        let mut desktop = Compositor::new(CanvasLayout::Position, 1.0);
+        desktop.set_origin(0, 0, 0);
+        desktop.set_origin(1, -40, 10);
+        desktop.push(display(0, 64, 32, [255, 0, 0, 255]), None);
+        desktop.push(display(1, 40, 30, [0, 255, 0, 255]), None);
+
+        let canvas = desktop.compose().unwrap();
+        assert_eq!(canvas.display_index, COMPOSITE_DISPLAY);
+        assert_eq!((canvas.width, canvas.height), (104, 40));
+        assert_eq!((canvas.timestamp, canvas.pts), (1_001, 2_001));
+        assert_eq!(canvas_pixel(&canvas, 0, 10), &[0, 255, 0, 255]);
+        assert_eq!(canvas_pixel(&canvas, 0, 0), &[0, 0, 0, 255]);
+        assert_eq!(canvas_pixel(&canvas, 40, 0), &[255, 0, 0, 255]);
+
+        // Empilement vertical à l'échelle 0.5
+        let mut stacked = Compositor::new(CanvasLayout::Vertical, 0.5);
+        stacked.push(display(0, 64, 32, [255, 0, 0, 255]), None);
+        stacked.push(display(1, 40, 30, [0, 255, 0, 255]), None);
+        let canvas = stacked.compose().unwrap();
+        assert_eq!((canvas.width, canvas.height), (32, 31));
+        assert_eq!(canvas_pixel(&canvas, 0, 20), &[0, 255, 0, 255]);
+
+        stacked.retain(|display| display == 0);
+        assert_eq!(stacked.compose().unwrap().height, 16);
+    }
+
+    #[tokio::test]
+    async fn test_screen_capture_composite_canvas() {
+        let mut file = synthetic_config(160, 90);
+        file.screen_synthetic_displays = 2;
+        file.screen_composite = true;
+        let screen = ScreenCapture::with_config(&file);
+        assert!(screen.is_compositing());
+
+        screen.start().await;
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        screen.stop().await;
+
+        let frame = screen.get_frame().expect("aucun canevas");
+        // Écrans synthétiques côte à côte sur le bureau
+        assert_eq!(frame.display_index, COMPOSITE_DISPLAY);
+        assert_eq!((frame.width, frame.height), (320, 90));
+        assert_eq!(frame.data.len(), 320 * 90 * 4);
+    }
+
+    #[test]
+    fn test_privacy_masks_fill_and_pixelate() {
+        let mut frame = test_frame(64, 48, None);
+        let original = frame.clone();
+        let masks = vec![
+            PrivacyMask { display: 0, rect: Rect::new(0, 0, 16, 8), style: MaskStyle::Fill { color: [255, 0, 0] } },
+            PrivacyMask::pixelate(0, Rect::new(32, 16, 32, 32), 8),
+            // Autre écran : sans effet
+            PrivacyMask::fill(1, Rect::new(0, 0, 64, 48)),
+        ];
+        assert!(visualisation_module::capture::mask::apply_masks(&mut frame, &masks));
+        assert!(frame.masked);
+
+        // Remplissage rouge écrit en BGRA
+        assert_eq!(canvas_pixel(&frame, 15, 7), &[0, 0, 255, 255]);
+        assert_eq!(canvas_pixel(&frame, 16, 8), canvas_pixel(&original, 16, 8));
+        // Un bloc pixelisé est uniforme
+        assert_eq!(canvas_pixel(&frame, 32, 16), canvas_pixel(&frame, 39, 23));
+
+        // Le drapeau survit à l'encodage
+        let packet = ScreenEncoder::new(ScreenCodec::Png, 85).encode_packet(&frame).unwrap();
+        assert!(ScreenEncoder::decode_packet(&packet).unwrap().masked);
+
+        let mut other = test_frame(64, 48, None);
+        assert!(!visualisation_module::capture::mask::apply_masks(&mut other, &masks[2..]));
+        assert!(!other.masked);
+    }
+
+    #[tokio::test]
+    async fn test_screen_capture_masks_at_runtime() {
+        let mut file = synthetic_config(160, 90);
+        file.screen_masks = vec![visualisation_module::config::ScreenMask {
+            display: 0, x: 0, y: 0, width: 20, height: 20,
+            style: "fill".to_string(), color: [0, 0, 0], block: 16,
+        }];
+        let screen = ScreenCapture::with_config(&file);
+        assert_eq!(screen.masks().len(), 1);
+
+        screen.add_mask(PrivacyMask::fill(0, Rect::new(0, 0, 160, 90)));
+        screen.start().await;
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        screen.stop().await;
+
+        let frame = screen.get_frame().expect("aucune frame");
+        assert!(frame.masked);
+        assert!(frame.data.chunks_exact(4).all(|p| p == [0, 0, 0, 255]));
+
+        screen.clear_masks(Some(0));
+        assert!(screen.masks().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_screen_buffer_bounded_with_drop_metrics() {
+        let metrics = Metrics::new();
+        let mut file = synthetic_config(64, 48);
+        file.screen_buffer_capacity = 3;
+        file.screen_drop_policy = "oldest".to_string();
+        let mut screen = ScreenCapture::with_config(&file);
+        screen.attach_metrics(Arc::clone(&metrics));
+        assert_eq!(screen.buffer_capacity(), 3);
+
+        screen.start().await;
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        screen.stop().await;
+
+        assert!(screen.buffer_len() <= 3);
+        assert!(screen.get_dropped() > 0);
+        assert_eq!(metrics.get_dropped(ModuleType::Screen), screen.get_dropped());
+        assert_eq!(metrics.get_summary().dropped_screen, screen.get_dropped());
+
+        // Capacité dérivée de la RAM quand elle n'est pas fixée
+        file.screen_buffer_capacity = 0;
+        file.ram_gb = 16;
+        assert_eq!(ScreenCapture::with_config(&file).buffer_capacity(), 64);
+    }
+
+    #[test]
+    fn test_screen_packet_roundtrip_with_padding() {
+        // 3x2 BGRA avec 4 octets de padding par ligne
+        let mut data = Vec::new();
+        for row in 0..2u8 {
+            data.extend((0..12).map(|i| row * 16 + i));
+            data.extend([0xFF; 4]);
+        }
+        let frame = ScreenFrame {
+            data,
+            width: 3,
+            height: 2,
+            stride: 16,
+            format: PixelFormat::Bgra8,
+            display_index: 2,
+            timestamp: 1_700_000_000_123,
+            pts: 42_000_001,
+            masked: false,
+        };
+
+        let packet = ScreenEncoder::new(ScreenCodec::Gzip, 100).encode_packet(&frame).unwrap();
+        let decoded = ScreenEncoder::decode_packet(&packet).unwrap();
+
+        assert_eq!((decoded.width, decoded.height, decoded.stride), (3, 2, 12));
+        assert_eq!(decoded.display_index, 2);
+        assert_eq!((decoded.timestamp, decoded.pts), (frame.timestamp, frame.pts));
+        assert_eq!(decoded.data, frame.to_packed());
+        assert!(ScreenEncoder::decode_packet(&packet[..10]).is_err());
+    }
+
+    /// Pixels BGRA attendus après décodage RGB8
+    fn bgra_to_rgb(frame: &ScreenFrame) -> Vec<u8> {
+        frame.data.chunks_exact(4).flat_map(|p| [p[2], p[1], p[0]]).collect()
+    }
+
+    #[test]
+    fn test_lossless_screen_codecs_roundtrip() {
+        let frame = test_frame(64, 48, None);
+        let expected = bgra_to_rgb(&frame);
+
+        for codec in [ScreenCodec::Png, ScreenCodec::WebpLossless, ScreenCodec::Qoi] {
+            let packet = ScreenEncoder::new(codec, 85).encode_packet(&frame).unwrap();
+            let decoded = ScreenEncoder::decode_packet(&packet).unwrap();
+
+            assert_eq!(decoded.format, PixelFormat::Rgb8, "{:?}", codec);
+            assert_eq!((decoded.width, decoded.height, decoded.stride), (64, 48, 64 * 3));
+            assert_eq!(decoded.data, expected, "{:?} doit être sans perte", codec);
+            assert!(packet.len() < frame.data.len(), "{:?} doit compresser", codec);
+        }
+    }
+
+    #[test]
+    fn test_jpeg_quality_controls_size() {
+        let frame = test_frame(128, 96, None);
+        let low = ScreenEncoder::new(ScreenCodec::Jpeg, 10).encode_packet(&frame).unwrap();
+        let high = ScreenEncoder::new(ScreenCodec::Jpeg, 95).encode_packet(&frame).unwrap();
+        assert!(low.len() < high.len());
+
+        let decoded = ScreenEncoder::decode_packet(&high).unwrap();
+        assert_eq!(decoded.data.len(), 128 * 96 * 3);
+    }
+
+    #[test]
+    fn test_screen_encoder_from_config() {
+        let mut file = Config::default().file;
+        file.screen_compression = "JPEG".to_string();
+        file.screen_quality = 150;
+        let encoder = ScreenEncoder::from_config(&file);
+        assert_eq!(encoder.codec(), ScreenCodec::Jpeg);
+        assert_eq!(encoder.quality(), 100);
+
+        file.screen_compression = "unknown".to_string();
+        assert_eq!(ScreenEncoder::from_config(&file).codec(), ScreenCodec::Png);
+    }
+
+    #[test]
+    fn test_delta_sends_only_dirty_tiles() {
+        let codec = ScreenEncoder::new(ScreenCodec::Gzip, 100);
+        let mut encoder = DeltaEncoder::new(16, 100);
+        let mut decoder = DeltaDecoder::new();
+
+        let first = ScreenFrame { timestamp: 1, pts: 1_000, ..test_frame(100, 70, Some([10; 4])) };
+        let key = encoder.encode(&first, &codec).unwrap();
+        assert_eq!(key[0], 0, "première frame = keyframe");
+        assert_eq!(decoder.decode(&key).unwrap().data, first.data);
+
+        // Modifier un pixel dans la tuile de bord (6, 4) (tuile partielle 4x6)
+        let mut second = ScreenFrame { timestamp: 2, pts: 2_000, ..test_frame(100, 70, Some([10; 4])) };
+        let i = ((68 * 100 + 99) * 4) as usize;
+        second.data[i..i + 4].copy_from_slice(&[1, 2, 3, 4]);
+        let delta = encoder.encode(&second, &codec).unwrap();
+        assert_eq!(delta[0], 1);
+        assert_eq!(u32::from_le_bytes(delta[25..29].try_into().unwrap()), 1, "une seule tuile");
+        assert!(delta.len() < key.len());
+
+        let rebuilt = decoder.decode(&delta).unwrap();
+        assert_eq!(rebuilt.data, second.data);
+        assert_eq!((rebuilt.timestamp, rebuilt.pts), (2, 2000));
+
+        // Frame identique : delta vide
+        let third = ScreenFrame { timestamp: 3, ..second.clone() };
+        let empty = encoder.encode(&third, &codec).unwrap();
+        assert_eq!(u32::from_le_bytes(empty[25..29].try_into().unwrap()), 0);
+        assert_eq!(decoder.decode(&empty).unwrap().data, second.data);
+
+        // Masquage porté par l'en-tête, même sans tuile modifiée
+        let masked = ScreenFrame { timestamp: 4, masked: true, ..second.clone() };
+        let packet = encoder.encode(&masked, &codec).unwrap();
+        assert_eq!(u32::from_le_bytes(packet[25..29].try_into().unwrap()), 0);
+        assert!(decoder.decode(&packet).unwrap().masked);
+        let unmasked = encoder.encode(&ScreenFrame { timestamp: 5, ..second.clone() }, &codec).unwrap();
+        assert!(!decoder.decode(&unmasked).unwrap().masked);
+    }
+
+    #[test]
+    fn test_delta_rejects_malformed_packets() {
+        let codec = ScreenEncoder::new(ScreenCodec::Gzip, 100);
+        let mut encoder = DeltaEncoder::new(16, 100);
+        let first = ScreenFrame { timestamp: 1, pts: 1_000, ..test_frame(64, 64, Some([10; 4])) };
+        let key = encoder.encode(&first, &codec).unwrap();
+        let mut second = ScreenFrame { timestamp: 2, pts: 2_000, ..test_frame(64, 64, Some([10; 4])) };
+        second.data[0..4].copy_from_slice(&[1, 2, 3, 4]);
+        let delta = encoder.encode(&second, &codec).unwrap();
+        assert_eq!(u32::from_le_bytes(delta[25..29].try_into().unwrap()), 1);
+
+        let decode = |packet: &[u8]| {
+            let mut decoder = DeltaDecoder::new();
+            decoder.decode(&key).unwrap();
+            decoder.decode(packet)
+        };
+        assert!(decode(&delta).is_ok());
+
+        // Paquet tronqué à n'importe quelle longueur : erreur, jamais de panique
+        for len in 0..delta.len() {
+            assert!(decode(&delta[..len]).is_err(), "truncated at {}", len);
+        }
+
+        // Taille de tuile nulle
+        let mut zero_tile = delta.clone();
+        zero_tile[23..25].copy_from_slice(&0u16.to_le_bytes());
+        assert!(decode(&zero_tile).is_err());
+
+        // Deux tuiles annoncées pour une bande qui n'en contient qu'une
+        let mut too_many = delta[..30].to_vec();
+        too_many[25..29].copy_from_slice(&2u32.to_le_bytes());
+        too_many.extend_from_slice(&[1, 0, 1, 0]);
+        too_many.extend_from_slice(&delta[30..]);
+        assert!(decode(&too_many).is_err());
+
+        // Nombre de tuiles démesuré
+        let mut huge = delta.clone();
+        huge[25..29].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(decode(&huge).is_err());
+    }
+
+    #[test]
+    fn test_delta_keyframes_and_gap_detection() {
+        let codec = ScreenEncoder::new(ScreenCodec::Png, 85);
+        let mut encoder = DeltaEncoder::new(32, 3);
+        let mut source = SyntheticSource::new(0, 320, 240);
+
+        let kinds: Vec<u8> = (0..7)
+            .map(|_| encoder.encode(&source.next_frame().unwrap().unwrap(), &codec).unwrap()[0])
+            .collect();
+        assert_eq!(kinds, vec![0, 1, 1, 0, 1, 1, 0]);
+
+        // Un delta sans keyframe préalable est refusé
+        let mut decoder = DeltaDecoder::new();
+        let delta = encoder.encode(&source.next_frame().unwrap().unwrap(), &codec).unwrap();
+        assert!(decoder.decode(&delta).is_err());
+
+        // Après une keyframe forcée, la reconstruction reprend et reste sans perte
+        encoder.request_keyframe();
+        let frame = source.next_frame().unwrap().unwrap();
+        decoder.decode(&encoder.encode(&frame, &codec).unwrap()).unwrap();
+        let frame = source.next_frame().unwrap().unwrap();
+        let rebuilt = decoder.decode(&encoder.encode(&frame, &codec).unwrap()).unwrap();
+        assert_eq!(rebuilt.data, bgra_to_rgb(&frame));
+    }
+
+    #[test]
+    fn test_adaptive_fps_ramps_and_backs_off() {
+        let mut fps = AdaptiveFps::new(5, 60, 30);
+        let active = FpsInputs { content_changed: true, ..Default::default() };
+        let idle = FpsInputs::default();
+
+        for _ in 0..10 {
+            fps.update(&active);
+        }
+        assert_eq!(fps.current_fps(), 60);
+
+        for _ in 0..40 {
+            fps.update(&idle);
+        }
+        assert_eq!(fps.current_fps(), 5);
+
+        // L'input seul suffit à remonter
+        let typing = FpsInputs { input_active: true, ..Default::default() };
+        assert!(fps.update(&typing) > 5);
+
+        // Surcharge CPU ou file pleine : recul même si le contenu bouge
+        let mut fps = AdaptiveFps::new(5, 60, 60);
+        fps.set_limits(80.0, 100);
+        let busy = FpsInputs { content_changed: true, cpu_percent: 95.0, ..Default::default() };
+        assert_eq!(fps.update(&busy), 30);
+        let queued = FpsInputs { content_changed: true, queue_depth: 500, ..Default::default() };
+        assert_eq!(fps.update(&queued), 15);
+    }
+}